@@ -123,6 +123,7 @@ public partial class RecordingViewModel : ObservableObject, ICaptureDelegate, ID
                 EnableSystemCapture = EnableSystem,
                 MixingStrategy = strategy,
                 ExportRawPcm = ExportRawPcm,
+                LevelBallistics = new MeterBallisticsConfiguration(),
             };
 
             _session.Configure(config);
@@ -249,8 +250,8 @@ public partial class RecordingViewModel : ObservableObject, ICaptureDelegate, ID
     {
         if (_session == null) return;
         var levels = _session.CurrentLevels;
-        MicLevel = Math.Clamp(levels.MicLevel, 0, 1);
-        SystemLevel = Math.Clamp(levels.SystemLevel, 0, 1);
+        MicLevel = Math.Clamp(levels.SmoothedMicLevel, 0, 1);
+        SystemLevel = Math.Clamp(levels.SmoothedSystemLevel, 0, 1);
         PeakMicLevel = Math.Clamp(levels.PeakMicLevel, 0, 1);
         PeakSystemLevel = Math.Clamp(levels.PeakSystemLevel, 0, 1);
         MicLevelText = $"{MicLevel:F4}";
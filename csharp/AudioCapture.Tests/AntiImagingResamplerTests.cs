@@ -0,0 +1,62 @@
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class AntiImagingResamplerTests
+{
+    private const int SourceRate = 16000;
+    private const int TargetRate = 48000;
+
+    [Fact]
+    public void Upsampled16kHzSource_LosesItsImagesAbove8kHz()
+    {
+        // A 3 kHz tone through linear interpolation images at 16 ± 3 kHz: 13 and 19 kHz.
+        var tone = Tone(3000, seconds: 1.2);
+
+        var plain = Steady(new LinearResampler().Process(tone, 1, SourceRate, TargetRate));
+        var filtered = Steady(new AntiImagingResampler(new LinearResampler()).Process(tone, 1, SourceRate, TargetRate));
+
+        foreach (var image in new[] { 13000, 19000 })
+        {
+            Assert.True(LevelDb(plain, image) - LevelDb(plain, 3000) > -40, $"no image at {image} Hz to remove");
+            Assert.True(LevelDb(filtered, image) - LevelDb(filtered, 3000) < -60, $"image at {image} Hz survived");
+        }
+        Assert.Equal(LevelDb(plain, 3000), LevelDb(filtered, 3000), 1.0); // the tone itself is kept
+    }
+
+    [Fact]
+    public void Downsampling_PassesThroughTheInnerResampler()
+    {
+        var input = Tone(3000, seconds: 0.1);
+        var inner = new LinearResampler();
+
+        var output = new AntiImagingResampler(new LinearResampler()).Process(input, 1, TargetRate, SourceRate);
+
+        Assert.Equal(inner.Process(input, 1, TargetRate, SourceRate), output);
+    }
+
+    private static float[] Tone(double frequency, double seconds)
+    {
+        var samples = new float[(int)(SourceRate * seconds)];
+        for (int i = 0; i < samples.Length; i++)
+            samples[i] = 0.5f * (float)Math.Sin(2 * Math.PI * frequency * i / SourceRate);
+        return samples;
+    }
+
+    /// <summary>One second from 0.1 s in, past the filter settling, on whole-hertz bins.</summary>
+    private static float[] Steady(float[] output) => output[(TargetRate / 10)..(TargetRate / 10 + TargetRate)];
+
+    /// <summary>The level of the <paramref name="frequency"/> bin of a one-second window, in dB.</summary>
+    private static double LevelDb(float[] samples, double frequency)
+    {
+        double re = 0, im = 0;
+        for (int i = 0; i < samples.Length; i++)
+        {
+            var phase = 2 * Math.PI * frequency * i / TargetRate;
+            re += samples[i] * Math.Cos(phase);
+            im -= samples[i] * Math.Sin(phase);
+        }
+        return 10 * Math.Log10(re * re + im * im + 1e-30);
+    }
+}
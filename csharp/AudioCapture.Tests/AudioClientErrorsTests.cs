@@ -0,0 +1,27 @@
+using System.Runtime.InteropServices;
+using AudioCapture.Capture;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class AudioClientErrorsTests
+{
+    [Fact]
+    public void UnsupportedFormat_IsRecognizedByHResultAlone()
+    {
+        var refused = new COMException("AUDCLNT_E_UNSUPPORTED_FORMAT", AudioClientErrors.UnsupportedFormat);
+
+        Assert.True(AudioClientErrors.IsUnsupportedFormat(refused));
+        Assert.True(AudioClientErrors.IsUnsupportedFormat(new InvalidOperationException("wrapped", refused)));
+    }
+
+    [Fact]
+    public void UnsupportedFormat_IsNotAnyArgumentException()
+    {
+        // A bad path or a null argument is a bug to surface, not a cue to reopen at the mix format.
+        Assert.False(AudioClientErrors.IsUnsupportedFormat(new ArgumentException("Unsupported Wave Format")));
+        Assert.False(AudioClientErrors.IsUnsupportedFormat(new ArgumentNullException("device")));
+        Assert.False(AudioClientErrors.IsUnsupportedFormat(
+            new COMException("AUDCLNT_E_DEVICE_IN_USE", AudioClientErrors.DeviceInUse)));
+    }
+}
@@ -0,0 +1,75 @@
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class AutomaticGainControlTests
+{
+    private const int SampleRate = 48000;
+
+    private static readonly AgcConfiguration Config = new()
+    {
+        TargetRms = 0.1f,
+        Attack = TimeSpan.FromMilliseconds(50),
+        Release = TimeSpan.FromMilliseconds(500),
+        MaxGain = 10f,
+    };
+
+    [Fact]
+    public void StepChangeInLevel_ConvergesToTheTargetEitherSide()
+    {
+        var agc = new AutomaticGainControl(Config, SampleRate);
+        var quiet = Tone(amplitude: 0.02f, seconds: 3);
+        var loud = Tone(amplitude: 0.4f, seconds: 3);
+
+        // Fed in 10 ms chunks, as the mic callback would.
+        ProcessInChunks(agc, quiet);
+        ProcessInChunks(agc, loud);
+
+        Assert.Equal(Config.TargetRms, Rms(quiet[^(SampleRate / 2)..]), 0.015f);
+        Assert.Equal(Config.TargetRms, Rms(loud[^(SampleRate / 2)..]), 0.015f);
+    }
+
+    [Fact]
+    public void NearSilence_IsNotAmplifiedPastTheCeiling()
+    {
+        var agc = new AutomaticGainControl(Config, SampleRate);
+        var hiss = Tone(amplitude: 0.001f, seconds: 3);
+
+        ProcessInChunks(agc, hiss);
+
+        Assert.InRange(agc.CurrentGain, 1f, Config.MaxGain);
+        Assert.True(Rms(hiss[^(SampleRate / 2)..]) < 0.001f * Config.MaxGain);
+    }
+
+    [Fact]
+    public void RejectsAMaxGainBelowUnity()
+    {
+        Assert.Throws<ArgumentOutOfRangeException>(
+            () => new AutomaticGainControl(Config with { MaxGain = 0.5f }, SampleRate));
+    }
+
+    private static void ProcessInChunks(AutomaticGainControl agc, float[] samples)
+    {
+        const int chunk = SampleRate / 100;
+        for (int i = 0; i < samples.Length; i += chunk)
+            agc.Process(samples.AsSpan(i, Math.Min(chunk, samples.Length - i)));
+    }
+
+    private static float[] Tone(float amplitude, double seconds)
+    {
+        var samples = new float[(int)(SampleRate * seconds)];
+        for (int i = 0; i < samples.Length; i++)
+            samples[i] = amplitude * (float)Math.Sin(2 * Math.PI * 440 * i / SampleRate);
+        return samples;
+    }
+
+    private static float Rms(float[] samples)
+    {
+        double sum = 0;
+        foreach (var s in samples)
+            sum += s * s;
+        return (float)Math.Sqrt(sum / samples.Length);
+    }
+}
@@ -0,0 +1,99 @@
+using System.Security.Cryptography;
+using AudioCapture.Models;
+using AudioCapture.Storage;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class CaptureConfigurationTests
+{
+    [Fact]
+    public void Json_RoundTripsEverySettingButTheEncryptor()
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var saved = new CaptureConfiguration
+        {
+            SampleRate = 44100,
+            Channels = 4,
+            MixingStrategy = MixingStrategy.Multichannel,
+            OutputDirectory = @"C:\Recordings",
+            MaxDuration = TimeSpan.FromHours(2),
+            MicDeviceName = "Yeti",
+            AdditionalMicDeviceIds = ["{0.0.1.00000000}.{a}", "{0.0.1.00000000}.{b}"],
+            MicAgc = new AgcConfiguration { TargetRms = 0.2f },
+            Bwf = new BwfConfiguration { Description = "Session 12" },
+            Tags = new Dictionary<string, string> { ["meeting_id"] = "m-42", ["participants"] = "Ada, Grace" },
+            MemoryBudgetBytes = 64_000_000,
+            Encryptor = encryptor,
+        };
+
+        var json = saved.ToJson();
+        var restored = CaptureConfiguration.FromJson(json, encryptor);
+
+        Assert.DoesNotContain("encryptor", json, StringComparison.OrdinalIgnoreCase);
+        Assert.Contains("\"sample_rate\": 44100", json);
+        Assert.Contains("\"mixing_strategy\": \"multichannel\"", json);
+        Assert.Equivalent(saved, restored, strict: true);
+        Assert.Same(encryptor, restored.Encryptor);
+        Assert.Null(CaptureConfiguration.FromJson(json).Encryptor);
+    }
+
+    [Fact]
+    public void Json_MissingSettingsKeepTheirDefaults()
+    {
+        var restored = CaptureConfiguration.FromJson("""{ "sample_rate": 16000 }""");
+
+        Assert.Equal(16000, restored.SampleRate);
+        Assert.Equal(new CaptureConfiguration().DeviceBufferDuration, restored.DeviceBufferDuration);
+        Assert.True(restored.EnableMicCapture);
+    }
+
+    [Fact]
+    public void Json_KeepsCombinedAndEmptyStreamFlags()
+    {
+        var saved = new CaptureConfiguration
+        {
+            MicStreamFlags = WasapiStreamFlags.NoPersist | WasapiStreamFlags.SrcDefaultQuality,
+            SystemStreamFlags = WasapiStreamFlags.None,
+        };
+
+        var restored = CaptureConfiguration.FromJson(saved.ToJson());
+
+        Assert.Equal(WasapiStreamFlags.NoPersist | WasapiStreamFlags.SrcDefaultQuality, restored.MicStreamFlags);
+        Assert.Equal(WasapiStreamFlags.None, restored.SystemStreamFlags); // no flags at all, not NAudio's defaults
+        Assert.Null(new CaptureConfiguration().MicStreamFlags);
+    }
+
+    [Fact]
+    public void ForCall_CapturesBothSidesOnTheCommunicationsDevices()
+    {
+        var config = CaptureConfiguration.ForCall(@"C:\Calls");
+
+        Assert.Equal(@"C:\Calls", config.OutputDirectory);
+        Assert.True(config.EnableMicCapture);
+        Assert.True(config.EnableSystemCapture);
+        Assert.Equal(DeviceRole.Communications, config.DeviceRole);
+        Assert.Equal(new CaptureConfiguration().SampleRate, config.SampleRate);
+    }
+
+    [Theory]
+    [InlineData(47999.7, 48000)]
+    [InlineData(4800, 8000)]
+    [InlineData(1_000_000, 192000)]
+    [InlineData(12000, 16000)] // halfway between 8000 and 16000 rounds up
+    [InlineData(22050, 22050)]
+    [InlineData(32000, 32000)]
+    [InlineData(176400, 192000)]
+    public void NearestStandardSampleRate_PicksTheClosestKnownRate(double rate, int expected)
+    {
+        Assert.Equal(expected, CaptureConfiguration.NearestStandardSampleRate(rate));
+    }
+
+    [Fact]
+    public void Json_ThatIsntAConfiguration_IsAConfigurationError()
+    {
+        var error = Assert.Throws<CaptureException>(() => CaptureConfiguration.FromJson("[1, 2]"));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+}
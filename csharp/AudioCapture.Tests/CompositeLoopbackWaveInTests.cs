@@ -0,0 +1,145 @@
+using AudioCapture.Capture;
+using NAudio.Wave;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class CompositeLoopbackWaveInTests
+{
+    [Fact]
+    public void Outputs_AreSummedAtUnityGainAsFloatStereo()
+    {
+        var speakers = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        var headset = new ManualWaveIn(new WaveFormat(48000, 16, 2));
+        using var composite = new CompositeLoopbackWaveIn([speakers, headset], 48000);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        speakers.EmitFloat(0.25f, frames: 100);
+        Assert.Empty(output); // the headset hasn't delivered anything to sum against yet
+
+        headset.EmitPcm16(0.5f, frames: 60);
+        var samples = Decode(output);
+        Assert.Equal(120, samples.Length);
+        Assert.All(samples, s => Assert.Equal(0.75f, s, 0.001f));
+        Assert.Equal(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2), composite.WaveFormat);
+    }
+
+    [Fact]
+    public void DifferingRatesAndLayouts_AreNormalizedBeforeSumming()
+    {
+        var native = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        var surround = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(44100, 6));
+        using var composite = new CompositeLoopbackWaveIn([native, surround], 48000);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        // One second from each; the 44.1 kHz 5.1 output should stretch to ~48000 stereo frames.
+        for (int i = 0; i < 10; i++)
+        {
+            native.EmitFloat(0.2f, frames: 4800);
+            surround.EmitFloat(0f, frames: 4410);
+        }
+
+        var frames = Decode(output).Length / 2;
+        Assert.InRange(frames, 47000, 48000);
+    }
+
+    [Fact]
+    public void AnIdleOutputContributesNothingAndHoldsNothingBack()
+    {
+        var playing = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        var idle = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        using var composite = new CompositeLoopbackWaveIn([playing, idle], 48000);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        for (int i = 0; i < 10; i++)
+            playing.EmitFloat(0.5f, frames: 4800);
+
+        // Everything but the last 100 ms has been let through, untouched.
+        var samples = Decode(output);
+        Assert.Equal((48000 - 4800) * 2, samples.Length);
+        Assert.All(samples, s => Assert.Equal(0.5f, s, 0.001f));
+    }
+
+    [Fact]
+    public void OneOutputFailing_StopsTheRestAndReportsIt()
+    {
+        var a = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        var b = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        using var composite = new CompositeLoopbackWaveIn([a, b], 48000);
+        var stops = new List<Exception?>();
+        composite.RecordingStopped += (_, e) => stops.Add(e.Exception);
+        composite.StartRecording();
+
+        var unplugged = new InvalidOperationException("unplugged");
+        a.Fail(unplugged);
+
+        Assert.Equal([unplugged], stops);
+    }
+
+    [Fact]
+    public void RecordingStopped_FiresOnceAfterEveryOutputStops()
+    {
+        var a = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        var b = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        using var composite = new CompositeLoopbackWaveIn([a, b], 48000);
+        var stops = 0;
+        composite.RecordingStopped += (_, _) => stops++;
+        composite.StartRecording();
+
+        composite.StopRecording();
+
+        Assert.Equal(1, stops);
+    }
+
+    private static List<byte> Collect(CompositeLoopbackWaveIn composite)
+    {
+        var output = new List<byte>();
+        composite.DataAvailable += (_, e) => output.AddRange(e.Buffer.AsSpan(0, e.BytesRecorded));
+        return output;
+    }
+
+    private static float[] Decode(List<byte> float32)
+    {
+        var bytes = float32.ToArray();
+        var samples = new float[bytes.Length / 4];
+        Buffer.BlockCopy(bytes, 0, samples, 0, bytes.Length);
+        return samples;
+    }
+
+    /// <summary>An <see cref="IWaveIn"/> the test drives by hand, synchronously.</summary>
+    private sealed class ManualWaveIn(WaveFormat waveFormat) : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable;
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = waveFormat;
+
+        public void StartRecording() { }
+
+        public void StopRecording() => RecordingStopped?.Invoke(this, new StoppedEventArgs());
+
+        public void Dispose() { }
+
+        public void Fail(Exception exception) => RecordingStopped?.Invoke(this, new StoppedEventArgs(exception));
+
+        public void EmitPcm16(float value, int frames)
+        {
+            var sample = (short)(value * short.MaxValue);
+            var bytes = new byte[frames * WaveFormat.Channels * 2];
+            for (int i = 0; i < bytes.Length; i += 2)
+                BitConverter.TryWriteBytes(bytes.AsSpan(i), sample);
+            DataAvailable?.Invoke(this, new WaveInEventArgs(bytes, bytes.Length));
+        }
+
+        public void EmitFloat(float value, int frames)
+        {
+            var bytes = new byte[frames * WaveFormat.Channels * 4];
+            for (int i = 0; i < bytes.Length; i += 4)
+                BitConverter.TryWriteBytes(bytes.AsSpan(i), value);
+            DataAvailable?.Invoke(this, new WaveInEventArgs(bytes, bytes.Length));
+        }
+    }
+}
@@ -0,0 +1,256 @@
+using AudioCapture.Capture;
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using NAudio.Wave;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class CompositeMicWaveInTests
+{
+    [Fact]
+    public void Sum_IsWeightedByGainAndFrameAligned()
+    {
+        var a = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var b = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 1));
+        using var composite = new CompositeMicWaveIn([a, b], 48000, gains: [1f, 0.5f]);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        a.EmitPcm16(0.25f, frames: 100);
+        Assert.Empty(output); // b hasn't delivered anything to sum against yet
+
+        b.EmitFloat(0.5f, frames: 60);
+        var samples = Decode(output);
+        Assert.Equal(60, samples.Length);
+        Assert.All(samples, s => Assert.Equal(0.5f, s, 0.001f)); // 0.25 + 0.5 * 0.5
+
+        b.EmitFloat(0.5f, frames: 40);
+        Assert.Equal(100, Decode(output).Length); // the carried 40 frames of a
+    }
+
+    [Fact]
+    public void DifferingRates_AreResampledToTheTargetBeforeSumming()
+    {
+        var native = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var slow = new ManualWaveIn(new WaveFormat(44100, 16, 2));
+        using var composite = new CompositeMicWaveIn([native, slow], 48000);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        // One second from each; the 44.1 kHz stereo mic should stretch to ~48000 frames.
+        for (int i = 0; i < 10; i++)
+        {
+            native.EmitPcm16(0.2f, frames: 4800);
+            slow.EmitPcm16(0.2f, frames: 4410);
+        }
+
+        var samples = Decode(output);
+        Assert.InRange(samples.Length, 47000, 48000);
+        Assert.Equal(0.2f, samples[samples.Length / 2], 0.01f); // (0.2 + 0.2) / 2
+    }
+
+    [Fact]
+    public void MatchingRates_SkipTheResamplerEntirely()
+    {
+        var native = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var slow = new ManualWaveIn(new WaveFormat(44100, 16, 1));
+        var built = 0;
+        using var composite = new CompositeMicWaveIn(
+            [native, slow], 48000, resamplerFactory: () => { built++; return new WdlAudioResampler(); });
+        composite.StartRecording();
+
+        native.EmitPcm16(0.2f, frames: 4800);
+        slow.EmitPcm16(0.2f, frames: 4410);
+
+        Assert.Equal(1, built); // only the 44.1 kHz input pays for one
+    }
+
+    [Fact]
+    public void AStalledSourceIsZeroFilledInsteadOfHoldingTheOthersBack()
+    {
+        var live = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var unplugged = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        using var composite = new CompositeMicWaveIn([live, unplugged], 48000);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        for (int i = 0; i < 10; i++)
+            live.EmitPcm16(0.5f, frames: 4800);
+
+        Assert.NotEmpty(output);
+    }
+
+    [Theory]
+    [InlineData(MicDownmix.Average, 0.4f, 0.4f, 0.4f)] // dual-mono: any fold keeps the level
+    [InlineData(MicDownmix.First, 0.4f, 0.4f, 0.4f)]
+    [InlineData(MicDownmix.Detect, 0.4f, 0.4f, 0.4f)]
+    [InlineData(MicDownmix.Average, 0.4f, 0.2f, 0.3f)] // distinct channels
+    [InlineData(MicDownmix.First, 0.4f, 0.2f, 0.4f)]
+    [InlineData(MicDownmix.Detect, 0.4f, 0.2f, 0.3f)]
+    [InlineData(MicDownmix.Average, 0.4f, 0f, 0.2f)]   // one dead channel: averaging loses 6 dB
+    [InlineData(MicDownmix.First, 0.4f, 0f, 0.4f)]
+    [InlineData(MicDownmix.Detect, 0f, 0.4f, 0.4f)]    // ...which Detect avoids, whichever side is live
+    public void StereoMic_IsFoldedPerTheDownmix(MicDownmix downmix, float left, float right, float expected)
+    {
+        var mic = new ManualWaveIn(WaveFormat.CreateIeeeFloatWaveFormat(48000, 2));
+        using var composite = new CompositeMicWaveIn([mic], 48000, gains: [1f], downmix: downmix);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        mic.EmitFloatFrames([left, right], frames: 480);
+
+        var samples = Decode(output);
+        Assert.Equal(480, samples.Length);
+        Assert.All(samples, s => Assert.Equal(expected, s, 0.001f));
+    }
+
+    [Fact]
+    public void RecordingStopped_FiresOnceAfterEverySourceStops()
+    {
+        var a = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var b = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        using var composite = new CompositeMicWaveIn([a, b], 48000);
+        var stops = 0;
+        composite.RecordingStopped += (_, _) => stops++;
+        composite.StartRecording();
+
+        composite.StopRecording();
+
+        Assert.Equal(1, stops);
+    }
+
+    [Fact]
+    public void UndecodableSourceFormat_FailsTheStartInsteadOfTheCallback()
+    {
+        var good = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var packed24 = new ManualWaveIn(new WaveFormat(48000, 24, 1));
+        using var composite = new CompositeMicWaveIn([good, packed24], 48000);
+        var output = Collect(composite);
+
+        var error = Assert.Throws<CaptureException>(composite.StartRecording);
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+        Assert.Contains("Mic 2", error.Message);
+        good.EmitPcm16(0.5f, frames: 480);
+        Assert.Empty(output); // never started
+    }
+
+    [Fact]
+    public void ExtensibleFloatSource_IsDecodedLikeIeeeFloat()
+    {
+        // The shape of a WASAPI mix format.
+        var mic = new ManualWaveIn(new WaveFormatExtensible(48000, 32, 2));
+        using var composite = new CompositeMicWaveIn([mic], 48000, gains: [1f]);
+        var output = Collect(composite);
+        composite.StartRecording();
+
+        mic.EmitFloatFrames([0.4f, 0.2f], frames: 480);
+
+        Assert.All(Decode(output), s => Assert.Equal(0.3f, s, 0.001f));
+    }
+
+    [Fact]
+    public void RecordingStopped_FiresWhenStoppedWhilePaused()
+    {
+        var plain = new ManualWaveIn(new WaveFormat(48000, 16, 1));
+        var pausable = new WasapiLikeWaveIn();
+        using var composite = new CompositeMicWaveIn([plain, pausable], 48000);
+        var stops = 0;
+        composite.RecordingStopped += (_, _) => stops++;
+        composite.StartRecording();
+
+        composite.Pause();
+        Assert.Equal(0, stops); // the pausable input's stop at pause isn't the composite's
+
+        composite.StopRecording();
+        Assert.Equal(1, stops);
+    }
+
+    private static List<byte> Collect(CompositeMicWaveIn composite)
+    {
+        var output = new List<byte>();
+        composite.DataAvailable += (_, e) => output.AddRange(e.Buffer.AsSpan(0, e.BytesRecorded));
+        return output;
+    }
+
+    private static float[] Decode(List<byte> pcm16)
+    {
+        var bytes = pcm16.ToArray();
+        var samples = new float[bytes.Length / 2];
+        for (int i = 0; i < samples.Length; i++)
+            samples[i] = BitConverter.ToInt16(bytes, i * 2) / (float)short.MaxValue;
+        return samples;
+    }
+
+    /// <summary>
+    /// Pauses as a WASAPI client does: its capture thread exits and reports the stop,
+    /// after which stopping it again has nothing to report.
+    /// </summary>
+    private sealed class WasapiLikeWaveIn : IPausableWaveIn
+    {
+        private bool _running;
+
+        public event EventHandler<WaveInEventArgs>? DataAvailable { add { } remove { } }
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = new(48000, 16, 1);
+
+        public void StartRecording() => _running = true;
+
+        public void StopRecording()
+        {
+            if (!_running) return;
+            _running = false;
+            RecordingStopped?.Invoke(this, new StoppedEventArgs());
+        }
+
+        public void Pause() => StopRecording();
+
+        public void Resume() => StartRecording();
+
+        public void Dispose() { }
+    }
+
+    /// <summary>An <see cref="IWaveIn"/> the test drives by hand, synchronously.</summary>
+    private sealed class ManualWaveIn(WaveFormat waveFormat) : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable;
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = waveFormat;
+
+        public void StartRecording() { }
+
+        public void StopRecording() => RecordingStopped?.Invoke(this, new StoppedEventArgs());
+
+        public void Dispose() { }
+
+        public void EmitPcm16(float value, int frames)
+        {
+            var sample = (short)(value * short.MaxValue);
+            var bytes = new byte[frames * WaveFormat.Channels * 2];
+            for (int i = 0; i < bytes.Length; i += 2)
+                BitConverter.TryWriteBytes(bytes.AsSpan(i), sample);
+            DataAvailable?.Invoke(this, new WaveInEventArgs(bytes, bytes.Length));
+        }
+
+        public void EmitFloat(float value, int frames)
+        {
+            var bytes = new byte[frames * WaveFormat.Channels * 4];
+            for (int i = 0; i < bytes.Length; i += 4)
+                BitConverter.TryWriteBytes(bytes.AsSpan(i), value);
+            DataAvailable?.Invoke(this, new WaveInEventArgs(bytes, bytes.Length));
+        }
+
+        /// <summary>Emits float frames carrying one constant per channel.</summary>
+        public void EmitFloatFrames(float[] channelValues, int frames)
+        {
+            var bytes = new byte[frames * channelValues.Length * 4];
+            for (int i = 0; i < bytes.Length / 4; i++)
+                BitConverter.TryWriteBytes(bytes.AsSpan(i * 4), channelValues[i % channelValues.Length]);
+            DataAvailable?.Invoke(this, new WaveInEventArgs(bytes, bytes.Length));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+using AudioCapture.Capture;
+using AudioCapture.Models;
+using NAudio.CoreAudioApi;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class DeviceEnumeratorTests
+{
+    private static AudioSource Mic(string name, bool isDefault = false) =>
+        new($"id:{name}", name, AudioTrackType.Mic, isDefault, AudioTransportType.Usb);
+
+    private static LoopbackDevice Output(string name, bool isDefault = false, bool isRendering = false) =>
+        new(new($"id:{name}", name, AudioTrackType.System, isDefault, TransportType: null), isRendering);
+
+    [Fact]
+    public void SelectByName_MatchesAFragmentIgnoringCase()
+    {
+        AudioSource[] devices = [Mic("Microphone (Realtek Audio)"), Mic("Microphone (Yeti Stereo Microphone)")];
+
+        Assert.Equal("id:Microphone (Yeti Stereo Microphone)", DeviceEnumerator.SelectByName(devices, "yeti")?.Id);
+        Assert.Null(DeviceEnumerator.SelectByName(devices, "Rode"));
+    }
+
+    [Fact]
+    public void SelectByName_AnExactNameBeatsLongerOnesContainingIt()
+    {
+        AudioSource[] devices = [Mic("Headset"), Mic("Headset (Hands-Free)")];
+
+        Assert.Equal("id:Headset", DeviceEnumerator.SelectByName(devices, "HEADSET")?.Id);
+    }
+
+    [Fact]
+    public void SelectByName_SettlesAmbiguityOnTheDefaultOrFails()
+    {
+        AudioSource[] withDefault = [Mic("USB Mic 1"), Mic("USB Mic 2", isDefault: true)];
+        AudioSource[] withoutDefault = [Mic("USB Mic 1"), Mic("USB Mic 2")];
+
+        Assert.Equal("id:USB Mic 2", DeviceEnumerator.SelectByName(withDefault, "USB Mic")?.Id);
+        var error = Assert.Throws<CaptureException>(() => DeviceEnumerator.SelectByName(withoutDefault, "USB Mic"));
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+        Assert.Contains("USB Mic 1, USB Mic 2", error.Message);
+    }
+
+    [Fact]
+    public void SuggestLoopbackDevice_PrefersTheOutputThatIsPlaying()
+    {
+        LoopbackDevice[] idleDefault = [Output("Speakers", isDefault: true), Output("Headphones", isRendering: true)];
+        LoopbackDevice[] busyDefault = [Output("Speakers", isDefault: true, isRendering: true), Output("Headphones", isRendering: true)];
+        LoopbackDevice[] allIdle = [Output("Headphones"), Output("Speakers", isDefault: true)];
+
+        Assert.Equal("Headphones", DeviceEnumerator.SuggestLoopbackDevice(idleDefault)?.Device.Name);
+        Assert.Equal("Speakers", DeviceEnumerator.SuggestLoopbackDevice(busyDefault)?.Device.Name);
+        Assert.Equal("Speakers", DeviceEnumerator.SuggestLoopbackDevice(allIdle)?.Device.Name);
+        Assert.Null(DeviceEnumerator.SuggestLoopbackDevice([]));
+    }
+
+    [Theory]
+    [InlineData(DeviceRole.Console, Role.Console)]
+    [InlineData(DeviceRole.Multimedia, Role.Multimedia)]
+    [InlineData(DeviceRole.Communications, Role.Communications)]
+    public void EachDeviceRole_ResolvesItsOwnWindowsRole(DeviceRole role, Role expected)
+    {
+        // Every default lookup — IsDefault flags included — goes through this mapping.
+        Assert.Equal(expected, DeviceEnumerator.ToRole(role));
+    }
+
+    [Fact]
+    public void SupportedFormats_OfADeviceThatCantBeActivated_IsDeviceNotAvailable()
+    {
+        // No endpoint has this ID, and off Windows there are no endpoints at all.
+        var error = Assert.Throws<CaptureException>(
+            () => DeviceEnumerator.GetSupportedFormats("{0.0.1.00000000}.{not-a-device}"));
+        Assert.Equal(CaptureErrorKind.DeviceNotAvailable, error.ErrorKind);
+    }
+}
@@ -1,3 +1,7 @@
+using System.Globalization;
+using System.Security.Cryptography;
+using System.Text;
+using AudioCapture.Interfaces;
 using AudioCapture.Models;
 using AudioCapture.Storage;
 using Xunit;
@@ -151,4 +155,761 @@ public class EncryptedWavWriterTests : IDisposable
         var header = EncryptedWavWriter.GenerateWavHeader(48000, 16, 2, 0);
         Assert.Equal(44, header.Length);
     }
+
+    [Fact]
+    public void GenerateWavHeader_MarksFloatDataAsIeeeFloat()
+    {
+        var header = EncryptedWavWriter.GenerateWavHeader(48000, 32, 2, 0, isFloat: true);
+
+        Assert.Equal(3, BitConverter.ToUInt16(header, 20));  // WAVE_FORMAT_IEEE_FLOAT
+        Assert.Equal(8, BitConverter.ToUInt16(header, 32));  // block align
+        Assert.Equal(32, BitConverter.ToUInt16(header, 34));
+    }
+
+    [Fact]
+    public void SurroundWav_HasExtensibleHeaderWithChannelMask()
+    {
+        var path = Path.Combine(_tempDir, "surround.wav");
+        using var writer = new EncryptedWavWriter(path);
+        writer.Open(DefaultConfig with { Channels = 6 });
+
+        var pcm = new byte[480 * 6 * 2]; // 480 frames * 6 channels * 2 bytes
+        writer.Write(pcm);
+        writer.Close();
+
+        var file = File.ReadAllBytes(path);
+
+        Assert.Equal(68 + pcm.Length, file.Length);
+        Assert.Equal(40u, BitConverter.ToUInt32(file, 16));      // extensible fmt size
+        Assert.Equal(0xFFFE, BitConverter.ToUInt16(file, 20));   // WAVE_FORMAT_EXTENSIBLE
+        Assert.Equal(6, BitConverter.ToUInt16(file, 22));
+        Assert.Equal(12, BitConverter.ToUInt16(file, 32));       // block align
+        Assert.Equal(0x3Fu, BitConverter.ToUInt32(file, 40));    // FL FR FC LFE BL BR
+        Assert.Equal(1u, BitConverter.ToUInt32(file, 44));       // PCM subformat
+
+        // Sizes are patched at the extensible header's offsets, not the 44-byte ones.
+        Assert.Equal((uint)(file.Length - 8), BitConverter.ToUInt32(file, 4));
+        Assert.Equal((uint)pcm.Length, BitConverter.ToUInt32(file, 64));
+    }
+
+    [Theory]
+    [InlineData(ChecksumMode.File)]
+    [InlineData(ChecksumMode.Streaming)]
+    public void VerifyChecksum_AcceptsTheRecordingAndRejectsAnyChange(ChecksumMode mode)
+    {
+        using var encryptor = new AesGcmEncryptor(new byte[32]);
+        var path = Path.Combine(_tempDir, "verify.enc.wav");
+        string checksum;
+        using (var writer = new EncryptedWavWriter(path, encryptor))
+        {
+            writer.Open(DefaultConfig with { ChecksumMode = mode });
+            writer.Write(new byte[9600]);
+            checksum = writer.Close();
+        }
+
+        if (mode == ChecksumMode.File)
+            Assert.Equal(checksum, EncryptedWavWriter.ComputeChecksum(path));
+        Assert.True(EncryptedWavWriter.VerifyChecksum(path, checksum.ToUpperInvariant(), mode));
+
+        // One flipped bit in the last sealed frame, as a bad transfer would leave.
+        var file = File.ReadAllBytes(path);
+        file[^1] ^= 1;
+        File.WriteAllBytes(path, file);
+        Assert.False(EncryptedWavWriter.VerifyChecksum(path, checksum, mode));
+    }
+
+    [Fact]
+    public void ComputeChecksum_MissingFileIsAStorageError()
+    {
+        var error = Assert.Throws<CaptureException>(
+            () => EncryptedWavWriter.ComputeChecksum(Path.Combine(_tempDir, "gone.wav")));
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+    }
+
+    [Fact]
+    public void Bwf_WritesABextChunkBetweenFmtAndData()
+    {
+        var path = Path.Combine(_tempDir, "broadcast.wav");
+        var config = DefaultConfig with
+        {
+            ChecksumMode = ChecksumMode.Streaming,
+            Bwf = new BwfConfiguration { Description = "Session 12, room B", OriginatorReference = "REF-0042" },
+        };
+        var before = DateTime.Now;
+        using var writer = new EncryptedWavWriter(path);
+        writer.Open(config);
+        var after = DateTime.Now;
+
+        var pcm = new byte[4800];
+        writer.Write(pcm);
+        var checksum = writer.Close();
+
+        var file = File.ReadAllBytes(path);
+        Assert.Equal(44 + 610 + pcm.Length, file.Length);
+        Assert.Equal("bext"u8.ToArray(), file[36..40]);
+        Assert.Equal(602u, BitConverter.ToUInt32(file, 40));
+
+        const int body = 44;
+        string Text(int offset, int width) => Encoding.ASCII.GetString(file, body + offset, width).TrimEnd('\0');
+        Assert.Equal("Session 12, room B", Text(0, 256));
+        Assert.Equal("AudioCaptureKit", Text(256, 32));
+        Assert.Equal("REF-0042", Text(288, 32));
+        Assert.Equal(1, BitConverter.ToUInt16(file, body + 346));
+
+        var origination = DateTime.ParseExact($"{Text(320, 10)} {Text(330, 8)}", "yyyy-MM-dd HH:mm:ss", CultureInfo.InvariantCulture);
+        Assert.InRange(origination, before.AddSeconds(-1), after);
+        var timeReference = BitConverter.ToUInt64(file, body + 338);
+        Assert.Equal(origination.TimeOfDay.TotalSeconds * 48000, timeReference, 48000.0);
+
+        // Data still comes last, sized as usual, and the payload digest skips the bext.
+        Assert.Equal("data"u8.ToArray(), file[654..658]);
+        Assert.Equal((uint)pcm.Length, BitConverter.ToUInt32(file, 658));
+        Assert.Equal((uint)(file.Length - 8), BitConverter.ToUInt32(file, 4));
+        Assert.Equal(EncryptedWavWriter.ComputePayloadChecksum(path), checksum);
+    }
+
+    [Fact]
+    public void Bwf_ResumeKeepsTheFirstRunsBextChunk()
+    {
+        var path = Path.Combine(_tempDir, "broadcast-resume.wav");
+        var config = DefaultConfig with { Bwf = new BwfConfiguration { Description = "first run" } };
+        using (var writer = new EncryptedWavWriter(path))
+        {
+            writer.Open(config);
+            writer.Write(new byte[400]);
+            writer.Close();
+        }
+        var bext = File.ReadAllBytes(path)[36..646];
+
+        using (var writer = new EncryptedWavWriter(path))
+        {
+            writer.OpenAppend(config with { Bwf = new BwfConfiguration { Description = "second run" } });
+            writer.Write(new byte[400]);
+            writer.Close();
+        }
+
+        var file = File.ReadAllBytes(path);
+        Assert.Equal(bext, file[36..646]);
+        Assert.Equal(800u, BitConverter.ToUInt32(file, 658));
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void StreamingChecksum_MatchesAReReadOfTheDataChunk(bool encrypted)
+    {
+        using var encryptor = new AesGcmEncryptor(new byte[32]);
+        var path = Path.Combine(_tempDir, "streamed.wav");
+        using var writer = new EncryptedWavWriter(path, encrypted ? encryptor : null);
+        writer.Open(DefaultConfig with { ChecksumMode = ChecksumMode.Streaming });
+
+        var pcm = new byte[4096];
+        new Random(7).NextBytes(pcm);
+        for (int i = 0; i < 5; i++)
+            writer.Write(pcm);
+        var streamed = writer.Close();
+
+        Assert.Equal(EncryptedWavWriter.ComputePayloadChecksum(path), streamed);
+
+        // The header is not covered — that is what lets close skip the re-read.
+        Assert.NotEqual(Convert.ToHexStringLower(SHA256.HashData(File.ReadAllBytes(path))), streamed);
+    }
+
+    [Fact]
+    public void OpenAppend_ContinuesAClosedPlaintextRecording()
+    {
+        var path = Path.Combine(_tempDir, "resume.wav");
+        using (var first = new EncryptedWavWriter(path))
+        {
+            first.Open(DefaultConfig);
+            first.Write(Enumerable.Repeat((byte)1, 400).ToArray());
+            first.Close();
+        }
+
+        using var second = new EncryptedWavWriter(path);
+        second.OpenAppend(DefaultConfig);
+        Assert.Equal(44 + 400, second.BytesWritten);
+        second.Write(Enumerable.Repeat((byte)2, 200).ToArray());
+        second.Close();
+
+        var file = File.ReadAllBytes(path);
+        Assert.Equal(44 + 600, file.Length);
+        Assert.Equal(600u, BitConverter.ToUInt32(file, 40));
+        Assert.Equal((uint)(file.Length - 8), BitConverter.ToUInt32(file, 4));
+        Assert.All(file[44..444], b => Assert.Equal(1, b));
+        Assert.All(file[444..], b => Assert.Equal(2, b));
+    }
+
+    [Fact]
+    public void OpenAppend_AddsSealedFramesAfterTheExistingChain()
+    {
+        using var encryptor = new AesGcmEncryptor(new byte[32]);
+        var path = Path.Combine(_tempDir, "resume.enc.wav");
+        using (var first = new EncryptedWavWriter(path, encryptor))
+        {
+            first.Open(DefaultConfig);
+            first.Write(new byte[100]);
+            first.Close();
+        }
+
+        // A crash mid-frame: a length prefix promising more than made it to disk.
+        using (var torn = new FileStream(path, FileMode.Append))
+            torn.Write([0xFF, 0x00, 0x00, 0x00, 0xAA]);
+
+        using var second = new EncryptedWavWriter(path, encryptor);
+        second.OpenAppend(DefaultConfig);
+        second.Write(Enumerable.Repeat((byte)7, 50).ToArray());
+        second.Close();
+
+        var file = File.ReadAllBytes(path);
+        var firstLength = (int)BitConverter.ToUInt32(file, 44);
+        var secondOffset = 48 + firstLength;
+        var secondLength = (int)BitConverter.ToUInt32(file, secondOffset);
+
+        Assert.Equal(file.Length, secondOffset + 4 + secondLength); // torn tail is gone
+        Assert.Equal(new byte[100], encryptor.Decrypt(file[48..secondOffset]));
+        Assert.Equal(Enumerable.Repeat((byte)7, 50), encryptor.Decrypt(file[(secondOffset + 4)..]));
+    }
+
+    [Fact]
+    public void EncryptionChunkSize_ReframesSmallWritesWithoutChangingThePcm()
+    {
+        using var encryptor = new AesGcmEncryptor(new byte[32]);
+        var path = Path.Combine(_tempDir, "chunked.enc.wav");
+        var config = DefaultConfig with { EncryptionChunkSize = 65536 };
+
+        // 20 ms writes, as a low-latency mix cycle would make them.
+        var pcm = Enumerable.Range(0, 50 * 3840).Select(i => (byte)(i * 31)).ToArray();
+        using (var writer = new EncryptedWavWriter(path, encryptor))
+        {
+            writer.Open(config);
+            for (int offset = 0; offset < pcm.Length; offset += 3840)
+                writer.Write(pcm.AsSpan(offset, 3840));
+            writer.Close();
+        }
+
+        var file = File.ReadAllBytes(path);
+        var plaintext = new List<byte>();
+        var frames = 0;
+        for (int offset = 44; offset < file.Length; frames++)
+        {
+            var length = (int)BitConverter.ToUInt32(file, offset);
+            var frame = encryptor.Decrypt(file[(offset + 4)..(offset + 4 + length)]);
+            Assert.Equal(0, frame.Length % 4); // whole stereo 16-bit frames
+            plaintext.AddRange(frame);
+            offset += 4 + length;
+        }
+
+        Assert.Equal(pcm, plaintext.ToArray());
+        Assert.Equal((pcm.Length + 65535) / 65536, frames);
+    }
+
+    [Fact]
+    public void OpenAppend_RejectsADifferentFormat()
+    {
+        var path = Path.Combine(_tempDir, "mono.wav");
+        using (var first = new EncryptedWavWriter(path))
+        {
+            first.Open(DefaultConfig with { Channels = 1 });
+            first.Close();
+        }
+
+        using var second = new EncryptedWavWriter(path);
+        var error = Assert.Throws<CaptureException>(() => second.OpenAppend(DefaultConfig));
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+    }
+
+    [Fact]
+    public void StreamTarget_HoldsTheSameWavAFileWould()
+    {
+        var path = Path.Combine(_tempDir, "disk.wav");
+        var pcm = Enumerable.Range(0, 400).Select(i => (byte)i).ToArray();
+        string fileChecksum;
+        using (var fileWriter = new EncryptedWavWriter(path))
+        {
+            fileWriter.Open(DefaultConfig);
+            fileWriter.Write(pcm);
+            fileWriter.Close();
+            fileChecksum = Convert.ToHexStringLower(SHA256.HashData(File.ReadAllBytes(path)));
+        }
+
+        using var memory = new MemoryStream();
+        using var writer = new EncryptedWavWriter(memory, leaveOpen: true);
+        writer.Open(DefaultConfig);
+        writer.Write(pcm);
+        var checksum = writer.Close();
+
+        Assert.Equal(File.ReadAllBytes(path), memory.ToArray());
+        Assert.Equal(fileChecksum, checksum);
+    }
+
+    [Fact]
+    public void StreamTarget_ResumesWithoutTouchingTheFilesystem()
+    {
+        using var memory = new MemoryStream();
+        using (var first = new EncryptedWavWriter(memory, leaveOpen: true))
+        {
+            first.Open(DefaultConfig);
+            first.Write(new byte[40]);
+            first.Close();
+        }
+        memory.WriteByte(0xAB); // a torn sample from a crash
+
+        using var second = new EncryptedWavWriter(memory, leaveOpen: true);
+        second.OpenAppend(DefaultConfig with { ChecksumMode = ChecksumMode.Streaming });
+        second.Write(Enumerable.Repeat((byte)1, 8).ToArray());
+        var checksum = second.Close();
+
+        var bytes = memory.ToArray();
+        Assert.Equal(44 + 48, bytes.Length);
+        Assert.Equal(48, BitConverter.ToInt32(bytes, 40));
+        Assert.Equal(Convert.ToHexStringLower(SHA256.HashData(bytes[44..])), checksum);
+    }
+
+    [Fact]
+    public void StreamTarget_WriteOnlyNeedsAStreamingChecksum()
+    {
+        using var writeOnly = new WriteOnlyStream();
+        using var writer = new EncryptedWavWriter(writeOnly);
+
+        var error = Assert.Throws<CaptureException>(() => writer.Open(DefaultConfig));
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+
+        writer.Open(DefaultConfig with { ChecksumMode = ChecksumMode.Streaming });
+        writer.Write(new byte[4]);
+        Assert.Equal(Convert.ToHexStringLower(SHA256.HashData(new byte[4])), writer.Close());
+    }
+
+    [Theory]
+    [InlineData(2)]
+    [InlineData(6)]
+    public void Spec_ReflectsTheHeaderAsWritten(int channels)
+    {
+        using var writer = new EncryptedWavWriter(new MemoryStream());
+        Assert.Null(writer.Spec);
+
+        writer.Open(DefaultConfig with { Channels = channels, SampleRate = 16000 });
+        writer.Close();
+
+        Assert.Equal(new WavSpec(SampleRate: 16000, Channels: channels, BitDepth: 16, IsFloat: false), writer.Spec);
+    }
+
+    /// <summary>Writes three equal-length frames bound to their sequence and returns the file and manifest.</summary>
+    private (string Path, ChunkManifest Manifest) WriteSequenceBound(AesGcmEncryptor encryptor)
+    {
+        var path = Path.Combine(_tempDir, "sequenced.enc.wav");
+        using var writer = new EncryptedWavWriter(path, encryptor);
+        writer.Open(DefaultConfig with { SequenceBoundChunks = true });
+        for (byte i = 1; i <= 3; i++)
+            writer.Write(Enumerable.Repeat(i, 400).ToArray());
+        writer.Close();
+        return (path, writer.Manifest!);
+    }
+
+    [Fact]
+    public void SequenceBoundChunks_ReadBackInOrderAndMatchTheManifest()
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, manifest) = WriteSequenceBound(encryptor);
+
+        var pcm = EncryptedWavReader.ReadPcm(path, encryptor, sequenceBound: true);
+
+        Assert.Equal(3, manifest.ChunkCount);
+        Assert.True(EncryptedWavReader.VerifyManifest(path, manifest));
+        Assert.Equal(Enumerable.Repeat((byte)1, 400).Concat(Enumerable.Repeat((byte)2, 400)).Concat(Enumerable.Repeat((byte)3, 400)), pcm);
+        // Bound frames don't open as plain ones.
+        Assert.Throws<CaptureException>(() => EncryptedWavReader.ReadPcm(path, encryptor));
+    }
+
+    [Fact]
+    public void SequenceBoundChunks_SwappedChunksAreDetectedOnDecrypt()
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, manifest) = WriteSequenceBound(encryptor);
+
+        // Swap the first two frames. They're the same length, so the file still
+        // walks cleanly and each box is individually genuine.
+        var file = File.ReadAllBytes(path);
+        var frameLength = 4 + (int)BitConverter.ToUInt32(file, 44);
+        var first = file[44..(44 + frameLength)];
+        file.AsSpan(44 + frameLength, frameLength).CopyTo(file.AsSpan(44));
+        first.CopyTo(file.AsSpan(44 + frameLength));
+        File.WriteAllBytes(path, file);
+
+        var error = Assert.Throws<CaptureException>(() => EncryptedWavReader.ReadPcm(path, encryptor, sequenceBound: true));
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, error.ErrorKind);
+        Assert.Contains("Chunk 0", error.Message);
+        Assert.False(EncryptedWavReader.VerifyManifest(path, manifest));
+    }
+
+    [Fact]
+    public void SequenceBoundChunks_ADroppedFinalChunkFailsTheManifest()
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, manifest) = WriteSequenceBound(encryptor);
+
+        // The one cut the sequence alone can't see: what's left still decrypts.
+        var file = File.ReadAllBytes(path);
+        var frameLength = 4 + (int)BitConverter.ToUInt32(file, 44);
+        File.WriteAllBytes(path, file[..^frameLength]);
+
+        Assert.Equal(800, EncryptedWavReader.ReadPcm(path, encryptor, sequenceBound: true).Length);
+        Assert.False(EncryptedWavReader.VerifyManifest(path, manifest));
+    }
+
+    [Fact]
+    public void VerifyStructure_CountsTheFramesOfAWellFormedFileWithoutTheKey()
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, _) = WriteSequenceBound(encryptor);
+
+        var stats = EncryptedWavReader.VerifyStructure(path);
+
+        Assert.Equal(3, stats.ChunkCount);
+        Assert.Equal(3 * (400 + encryptor.Overhead), stats.CiphertextBytes);
+    }
+
+    [Theory]
+    [InlineData(1)]   // into the last sealed box
+    [InlineData(430)] // into the last length prefix
+    public void VerifyStructure_RejectsATruncatedFile(int cut)
+    {
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, _) = WriteSequenceBound(encryptor);
+        var file = File.ReadAllBytes(path);
+        File.WriteAllBytes(path, file[..^cut]);
+
+        var error = Assert.Throws<CaptureException>(() => EncryptedWavReader.VerifyStructure(path));
+
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public void VerifyStructure_RejectsWholeFramesCutFromBehindTheHeader()
+    {
+        // Every frame that's left walks cleanly; only the header knows one is gone.
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        var (path, _) = WriteSequenceBound(encryptor);
+        var file = File.ReadAllBytes(path);
+        var frameLength = 4 + (int)BitConverter.ToUInt32(file, 44);
+        File.WriteAllBytes(path, file[..^frameLength]);
+
+        var error = Assert.Throws<CaptureException>(() => EncryptedWavReader.VerifyStructure(path));
+
+        Assert.Contains("declares", error.Message);
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void LiveHeader_AReaderMidRecordingSeesTheDataSoFar(bool liveHeader)
+    {
+        var path = Path.Combine(_tempDir, "live.wav");
+        using var writer = new EncryptedWavWriter(path);
+        writer.Open(DefaultConfig with { LiveHeader = liveHeader });
+
+        var pcm = Enumerable.Range(0, 3840).Select(i => (byte)i).ToArray();
+        for (var written = 1; written <= 3; written++)
+        {
+            writer.Write(pcm);
+
+            // Read the way a player would while recording carries on.
+            using var reader = new FileStream(path, FileMode.Open, FileAccess.Read, FileShare.ReadWrite);
+            var header = new byte[44];
+            reader.ReadExactly(header);
+            var expected = liveHeader ? pcm.Length * written : 0;
+            Assert.Equal((uint)expected, BitConverter.ToUInt32(header, 40));
+            Assert.Equal((uint)(36 + expected), BitConverter.ToUInt32(header, 4));
+        }
+        writer.Close();
+
+        // Patching the header never disturbs where the audio goes.
+        var file = File.ReadAllBytes(path);
+        Assert.Equal(44 + 3 * pcm.Length, file.Length);
+        Assert.Equal(pcm, file[^pcm.Length..]);
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void PreallocateFile_IsTruncatedToExactlyHeaderAndDataAtClose(bool encrypted)
+    {
+        var path = Path.Combine(_tempDir, "preallocated.wav");
+        using var encryptor = encrypted ? new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32)) : null;
+        using var writer = new EncryptedWavWriter(path, encryptor);
+        var config = DefaultConfig with { PreallocateFile = true, MaxDuration = TimeSpan.FromMinutes(1), Encryptor = encryptor };
+        writer.Open(config);
+
+        // A minute's worth, though only a fraction of it will be written.
+        Assert.True(new FileInfo(path).Length >= 44 + 48000 * 4 * 60);
+
+        var pcm = Enumerable.Range(0, 19200).Select(i => (byte)i).ToArray();
+        writer.Write(pcm);
+        writer.Flush();
+        writer.Write(pcm);
+        writer.Close();
+
+        var file = File.ReadAllBytes(path);
+        Assert.Equal(writer.BytesWritten, file.Length);
+        Assert.Equal((uint)(file.Length - 8), BitConverter.ToUInt32(file, 4));
+        Assert.Equal((uint)(file.Length - 44), BitConverter.ToUInt32(file, 40));
+        var expected = encrypted ? EncryptedWavReader.ReadPcm(path, encryptor!) : file[44..];
+        Assert.Equal([.. pcm, .. pcm], expected);
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void WriteCombineBytes_LeavesTheFileByteForByteTheSame(bool encrypted)
+    {
+        var encryptor = encrypted ? new FramingOnlyEncryptor() : null;
+        // 20 ms cycles of stereo 16-bit at 48 kHz, with a pause in the middle.
+        var cycle = Enumerable.Range(0, 3840).Select(i => (byte)(i * 7)).ToArray();
+
+        byte[] Record(string name, int? writeCombineBytes)
+        {
+            var path = Path.Combine(_tempDir, name);
+            using var writer = new EncryptedWavWriter(path, encryptor);
+            writer.Open(DefaultConfig with { Encryptor = encryptor, WriteCombineBytes = writeCombineBytes });
+            for (int i = 0; i < 50; i++)
+            {
+                writer.Write(cycle);
+                if (i == 20) writer.Flush();
+            }
+            if (writeCombineBytes != null)
+                Assert.True(new FileInfo(path).Length < writer.BytesWritten, "the last cycles should still be buffered");
+            writer.Close();
+            return File.ReadAllBytes(path);
+        }
+
+        var unbuffered = Record("unbuffered.wav", null);
+        var buffered = Record("buffered.wav", 256 * 1024);
+        Assert.Equal(unbuffered, buffered);
+    }
+
+    [Fact]
+    public void Write_PastTheWavSizeLimitIsAStorageErrorAndLeavesAValidHeader()
+    {
+        var target = new SparseStream();
+        var config = DefaultConfig with { Channels = 1 };
+        using (var first = new EncryptedWavWriter(target, leaveOpen: true))
+        {
+            first.Open(config);
+            first.Close();
+        }
+
+        // Grow the data to just short of the limit without writing four gigabytes.
+        target.SetLength(EncryptedWavWriter.MaxWavBytes - 5);
+        // Not disposed: closing would checksum the whole file.
+        var writer = new EncryptedWavWriter(target, leaveOpen: true);
+        writer.OpenAppend(config);
+        writer.Write(new byte[(EncryptedWavWriter.MaxWavBytes - writer.BytesWritten) & ~1]);
+
+        var error = Assert.Throws<CaptureException>(() => writer.Write(new byte[2]));
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+
+        writer.Flush();
+        var header = new byte[44];
+        target.Seek(0, SeekOrigin.Begin);
+        target.ReadExactly(header);
+        Assert.True(writer.BytesWritten <= EncryptedWavWriter.MaxWavBytes);
+        Assert.Equal((uint)(writer.BytesWritten - 8), BitConverter.ToUInt32(header, 4));
+        Assert.Equal((uint)(writer.BytesWritten - 44), BitConverter.ToUInt32(header, 40));
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void OpenAppend_AfterACrashWhilePreallocatedSkipsTheZeroTail(bool pausedFirst)
+    {
+        var path = Path.Combine(_tempDir, "preallocated.wav");
+        var crashed = Path.Combine(_tempDir, "crashed.wav");
+        var config = DefaultConfig with { PreallocateFile = true, MaxDuration = TimeSpan.FromMinutes(1) };
+        var first = Enumerable.Range(0, 19200).Select(i => (byte)(i % 255 + 1)).ToArray();
+        var second = Enumerable.Reverse(first).ToArray();
+
+        using (var writer = new EncryptedWavWriter(path))
+        {
+            writer.Open(config);
+            writer.Write(first);
+            if (pausedFirst) writer.Flush();
+            writer.Write(second);
+            // The process dies here: what reached the file is all there is.
+            File.Copy(path, crashed);
+        }
+        Assert.True(new FileInfo(crashed).Length >= 44 + 48000 * 4 * 60);
+
+        using (var resumed = new EncryptedWavWriter(crashed))
+        {
+            resumed.OpenAppend(config);
+            Assert.Equal(44 + first.Length + second.Length, resumed.BytesWritten);
+            resumed.Write(first);
+            resumed.Close();
+        }
+
+        Assert.Equal([.. first, .. second, .. first], File.ReadAllBytes(crashed)[44..]);
+    }
+
+    [Fact]
+    public void OpenAppend_KeepsTheSilenceOfAFileThatWasntPreallocated()
+    {
+        // A crash leaves the declared size at 0, and everything written was digital
+        // silence. Without preallocation those zeros are the recording, not slack.
+        var crashed = Path.Combine(_tempDir, "crashed.wav");
+        var silence = new byte[19200];
+        using (var writer = new EncryptedWavWriter(crashed))
+        {
+            writer.Open(DefaultConfig);
+            writer.Write(silence);
+        }
+        // The header as the crash left it: sizes never patched.
+        var bytes = File.ReadAllBytes(crashed);
+        bytes.AsSpan(4, 4).Clear();
+        bytes.AsSpan(40, 4).Clear();
+        File.WriteAllBytes(crashed, bytes);
+
+        using var resumed = new EncryptedWavWriter(crashed);
+        resumed.OpenAppend(DefaultConfig);
+
+        Assert.Equal(44 + silence.Length, resumed.BytesWritten);
+    }
+
+    [Fact]
+    public void Flush_AfterASealedChunkIsRefusedAtTheWavSizeLimitFinishesTheFile()
+    {
+        var target = new SparseStream();
+        var writer = ResumeNearTheSizeLimit(target, roomLeft: 100);
+        var error = Assert.Throws<CaptureException>(() => writer.Write(new byte[4800]));
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+
+        writer.Flush();
+        AssertSizesPatched(target, writer);
+    }
+
+    [Fact]
+    public void Flush_ReportsAShortChunkThatDidntFitButStillPatchesTheSizes()
+    {
+        var target = new SparseStream();
+        var writer = ResumeNearTheSizeLimit(target, roomLeft: 100);
+        writer.Write(new byte[200]); // short of a chunk, so still pending
+
+        var error = Assert.Throws<CaptureException>(() => writer.Flush());
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+        AssertSizesPatched(target, writer);
+    }
+
+    /// <summary>
+    /// Resumes, with 4800-byte sealed chunks, a file whose one frame leaves
+    /// <paramref name="roomLeft"/> bytes under the WAV size limit, without writing it.
+    /// </summary>
+    private static EncryptedWavWriter ResumeNearTheSizeLimit(SparseStream target, int roomLeft)
+    {
+        var encryptor = new FramingOnlyEncryptor();
+        var config = DefaultConfig with { Channels = 1, Encryptor = encryptor, EncryptionChunkSize = 4800 };
+        using (var first = new EncryptedWavWriter(target, encryptor, leaveOpen: true))
+        {
+            first.Open(config);
+            first.Close();
+        }
+
+        var frameLength = (uint)(EncryptedWavWriter.MaxWavBytes - 44 - 4 - roomLeft);
+        target.Seek(44, SeekOrigin.Begin);
+        target.Write(BitConverter.GetBytes(frameLength));
+        target.SetLength(44 + 4 + frameLength);
+
+        // Not disposed: closing would checksum the whole file.
+        var writer = new EncryptedWavWriter(target, encryptor, leaveOpen: true);
+        writer.OpenAppend(config);
+        return writer;
+    }
+
+    private static void AssertSizesPatched(SparseStream target, EncryptedWavWriter writer)
+    {
+        var header = new byte[44];
+        target.Seek(0, SeekOrigin.Begin);
+        target.ReadExactly(header);
+        Assert.True(writer.BytesWritten <= EncryptedWavWriter.MaxWavBytes);
+        Assert.Equal(target.Length, writer.BytesWritten);
+        Assert.Equal((uint)(writer.BytesWritten - 8), BitConverter.ToUInt32(header, 4));
+        Assert.Equal((uint)(writer.BytesWritten - 44), BitConverter.ToUInt32(header, 40));
+    }
+
+    /// <summary>
+    /// A stream of any length that holds only what was written to it, reading zeros
+    /// elsewhere — so a file at the 4 GB limit costs next to no memory.
+    /// </summary>
+    private sealed class SparseStream : Stream
+    {
+        // Writes in order; a later one covers an earlier one where they overlap.
+        private readonly List<(long Offset, byte[] Bytes)> _written = [];
+        private long _length;
+
+        public override bool CanRead => true;
+        public override bool CanSeek => true;
+        public override bool CanWrite => true;
+        public override long Length => _length;
+        public override long Position { get; set; }
+
+        public override int Read(byte[] buffer, int offset, int count)
+        {
+            count = (int)Math.Clamp(_length - Position, 0, count);
+            Array.Clear(buffer, offset, count);
+            foreach (var (start, bytes) in _written)
+            {
+                var from = Math.Max(start, Position);
+                var to = Math.Min(start + bytes.Length, Position + count);
+                if (from < to)
+                    Array.Copy(bytes, from - start, buffer, offset + (from - Position), to - from);
+            }
+            Position += count;
+            return count;
+        }
+
+        public override void Write(byte[] buffer, int offset, int count)
+        {
+            _written.Add((Position, buffer.AsSpan(offset, count).ToArray()));
+            Position += count;
+            _length = Math.Max(_length, Position);
+        }
+
+        public override long Seek(long offset, SeekOrigin origin) => Position = origin switch
+        {
+            SeekOrigin.Begin => offset,
+            SeekOrigin.Current => Position + offset,
+            _ => _length + offset,
+        };
+
+        public override void SetLength(long value)
+        {
+            for (int i = _written.Count - 1; i >= 0; i--)
+            {
+                var (start, bytes) = _written[i];
+                if (start >= value)
+                    _written.RemoveAt(i);
+                else if (start + bytes.Length > value)
+                    _written[i] = (start, bytes[..(int)(value - start)]);
+            }
+            _length = value;
+        }
+
+        public override void Flush() { }
+    }
+
+    /// <summary>
+    /// Frames plaintext as a sealed box would — nonce, data, tag — without the
+    /// randomness, so two recordings of the same audio come out identical.
+    /// </summary>
+    private sealed class FramingOnlyEncryptor : ICaptureEncryptor
+    {
+        public string Algorithm => "test-framing";
+        public Dictionary<string, string> KeyMetadata => [];
+
+        public byte[] Encrypt(byte[] data) => [.. new byte[12], .. data, .. new byte[16]];
+    }
+
+    /// <summary>A seekable sink that refuses reads, like a network-backed upload buffer.</summary>
+    private sealed class WriteOnlyStream : MemoryStream
+    {
+        public override bool CanRead => false;
+    }
 }
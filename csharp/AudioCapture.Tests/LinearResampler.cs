@@ -0,0 +1,25 @@
+using AudioCapture.Interfaces;
+
+namespace AudioCapture.Tests;
+
+/// <summary>Straight-line interpolation between input frames: cheap, and full of images.</summary>
+internal sealed class LinearResampler : IAudioResampler
+{
+    public float[] Process(float[] input, int channels, int inputRate, int outputRate)
+    {
+        var inFrames = input.Length / channels;
+        var outFrames = (int)((long)inFrames * outputRate / inputRate);
+        var output = new float[outFrames * channels];
+        for (int i = 0; i < outFrames; i++)
+        {
+            var position = (double)i * inputRate / outputRate;
+            var index = (int)position;
+            var next = Math.Min(index + 1, inFrames - 1);
+            var fraction = (float)(position - index);
+            for (int ch = 0; ch < channels; ch++)
+                output[i * channels + ch] = input[index * channels + ch]
+                    + fraction * (input[next * channels + ch] - input[index * channels + ch]);
+        }
+        return output;
+    }
+}
@@ -0,0 +1,64 @@
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class MeterBallisticsTests
+{
+    private static readonly MeterBallisticsConfiguration Config = new()
+    {
+        Attack = TimeSpan.FromMilliseconds(10),
+        Release = TimeSpan.FromMilliseconds(300),
+    };
+
+    private static readonly TimeSpan Callback = TimeSpan.FromMilliseconds(10);
+
+    [Fact]
+    public void RisesOverTheAttackAndFallsOverTheRelease()
+    {
+        var meter = new MeterBallistics(Config);
+
+        // One attack time in, ~63% of the step.
+        Assert.Equal(0.5f * 0.632f, meter.Update(0.5f, Callback), 0.001f);
+
+        for (var i = 0; i < 20; i++)
+            meter.Update(0.5f, Callback);
+        Assert.Equal(0.5f, meter.Current, 0.001f);
+
+        // The same 10 ms of silence barely moves it on the way down.
+        var afterDrop = meter.Update(0f, Callback);
+        Assert.Equal(0.5f * MathF.Exp(-10f / 300f), afterDrop, 0.001f);
+    }
+
+    [Fact]
+    public void IrregularCallbacksSmoothTheSameAsEvenOnes()
+    {
+        var even = new MeterBallistics(Config);
+        var uneven = new MeterBallistics(Config);
+
+        even.Update(0.8f, TimeSpan.FromMilliseconds(5));
+        even.Update(0.8f, TimeSpan.FromMilliseconds(5));
+        uneven.Update(0.8f, TimeSpan.FromMilliseconds(10));
+
+        Assert.Equal(even.Current, uneven.Current, 0.0001f);
+    }
+
+    [Fact]
+    public void ResetDropsToZero()
+    {
+        var meter = new MeterBallistics(Config);
+        meter.Update(0.5f, Callback);
+
+        meter.Reset();
+
+        Assert.Equal(0f, meter.Current);
+    }
+
+    [Fact]
+    public void RejectsNonPositiveTimes()
+    {
+        Assert.Throws<ArgumentOutOfRangeException>(() =>
+            new MeterBallistics(Config with { Release = TimeSpan.Zero }));
+    }
+}
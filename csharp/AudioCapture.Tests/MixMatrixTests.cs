@@ -0,0 +1,102 @@
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class MixMatrixTests
+{
+    private readonly StereoMixer _mixer = new();
+
+    [Theory]
+    [InlineData(MixingStrategy.Blended)]
+    [InlineData(MixingStrategy.Separated)]
+    public void Stereo_MatchesStereoMixer(MixingStrategy strategy)
+    {
+        float[] mic = [0.5f, -0.25f];
+        float[] system = [0.3f, 0.7f, -0.1f, 0.2f];
+
+        var matrix = MixMatrix.ForLayout(2, strategy).Mix(mic, system);
+        var stereo = _mixer.Mix(mic, system, strategy);
+
+        Assert.Equal(stereo.Length, matrix.Length);
+        for (int i = 0; i < stereo.Length; i++)
+            Assert.Equal(stereo[i], matrix[i], 0.0001f);
+    }
+
+    [Fact]
+    public void Surround51_MicOnCentre_SystemOnFrontPair()
+    {
+        float[] mic = [0.5f];
+        float[] system = [0.3f, 0.7f];
+
+        var result = MixMatrix.ForLayout(6).Mix(mic, system);
+
+        // FL FR FC LFE BL BR
+        Assert.Equal(new[] { 0.3f, 0.7f, 0.5f, 0f, 0f, 0f }, result);
+    }
+
+    [Fact]
+    public void Quad_MicOnRears_SystemOnFrontPair()
+    {
+        float[] mic = [0.5f];
+        float[] system = [0.3f, 0.7f];
+
+        var result = MixMatrix.ForLayout(4).Mix(mic, system);
+
+        // FL FR BL BR
+        Assert.Equal(new[] { 0.3f, 0.7f, 0.5f, 0.5f }, result);
+    }
+
+    [Fact]
+    public void Multitrack_KeepsSourcesOnDiscreteChannels()
+    {
+        float[] mic = [0.5f];
+        float[] system = [0.3f, 0.7f];
+
+        var matrix = MixMatrix.ForLayout(4, MixingStrategy.Multichannel);
+
+        // mic, mic, system L, system R — and no speaker positions
+        Assert.Equal(new[] { 0.5f, 0.5f, 0.3f, 0.7f }, matrix.Mix(mic, system));
+        Assert.Equal(0u, matrix.ChannelMask);
+    }
+
+    [Fact]
+    public void Mono_SumsMicWithSystemFold()
+    {
+        var result = MixMatrix.ForLayout(1).Mix([0.25f], [0.3f, 0.7f]);
+
+        Assert.Single(result);
+        Assert.Equal(0.75f, result[0], 0.0001f); // 0.25 + (0.3 + 0.7) / 2
+    }
+
+    [Fact]
+    public void ShorterInput_IsZeroPadded()
+    {
+        var result = MixMatrix.ForLayout(6).Mix([0.5f, 0.5f], [0.3f, 0.7f]);
+
+        Assert.Equal(12, result.Length);
+        Assert.Equal(new[] { 0f, 0f, 0.5f, 0f, 0f, 0f }, result[6..]);
+    }
+
+    [Theory]
+    [InlineData(2, 0x3u)]
+    [InlineData(4, 0x33u)]
+    [InlineData(6, 0x3Fu)]
+    public void ChannelMask_MatchesOutputChannels(int channels, uint mask)
+    {
+        var matrix = MixMatrix.ForLayout(channels);
+
+        Assert.Equal(channels, matrix.OutputChannels);
+        Assert.Equal(mask, matrix.ChannelMask);
+        Assert.Equal(channels, System.Numerics.BitOperations.PopCount(mask));
+    }
+
+    [Theory]
+    [InlineData(3)]
+    [InlineData(8)]
+    public void ForLayout_RejectsUnsupportedChannelCounts(int channels)
+    {
+        Assert.Throws<ArgumentOutOfRangeException>(() => MixMatrix.ForLayout(channels));
+    }
+}
@@ -0,0 +1,75 @@
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class NoiseGateTests
+{
+    private const int SampleRate = 48000;
+
+    private static readonly GateConfiguration Config = new()
+    {
+        ThresholdDbfs = -40f,
+        Attack = TimeSpan.FromMilliseconds(5),
+        Hold = TimeSpan.FromMilliseconds(100),
+        Release = TimeSpan.FromMilliseconds(50),
+        FloorDb = -30f,
+    };
+
+    [Fact]
+    public void Speech_PassesThroughAndTheSubThresholdTailIsAttenuated()
+    {
+        var gate = new NoiseGate(Config, SampleRate);
+        var speech = Tone(amplitude: 0.3f, seconds: 1);
+        var tail = Tone(amplitude: 0.003f, seconds: 1); // ~ -50 dBFS room noise
+
+        var speechIn = Rms(speech[^(SampleRate / 4)..]);
+        var tailIn = Rms(tail[^(SampleRate / 4)..]);
+        gate.Process(speech);
+        gate.Process(tail);
+
+        Assert.Equal(speechIn, Rms(speech[^(SampleRate / 4)..]), 0.01f);
+        Assert.Equal(tailIn * 0.0316f, Rms(tail[^(SampleRate / 4)..]), 0.00001f); // -30 dB
+    }
+
+    [Fact]
+    public void HoldBridgesAShortPauseBetweenWords()
+    {
+        var gate = new NoiseGate(Config, SampleRate);
+        gate.Process(Tone(amplitude: 0.3f, seconds: 0.5));
+
+        var pause = Tone(amplitude: 0.003f, seconds: 0.05);
+        gate.Process(pause);
+
+        Assert.True(gate.CurrentGain > 0.99f);
+    }
+
+    [Fact]
+    public void Closing_FadesRatherThanSteps()
+    {
+        var gate = new NoiseGate(Config, SampleRate);
+        gate.Process(Tone(amplitude: 0.3f, seconds: 0.5));
+
+        // Past the hold and a little into the release: partway down, not at the floor.
+        gate.Process(new float[(int)(SampleRate * 0.2)]);
+
+        Assert.InRange(gate.CurrentGain, 0.1f, 0.9f);
+    }
+
+    private static float[] Tone(float amplitude, double seconds)
+    {
+        var samples = new float[(int)(SampleRate * seconds)];
+        for (int i = 0; i < samples.Length; i++)
+            samples[i] = amplitude * (float)Math.Sin(2 * Math.PI * 440 * i / SampleRate);
+        return samples;
+    }
+
+    private static float Rms(float[] samples)
+    {
+        double sum = 0;
+        foreach (var s in samples)
+            sum += s * s;
+        return (float)Math.Sqrt(sum / samples.Length);
+    }
+}
@@ -0,0 +1,46 @@
+using AudioCapture.Models;
+using AudioCapture.Storage;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class PassphraseEncryptorTests
+{
+    private const string Passphrase = "correct horse battery staple";
+
+    [Fact]
+    public void RoundTrip_ThroughTheKeyMetadata()
+    {
+        using var encryptor = new PassphraseEncryptor(Passphrase, iterations: 1000);
+        var plaintext = new byte[] { 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 };
+
+        var sealedBox = encryptor.Encrypt(plaintext);
+        using var reopened = PassphraseEncryptor.FromKeyMetadata(Passphrase, encryptor.KeyMetadata);
+
+        Assert.Equal(plaintext, reopened.Cipher.Decrypt(sealedBox));
+        Assert.Equal(encryptor.KeyMetadata, reopened.KeyMetadata);
+    }
+
+    [Fact]
+    public void EachInstance_DrawsItsOwnSalt()
+    {
+        using var first = new PassphraseEncryptor(Passphrase, iterations: 1000);
+        using var second = new PassphraseEncryptor(Passphrase, iterations: 1000);
+
+        Assert.Equal("PBKDF2-HMAC-SHA256", first.KeyMetadata["kdf"]);
+        Assert.Equal("1000", first.KeyMetadata["kdfIterations"]);
+        Assert.NotEqual(first.KeyMetadata["kdfSalt"], second.KeyMetadata["kdfSalt"]);
+        // Same passphrase, different salt: a different key.
+        Assert.ThrowsAny<Exception>(() => second.Cipher.Decrypt(first.Encrypt([1, 2, 3])));
+    }
+
+    [Fact]
+    public void FromKeyMetadata_RejectsMetadataOfAnotherKind()
+    {
+        using var raw = new AesGcmEncryptor(new byte[32]);
+
+        var error = Assert.Throws<CaptureException>(() => PassphraseEncryptor.FromKeyMetadata(Passphrase, raw.KeyMetadata));
+
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, error.ErrorKind);
+    }
+}
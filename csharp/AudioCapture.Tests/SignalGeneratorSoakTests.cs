@@ -172,6 +172,46 @@ public class SignalGeneratorSoakTests : IDisposable
                 + "— a burst that sounded while paused reached the recording");
     }
 
+    /// <summary>
+    /// Two devices never share a clock. Here the mic runs ~2% fast (49 kHz delivered
+    /// into a 48 kHz pipeline) — far worse than real hardware, so the effect shows in
+    /// seconds rather than hours. The drift must be measured, and the session must trim
+    /// the surplus as it goes rather than let it pile up toward the stall guard.
+    /// </summary>
+    [Fact]
+    public async Task ClockDriftBetweenSourcesIsMeasuredAndTrimmed()
+    {
+        using var session = new WasapiCaptureSession(
+            () => new SignalGeneratorWaveIn(new NAudio.Wave.WaveFormat(49000, 16, 1), MicTone),
+            () => SignalGeneratorWaveIn.StereoFloat(SystemTone, SampleRate));
+
+        var config = new CaptureConfiguration
+        {
+            SampleRate = SampleRate,
+            BitDepth = 16,
+            Channels = 2,
+            OutputDirectory = _tempDir,
+            DeviceBufferDuration = TimeSpan.FromMilliseconds(20),
+        };
+        session.Configure(config);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(TimeSpan.FromSeconds(4));
+        await session.StopCaptureAsync();
+        await capture;
+
+        var diagnostics = session.Diagnostics;
+        var tolerance = (int)(SampleRate * config.DeviceBufferDuration.TotalSeconds * 2);
+
+        // ~1000 frames of drift per second, give or take start-up skew.
+        Assert.InRange(diagnostics.SourceDriftFrames, 2500, 5500);
+        // Everything past the jitter tolerance was trimmed, less what arrived since the last cycle.
+        Assert.True(
+            diagnostics.DriftCorrectedFrames >= diagnostics.SourceDriftFrames - tolerance - 1500,
+            $"drift {diagnostics.SourceDriftFrames}, corrected {diagnostics.DriftCorrectedFrames}");
+        Assert.True(diagnostics.DriftCorrectedFrames > 0);
+    }
+
     // --- Assertions ---
 
     private static void AssertBytesWrittenMonotonic(IReadOnlyList<long> samples)
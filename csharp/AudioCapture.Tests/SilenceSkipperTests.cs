@@ -0,0 +1,80 @@
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using Xunit;
+
+namespace AudioCapture.Tests;
+
+public class SilenceSkipperTests
+{
+    private const int SampleRate = 48000;
+
+    private static readonly SilenceSkipConfiguration Config = new()
+    {
+        ThresholdDbfs = -50f,
+        Hold = TimeSpan.FromSeconds(1),
+    };
+
+    [Fact]
+    public void LongSilence_IsCutAfterTheHoldAndRecordedAsAGap()
+    {
+        var skipper = new SilenceSkipper(Config, SampleRate, channels: 1);
+
+        var kept = skipper.Process(Tone(seconds: 1))
+            + skipper.Process(new float[SampleRate * 5])
+            + skipper.Process(Tone(seconds: 1));
+
+        Assert.Equal(SampleRate * 3, kept); // tone, the hold's second of silence, tone
+        var gap = Assert.Single(skipper.Gaps);
+        Assert.Equal(new SkippedGap(StartSecs: 2, FileSecs: 2, DurationSecs: 4), gap);
+    }
+
+    [Fact]
+    public void SilenceShorterThanTheHold_IsKept()
+    {
+        var skipper = new SilenceSkipper(Config, SampleRate, channels: 2);
+
+        var kept = skipper.Process(Tone(seconds: 1, channels: 2))
+            + skipper.Process(new float[SampleRate]) // half a second of stereo
+            + skipper.Process(Tone(seconds: 1, channels: 2));
+
+        Assert.Equal(SampleRate * 2 * 2 + SampleRate, kept);
+        Assert.Empty(skipper.Gaps);
+    }
+
+    [Fact]
+    public void KeptBlocks_AreMovedToTheFrontInOrder()
+    {
+        var skipper = new SilenceSkipper(Config with { Hold = TimeSpan.Zero }, SampleRate, channels: 1);
+        var samples = new float[SampleRate / 10]; // ten blocks
+        samples.AsSpan(0, SampleRate / 100).Fill(0.5f);
+        samples.AsSpan(SampleRate / 20, SampleRate / 100).Fill(0.25f);
+
+        var kept = skipper.Process(samples);
+
+        Assert.Equal(SampleRate / 50, kept);
+        Assert.All(samples[..(SampleRate / 100)], s => Assert.Equal(0.5f, s));
+        Assert.All(samples[(SampleRate / 100)..kept], s => Assert.Equal(0.25f, s));
+    }
+
+    [Fact]
+    public void GapOpenAtTheEnd_IsClosedByFinish()
+    {
+        var skipper = new SilenceSkipper(Config, SampleRate, channels: 1);
+        skipper.Process(Tone(seconds: 1));
+        skipper.Process(new float[SampleRate * 3]);
+        Assert.True(skipper.IsSkipping);
+        Assert.Empty(skipper.Gaps);
+
+        skipper.Finish();
+
+        Assert.Equal(new SkippedGap(StartSecs: 2, FileSecs: 2, DurationSecs: 2), Assert.Single(skipper.Gaps));
+    }
+
+    private static float[] Tone(double seconds, int channels = 1)
+    {
+        var samples = new float[(int)(SampleRate * seconds) * channels];
+        for (int i = 0; i < samples.Length; i++)
+            samples[i] = 0.3f * (float)Math.Sin(2 * Math.PI * 440 * (i / channels) / SampleRate);
+        return samples;
+    }
+}
@@ -21,6 +21,24 @@ public class StereoMixerTests
         Assert.Equal(0.9f, result[1], 0.001f); // R = 0.5 + 0.4
     }
 
+    [Theory]
+    [InlineData(MicPlacement.Center, 0.8f, 0.9f)] // L = 0.5 + 0.3, R = 0.5 + 0.4
+    [InlineData(MicPlacement.Left, 0.8f, 0.4f)]   // L = 0.5 + 0.3, R = 0.4
+    [InlineData(MicPlacement.Right, 0.3f, 0.9f)]  // L = 0.3,       R = 0.5 + 0.4
+    public void Blended_MicPlacement_PutsTheMicOnlyWhereAsked(MicPlacement placement, float left, float right)
+    {
+        float[] mic = [0.5f];
+        float[] system = [0.3f, 0.4f];
+
+        var result = _mixer.Mix(mic, system, MixingStrategy.Blended, placement);
+        var scratch = Array.Empty<float>();
+        var written = _mixer.MixInto(mic, system, MixingStrategy.Blended, ref scratch, placement);
+
+        Assert.Equal(left, result[0], 0.001f);
+        Assert.Equal(right, result[1], 0.001f);
+        Assert.Equal(result, scratch[..written]);
+    }
+
     [Fact]
     public void Separated_MicOnLeft_SystemMonoFoldOnRight()
     {
@@ -34,6 +52,55 @@ public class StereoMixerTests
         Assert.Equal(0.5f, result[1], 0.001f);  // R = (0.3+0.7)/2 = 0.5
     }
 
+    [Fact]
+    public void Interleave_PadsTheShorterChannel()
+    {
+        var result = _mixer.Interleave([0.1f, 0.2f], [0.3f]);
+
+        Assert.Equal([0.1f, 0.3f, 0.2f, 0f], result);
+    }
+
+    [Fact]
+    public void Deinterleave_SplitsEachChannelAndPadsAPartialFrame()
+    {
+        float[] interleaved = [1f, 2f, 3f, 4f, 5f, 6f, 7f];
+
+        var planes = StereoMixer.Deinterleave(interleaved, channels: 3);
+
+        Assert.Equal(3, planes.Length);
+        Assert.Equal([1f, 4f, 7f], planes[0]);
+        Assert.Equal([2f, 5f, 0f], planes[1]);
+        Assert.Equal([3f, 6f, 0f], planes[2]);
+    }
+
+    [Theory]
+    [InlineData(2, 4)]
+    [InlineData(4, 2)]
+    [InlineData(2, 0)]
+    public void InterleavePlanar_OfTwoChannels_MatchesInterleave(int leftFrames, int rightFrames)
+    {
+        var left = Enumerable.Range(1, leftFrames).Select(i => (float)i).ToArray();
+        var right = Enumerable.Range(5, rightFrames).Select(i => (float)i).ToArray();
+
+        Assert.Equal(_mixer.Interleave(left, right), StereoMixer.InterleavePlanar([left, right]));
+    }
+
+    [Fact]
+    public void InterleavePlanar_RoundTripsDeinterleave()
+    {
+        var interleaved = Enumerable.Range(0, 6 * 100).Select(i => i / 600f).ToArray();
+
+        var planes = StereoMixer.Deinterleave(interleaved, channels: 6);
+
+        Assert.Equal(interleaved, StereoMixer.InterleavePlanar(planes));
+    }
+
+    [Fact]
+    public void InterleavePlanar_NoChannels_ReturnsEmpty()
+    {
+        Assert.Empty(StereoMixer.InterleavePlanar([]));
+    }
+
     [Fact]
     public void Mix_EmptyInputs_ReturnsEmpty()
     {
@@ -106,6 +173,15 @@ public class StereoMixerTests
         Assert.Equal(-short.MaxValue, BitConverter.ToInt16(pcm, 8));
     }
 
+    [Fact]
+    public void CountClipped_CountsOnlySamplesBeyondFullScale()
+    {
+        float[] samples = [0f, 1f, -1f, 1.5f, -1.5f, 0.99f, 2f];
+
+        Assert.Equal(3, StereoMixer.CountClipped(samples));
+        Assert.Equal(0, StereoMixer.CountClipped([]));
+    }
+
     [Fact]
     public void Mix_WithStrategyParameter_DispatchesCorrectly()
     {
@@ -121,4 +197,126 @@ public class StereoMixerTests
         Assert.Equal(0.5f, separated[0], 0.001f);
         Assert.Equal(0.5f, separated[1], 0.001f);
     }
+
+    [Fact]
+    public void MixInto_MatchesMixAndReusesTheBuffer()
+    {
+        float[] mic = [0.5f, -0.25f];
+        float[] system = [0.1f, 0.2f, 0.3f, 0.4f];
+        var scratch = new float[64];
+        var original = scratch;
+
+        var written = _mixer.MixInto(mic, system, MixingStrategy.Separated, ref scratch);
+
+        Assert.Same(original, scratch);
+        Assert.Equal(_mixer.Mix(mic, system, MixingStrategy.Separated), scratch[..written]);
+    }
+
+    [Fact]
+    public void MixInto_GrowsAnUndersizedBuffer()
+    {
+        float[] mic = [0.5f, 0.5f, 0.5f];
+        var scratch = Array.Empty<float>();
+
+        var written = _mixer.MixInto(mic, [], MixingStrategy.Blended, ref scratch);
+
+        Assert.Equal(6, written);
+        Assert.True(scratch.Length >= 6);
+    }
+
+    [Fact]
+    public void SteadyStateMixAndConvert_AllocateNothing()
+    {
+        var mic = new float[4800];
+        var system = new float[9600];
+        var mixScratch = Array.Empty<float>();
+        var pcmScratch = Array.Empty<byte>();
+
+        // First cycle sizes the scratch; every cycle after should reuse it.
+        var mixed = _mixer.MixInto(mic, system, MixingStrategy.Blended, ref mixScratch);
+        StereoMixer.ConvertToInt16PcmInto(mixScratch.AsSpan(0, mixed), ref pcmScratch);
+
+        var before = GC.GetAllocatedBytesForCurrentThread();
+        for (int i = 0; i < 100; i++)
+        {
+            mixed = _mixer.MixInto(mic, system, MixingStrategy.Blended, ref mixScratch);
+            StereoMixer.ConvertToInt16PcmInto(mixScratch.AsSpan(0, mixed), ref pcmScratch);
+        }
+
+        Assert.Equal(0, GC.GetAllocatedBytesForCurrentThread() - before);
+    }
+
+    [Fact]
+    public void ConvertToInt16PcmInto_MatchesConvertToInt16Pcm()
+    {
+        float[] samples = [0f, 0.5f, -1f, 2f];
+        var scratch = new byte[3];
+
+        var written = StereoMixer.ConvertToInt16PcmInto(samples, ref scratch);
+
+        Assert.Equal(StereoMixer.ConvertToInt16Pcm(samples), scratch[..written]);
+    }
+
+    [Fact]
+    public void TruePeak_CatchesTheInterSamplePeakASamplePeakMisses()
+    {
+        // A full-scale sine at a quarter of the sample rate, phased so every sample
+        // lands at ±0.707: the waveform's crests fall between samples.
+        var samples = Enumerable.Range(0, 480)
+            .Select(n => (float)Math.Sin(Math.PI / 2 * n + Math.PI / 4))
+            .ToArray();
+
+        var samplePeak = samples.Max(Math.Abs);
+        var truePeak = StereoMixer.TruePeakLevel(samples);
+
+        Assert.Equal(0.707f, samplePeak, 0.001f);
+        Assert.Equal(1f, truePeak, 0.03f);
+    }
+
+    [Fact]
+    public void TruePeak_MatchesTheSamplePeakWhenNothingHidesBetweenSamples()
+    {
+        // Interleaved stereo: a slow sine on the left, near-DC on the right.
+        var samples = new float[960];
+        for (int i = 0; i < 480; i++)
+        {
+            samples[i * 2] = 0.5f * (float)Math.Sin(2 * Math.PI * 100 * i / 48000.0);
+            samples[i * 2 + 1] = 0.25f;
+        }
+
+        Assert.Equal(0.5f, StereoMixer.TruePeakLevel(samples, channels: 2), 0.01f);
+    }
+
+    [Theory]
+    [InlineData(1f, 1f)]    // same signal both sides: mono
+    [InlineData(-1f, -1f)]  // right is left inverted: cancels when summed
+    [InlineData(0.5f, 1f)]  // level differences don't change the phase relationship
+    public void StereoCorrelation_ReadsThePhaseRelationship(float rightGain, float expected)
+    {
+        var stereo = new float[960];
+        for (int i = 0; i < 480; i++)
+        {
+            var sample = 0.5f * (float)Math.Sin(2 * Math.PI * 440 * i / 48000.0);
+            stereo[i * 2] = sample;
+            stereo[i * 2 + 1] = rightGain * sample;
+        }
+
+        Assert.Equal(expected, StereoMixer.StereoCorrelation(stereo), 0.001f);
+    }
+
+    [Fact]
+    public void StereoCorrelation_QuadratureAndSilenceReadZero()
+    {
+        // A sine against its cosine shares no phase over whole cycles.
+        var stereo = new float[960];
+        for (int i = 0; i < 480; i++)
+        {
+            var phase = 2 * Math.PI * 400 * i / 48000.0;
+            stereo[i * 2] = (float)Math.Sin(phase);
+            stereo[i * 2 + 1] = (float)Math.Cos(phase);
+        }
+
+        Assert.Equal(0f, StereoMixer.StereoCorrelation(stereo), 0.01f);
+        Assert.Equal(0f, StereoMixer.StereoCorrelation(new float[960]));
+    }
 }
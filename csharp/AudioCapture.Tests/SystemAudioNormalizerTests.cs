@@ -1,3 +1,4 @@
+using AudioCapture.Interfaces;
 using AudioCapture.Processing;
 using Xunit;
 
@@ -127,6 +128,23 @@ public class SystemAudioNormalizerTests
         Assert.InRange(total, Target * 0.99, Target * 1.01);
     }
 
+    [Fact]
+    public void At44_1kHz_AnHourOf48kHzLoopbackKeepsItsDuration()
+    {
+        // The common consumer case, over the length the drift would show in: an hour
+        // of a 48 kHz render device in 10 ms packets. Any per-packet rounding would
+        // add up to seconds; what remains is the resampler's fixed filter latency.
+        var normalizer = new SystemAudioNormalizer(48000, 2, 44100);
+        var packet = StereoSine(480, 48000);
+
+        long total = 0;
+        for (int i = 0; i < 3600 * 100; i++)
+            total += normalizer.Normalize(packet).Length / 2;
+
+        // Within 5 ms of 3600 s.
+        Assert.InRange(total, 3600L * 44100 - 220, 3600L * 44100 + 220);
+    }
+
     [Fact]
     public void MismatchedRate_DoesNotResetPerChunk()
     {
@@ -182,6 +200,30 @@ public class SystemAudioNormalizerTests
         Assert.InRange(total, Target * 0.99, Target * 1.01);
     }
 
+    [Fact]
+    public void InjectedResampler_ReplacesTheDefault()
+    {
+        var resampler = new NearestResampler();
+        var normalizer = new SystemAudioNormalizer(44100, 1, Target, resampler);
+
+        var output = normalizer.Normalize(new float[441]);
+
+        // Folded to stereo before it reaches the resampler, which alone sets the length.
+        Assert.Equal((2, 44100, Target), resampler.LastCall);
+        Assert.Equal(480 * 2, output.Length);
+    }
+
+    [Fact]
+    public void InjectedResampler_IsUnusedAtTheTargetRate()
+    {
+        var resampler = new NearestResampler();
+        var normalizer = new SystemAudioNormalizer(Target, 2, Target, resampler);
+
+        normalizer.Normalize(StereoSine(480, Target));
+
+        Assert.Null(resampler.LastCall);
+    }
+
     [Theory]
     [InlineData(0, 2, 48000)]
     [InlineData(44100, 0, 48000)]
@@ -191,4 +233,25 @@ public class SystemAudioNormalizerTests
         Assert.Throws<ArgumentOutOfRangeException>(
             () => new SystemAudioNormalizer(sourceRate, sourceChannels, targetRate));
     }
+
+    /// <summary>Picks the nearest input frame for each output frame, and records how it was called.</summary>
+    private sealed class NearestResampler : IAudioResampler
+    {
+        public (int Channels, int InputRate, int OutputRate)? LastCall { get; private set; }
+
+        public float[] Process(float[] input, int channels, int inputRate, int outputRate)
+        {
+            LastCall = (channels, inputRate, outputRate);
+            var inFrames = input.Length / channels;
+            var outFrames = (int)((long)inFrames * outputRate / inputRate);
+            var output = new float[outFrames * channels];
+            for (int i = 0; i < outFrames; i++)
+            {
+                var source = (int)((long)i * inputRate / outputRate);
+                for (int ch = 0; ch < channels; ch++)
+                    output[i * channels + ch] = input[source * channels + ch];
+            }
+            return output;
+        }
+    }
 }
@@ -1,5 +1,12 @@
+using System.Runtime.InteropServices;
+using System.Security.Cryptography;
+using System.Text.Json;
+using System.Threading.Channels;
 using AudioCapture.Capture;
+using AudioCapture.Interfaces;
 using AudioCapture.Models;
+using AudioCapture.Storage;
+using NAudio.CoreAudioApi;
 using NAudio.Wave;
 using Xunit;
 
@@ -35,14 +42,14 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         OutputDirectory = _tempDir,
     };
 
-    private string WriteFixture(string name, int channels = 1, double seconds = 0.3)
+    private string WriteFixture(string name, int channels = 1, double seconds = 0.3, double frequency = 440)
     {
         var path = Path.Combine(_tempDir, name);
         using var writer = new WaveFileWriter(path, new WaveFormat(48000, 16, channels));
         var frames = (int)(48000 * seconds);
         for (int i = 0; i < frames; i++)
         {
-            var sample = (float)(Math.Sin(2 * Math.PI * 440 * i / 48000) * 0.5);
+            var sample = (float)(Math.Sin(2 * Math.PI * frequency * i / 48000) * 0.5);
             for (int c = 0; c < channels; c++)
                 writer.WriteSample(sample);
         }
@@ -93,6 +100,89 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         Assert.InRange(ratio, 1.95, 2.05);
     }
 
+    [Fact]
+    public async Task At44_1kHz_48kHzSourcesKeepTheirDuration()
+    {
+        // The common consumer case: 44.1 kHz output from a 48 kHz render device and a
+        // 48 kHz mic. The mic goes through the composite, as it does when the session
+        // resamples it; loopback through the normalizer. Any per-chunk rounding in
+        // either would surface as a file longer or shorter than the sources.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 10);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 10);
+
+        using var session = new WasapiCaptureSession(
+            () => new CompositeMicWaveIn([FileWaveIn.Mono16(micFixture, speedFactor: 20)], 44100, gains: [1f]),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+        session.Configure(DefaultConfig with { SampleRate = 44100 });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(TimeSpan.FromSeconds(1.5)); // 10s at 20x, with room to spare
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(44100, result.Spec!.SampleRate);
+        using var reader = new WaveFileReader(result.FilePath);
+        Assert.Equal(44100, reader.WaveFormat.SampleRate);
+        // Within 5 ms of the sources' 10 s: resampler latency, not drift.
+        Assert.InRange(reader.SampleCount, 441000 - 220, 441000 + 220);
+    }
+
+    [Fact]
+    public async Task DeliverySampleRate_WritesA16kHzFileFrom48kHzCapture()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 10);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 10);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+        session.Configure(DefaultConfig with { DeliverySampleRate = 16000 });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(TimeSpan.FromSeconds(1.5)); // 10s at 20x, with room to spare
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(new WavSpec(16000, 2, 16, IsFloat: false), result.Spec);
+        using var reader = new WaveFileReader(result.FilePath);
+        Assert.Equal(16000, reader.WaveFormat.SampleRate);
+        // A third of the 480,000 frames captured, within 10 ms of resampler latency.
+        Assert.InRange(reader.SampleCount, 160000 - 160, 160000 + 160);
+    }
+
+    [Theory]
+    [InlineData(1000, true)]
+    [InlineData(6000, false)] // above 4 kHz: unfiltered, it would fold back to 2 kHz
+    public async Task At8kHzMono_InBandTonesPassAndOutOfBandOnesDontAlias(double frequency, bool passes)
+    {
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 10, frequency: frequency);
+
+        using var session = new WasapiCaptureSession(
+            micFactory: null, () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+        session.Configure(DefaultConfig with { SampleRate = 8000, Channels = 1, EnableMicCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(TimeSpan.FromSeconds(1.5)); // 10s at 20x, with room to spare
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(new WavSpec(8000, 1, 16, IsFloat: false), result.Spec);
+        using var reader = new WaveFileReader(result.FilePath);
+        // Within 10 ms of the source's 10 s.
+        Assert.InRange(reader.SampleCount, 80000 - 80, 80000 + 80);
+
+        var samples = reader.ToSampleProvider();
+        var buffer = new float[(int)reader.SampleCount];
+        var read = samples.Read(buffer, 0, buffer.Length);
+        // Skip the first and last 100 ms, where the filter is filling and draining.
+        var steady = buffer.AsSpan(800, read - 1600);
+        var rms = Math.Sqrt(steady.ToArray().Average(x => (double)x * x));
+        if (passes)
+            Assert.InRange(rms, 0.2, 0.5);
+        else
+            Assert.InRange(rms, 0, 0.005); // over 35 dB down on the tone's own 0.35
+    }
+
     [Fact]
     public async Task SystemEndpointAtTheConfiguredRate_IsLeftAlone()
     {
@@ -116,6 +206,148 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         Assert.False(diagnostics.SystemNormalized, "a matching endpoint should not be resampled");
     }
 
+    [Fact]
+    public async Task MonoSystemEndpoint_IsCountedInFramesAndWrittenAsStereo()
+    {
+        // A mono render device delivers one sample per frame. Read as stereo, the
+        // file would come out half as long and the two legs would interleave time.
+        var systemFixture = WriteFixture("system.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            micFactory: null,
+            () => new FileWaveIn(systemFixture, WaveFormat.CreateIeeeFloatWaveFormat(48000, 1), speedFactor: 20));
+        session.Configure(DefaultConfig with { EnableMicCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(1, session.Diagnostics.SystemSourceChannels);
+        var wav = MarkerAnalysis.ReadWavChannels(result.FilePath);
+        Assert.Equal(14400, wav.Left.Length); // 0.3 s of 48 kHz frames
+        Assert.Equal(wav.Left, wav.Right);
+        Assert.Contains(wav.Left, sample => Math.Abs(sample) > 0.1f);
+    }
+
+    [Fact]
+    public async Task LoudBlend_IsReportedAsClipped()
+    {
+        // Steady system audio near full scale under the mic tone: the blend goes past
+        // 1.0 whenever the tone's positive half adds to it, whatever the phase.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = Path.Combine(_tempDir, "loud.wav");
+        using (var writer = new WaveFileWriter(systemFixture, new WaveFormat(48000, 16, 2)))
+        {
+            for (int i = 0; i < 48000 * 0.3 * 2; i++)
+                writer.WriteSample(0.9f);
+        }
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.True(result.DidClip);
+        Assert.InRange(session.Diagnostics.ClippedSamples, 1000, long.MaxValue);
+    }
+
+    [Fact]
+    public async Task SourceWithNoSampleRate_IsRejectedOnItsFirstBuffer()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var broken = new ZeroRateWaveIn();
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, loop: true), () => broken);
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        Assert.True(broken.Stopped); // by the session, before anyone asked it to stop
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var error = Assert.Single(observer.Errors);
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+        Assert.Contains("System audio", error.Message);
+        Assert.Contains(MarkerAnalysis.ReadWavChannels(result.FilePath).Left, s => Math.Abs(s) > 0.1f); // the mic carried on
+    }
+
+    [Fact]
+    public async Task MultitrackOutput_KeepsMicAndSystemOnTheirOwnChannels()
+    {
+        // Different tones on each side, so a channel carrying the wrong source — or
+        // both summed — shows up as energy at the other's frequency.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3, frequency: 1000);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+        session.Configure(DefaultConfig with { Channels = 4, MixingStrategy = MixingStrategy.Multichannel });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        using var reader = new WaveFileReader(result.FilePath);
+        var format = Assert.IsType<WaveFormatExtensible>(reader.WaveFormat);
+        Assert.Equal(4, format.Channels);
+        var samples = reader.ToSampleProvider();
+        var channels = Enumerable.Range(0, 4).Select(_ => new List<float>()).ToArray();
+        var buffer = new float[4800 * 4];
+        int read;
+        while ((read = samples.Read(buffer, 0, buffer.Length)) > 0)
+        {
+            for (int i = 0; i < read; i++)
+                channels[i % 4].Add(buffer[i]);
+        }
+
+        double Level(int channel, double frequency) =>
+            MarkerAnalysis.GoertzelMagnitude(channels[channel].ToArray(), frequency, 48000);
+
+        Assert.All(channels, channel => Assert.NotEmpty(channel));
+        foreach (var mic in new[] { 0, 1 })
+        {
+            Assert.InRange(channels[mic].Max(s => Math.Abs(s)), 0.4f, 0.6f); // the mic tone, unsummed
+            Assert.True(Level(mic, 440) > 10 * Level(mic, 1000), $"channel {mic} should carry the mic only");
+        }
+        foreach (var system in new[] { 2, 3 })
+        {
+            Assert.InRange(channels[system].Max(s => Math.Abs(s)), 0.4f, 0.6f);
+            Assert.True(Level(system, 1000) > 10 * Level(system, 440), $"channel {system} should carry the system only");
+        }
+        Assert.Equal(ChannelLayout.Multitrack, result.Metadata.ChannelLayout);
+    }
+
+    [Fact]
+    public async Task ExcludeOwnAudio_LeavesAnInjectedSystemSourceAsItIs()
+    {
+        // The setting only changes how the real loopback is built. An injected source
+        // is the caller's choice, so it's used unchanged — and with nothing to
+        // downgrade, there's no "this Windows can't exclude" warning whatever the OS.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3, frequency: 1000);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig with { ExcludeOwnAudio = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Empty(observer.Errors);
+        Assert.True(session.Diagnostics.SystemChunks > 0);
+    }
+
     [Fact]
     public void Configure_WithInjectedMic_NeverResolvesADevice()
     {
@@ -139,6 +371,102 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         Assert.Equal(CaptureStateKind.Ready, session.State.Kind);
     }
 
+    [Fact]
+    public void Configure_RejectsAConfigurationWithNoSourcesEnabled()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), () => FileWaveIn.Mono16(fixture));
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableMicCapture = false,
+            EnableSystemCapture = false,
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+        Assert.Contains("No sources enabled", error.Message);
+    }
+
+    [Fact]
+    public void Configure_RejectsANonPositiveDeviceBuffer()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            DeviceBufferDuration = TimeSpan.Zero,
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+        Assert.Equal(CaptureStateKind.Failed, session.State.Kind);
+    }
+
+    [Fact]
+    public void Configure_FailsFastWhenTheOutputDirectoryCannotBeWritten()
+    {
+        var fixture = WriteFixture("mic.wav");
+        var blocker = Path.Combine(_tempDir, "not-a-directory");
+        File.WriteAllText(blocker, "");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            OutputDirectory = Path.Combine(blocker, "recordings"),
+        }));
+
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+        Assert.Equal(CaptureStateKind.Failed, session.State.Kind);
+    }
+
+    [Fact]
+    public void Configure_LeavesNoProbeFileBehind()
+    {
+        var fixture = WriteFixture("mic.wav");
+        var output = Path.Combine(_tempDir, "fresh", "recordings");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, OutputDirectory = output });
+
+        Assert.True(Directory.Exists(output));
+        Assert.Empty(Directory.GetFileSystemEntries(output));
+    }
+
+    [Fact]
+    public async Task SetOutputDirectory_RedirectsTheNextRecording()
+    {
+        var fixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var meeting = Path.Combine(_tempDir, "meeting-42");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture, speedFactor: 20), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        session.SetOutputDirectory(meeting);
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(200);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(meeting, Path.GetDirectoryName(result.FilePath));
+        Assert.True(File.Exists(result.FilePath));
+    }
+
+    [Fact]
+    public void SetOutputDirectory_RejectsAnUnwritableDirectoryAndStaysReady()
+    {
+        var fixture = WriteFixture("mic.wav");
+        var blocker = Path.Combine(_tempDir, "not-a-directory");
+        File.WriteAllText(blocker, "");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        var error = Assert.Throws<CaptureException>(() => session.SetOutputDirectory(Path.Combine(blocker, "recordings")));
+
+        Assert.Equal(CaptureErrorKind.StorageError, error.ErrorKind);
+        Assert.Equal(CaptureStateKind.Ready, session.State.Kind);
+    }
+
     [Fact]
     public async Task InjectedSources_ProduceRecordingAndSidecars()
     {
@@ -194,6 +522,65 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         Assert.Equal(0, diagnostics.MixErrors);
     }
 
+    [Fact]
+    public async Task ResetDiagnostics_CountsAfreshWhileLifetimeKeepsTheTotals()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(400);
+        var beforeReset = session.Diagnostics;
+        session.ResetDiagnostics();
+        await Task.Delay(400);
+        await session.StopCaptureAsync();
+        await capture;
+
+        var segment = session.Diagnostics;
+        var lifetime = session.LifetimeDiagnostics;
+        Assert.True(beforeReset.MicChunks > 0 && beforeReset.BytesWritten > 0);
+        Assert.InRange(segment.MicChunks, 1, lifetime.MicChunks - beforeReset.MicChunks);
+        Assert.InRange(segment.BytesWritten, 1, lifetime.BytesWritten - beforeReset.BytesWritten);
+        Assert.InRange(segment.MixCycles, 1, lifetime.MixCycles - beforeReset.MixCycles);
+        Assert.True(segment.PeakBufferedSamples <= lifetime.PeakBufferedSamples);
+    }
+
+    [Theory]
+    [InlineData(CallbackFaultPolicy.StopSource)]
+    [InlineData(CallbackFaultPolicy.Continue)]
+    public async Task ThrowingDelegate_IsContainedAndReportedOnce(CallbackFaultPolicy policy)
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var mic = FileWaveIn.Mono16(micFixture, loop: true);
+        var recorder = new RecordingDelegate { ThrowOnLevels = true };
+        using var session = new WasapiCaptureSession(() => mic, systemFactory: null);
+        session.Delegate = recorder;
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, CallbackFaultPolicy = policy });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var error = Assert.Single(recorder.Errors);
+        Assert.Equal(CaptureErrorKind.Unknown, error.ErrorKind);
+        Assert.IsType<InvalidOperationException>(error.InnerException);
+        Assert.True(File.Exists(result.FilePath));
+        var faults = session.Diagnostics.CallbackFaults;
+        if (policy == CallbackFaultPolicy.StopSource)
+        {
+            // The stop lands within a buffer or two of the first fault.
+            Assert.InRange(faults, 1, 4);
+            Assert.Contains("source stopped", error.Message);
+        }
+        else
+        {
+            Assert.True(faults > 10, $"expected a fault per buffer, got {faults}");
+            Assert.Contains("buffer dropped", error.Message);
+        }
+    }
+
     [Fact]
     public async Task MicOnly_RunsWithSystemCaptureDisabled()
     {
@@ -239,13 +626,16 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
             () => session.Configure(DefaultConfig with { EnableSystemCapture = false }));
     }
 
-    [Fact]
-    public async Task BlendedStrategy_ReportsCenterMicAndStereoSystem()
+    [Theory]
+    [InlineData(MicPlacement.Center, AudioChannel.Center)]
+    [InlineData(MicPlacement.Left, AudioChannel.Left)]
+    [InlineData(MicPlacement.Right, AudioChannel.Right)]
+    public async Task BlendedStrategy_ReportsTheMicWherePlacedAndStereoSystem(MicPlacement placement, AudioChannel micChannel)
     {
-        // Blended sums mic into both channels and leaves system stereo, so neither
-        // source owns a channel. The metadata must say so — claiming mic=Left/
-        // system=Right (the separated shape) would tell the backend it can split
-        // speakers by channel when the mix makes that impossible.
+        // Blended sums mic into both channels, or the one MicPlacement names, and
+        // leaves system stereo, so system never owns a channel. The metadata must say
+        // so — claiming system=Right (the separated shape) would tell the backend it
+        // can split speakers by channel when the mix makes that impossible.
         var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
         var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
 
@@ -253,14 +643,14 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
             () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
             () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
 
-        session.Configure(DefaultConfig with { MixingStrategy = MixingStrategy.Blended });
+        session.Configure(DefaultConfig with { MixingStrategy = MixingStrategy.Blended, MicPlacement = placement });
         var capture = session.StartCaptureAsync();
         await Task.Delay(500);
         var result = await session.StopCaptureAsync();
         await capture;
 
         Assert.Equal(ChannelLayout.Blended, result.Metadata.ChannelLayout);
-        Assert.Equal(AudioChannel.Center, result.Metadata.Tracks.Single(t => t.Type == AudioTrackType.Mic).Channel);
+        Assert.Equal(micChannel, result.Metadata.Tracks.Single(t => t.Type == AudioTrackType.Mic).Channel);
         Assert.Equal(AudioChannel.Stereo, result.Metadata.Tracks.Single(t => t.Type == AudioTrackType.System).Channel);
     }
 
@@ -320,4 +710,1581 @@ public class WasapiCaptureSessionInjectionTests : IDisposable
         // And that length is the real 0.5s (24000 frames @ 48 kHz), not an inflated one.
         Assert.InRange(mixedFrames, 24000 - 480, 24000 + 480);
     }
+
+    [Fact]
+    public async Task AudioSink_ReceivesTheSameMixedAudioAsTheFile()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+
+        var sink = Channel.CreateUnbounded<float[]>();
+        session.SetAudioSink(sink.Writer);
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        long sinkSamples = 0;
+        while (sink.Reader.TryRead(out var chunk))
+            sinkSamples += chunk.Length;
+
+        // Every i16 sample in the plaintext WAV came through the sink as one float.
+        var fileSamples = (new FileInfo(result.FilePath).Length - 44) / 2;
+        Assert.Equal(fileSamples, sinkSamples);
+        Assert.Equal(0, session.Diagnostics.SinkDroppedChunks);
+    }
+
+    [Fact]
+    public async Task FloatOutput_WritesTheMixedSamplesUntouched()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+
+        var sink = Channel.CreateUnbounded<float[]>();
+        session.SetAudioSink(sink.Writer);
+        session.Configure(DefaultConfig with { BitDepth = 32, FloatOutput = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var mixed = new List<float>();
+        while (sink.Reader.TryRead(out var chunk))
+            mixed.AddRange(chunk);
+
+        using var reader = new WaveFileReader(result.FilePath);
+        Assert.Equal(WaveFormatEncoding.IeeeFloat, reader.WaveFormat.Encoding);
+        Assert.Equal(32, reader.WaveFormat.BitsPerSample);
+        var samples = new float[reader.SampleCount * reader.WaveFormat.Channels];
+        Assert.Equal(samples.Length, reader.ToSampleProvider().Read(samples, 0, samples.Length));
+
+        // The sink sees the mix before it's written; a float file holds it bit for bit.
+        Assert.NotEmpty(samples);
+        Assert.Equal(mixed, samples);
+    }
+
+    [Fact]
+    public async Task InvalidatedLoopback_FollowsTheNewDefaultDevice()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.5);
+        var opened = 0;
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 5),
+            () => Interlocked.Increment(ref opened) == 1
+                ? new InvalidatedWaveIn()
+                : new SilentWaveIn { WaveFormat = WaveFormat.CreateIeeeFloatWaveFormat(44100, 1) });
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var diagnostics = session.Diagnostics;
+        Assert.Equal(1, diagnostics.SystemDeviceSwitches);
+        Assert.Equal(44100, diagnostics.SystemSourceSampleRate); // the replacement's format
+        Assert.Equal(1, diagnostics.SystemSourceChannels);
+        Assert.Empty(observer.Errors);
+        Assert.True(new FileInfo(result.FilePath).Length > 44);
+    }
+
+    [Fact]
+    public void FloatOutput_IsRejectedBelow32Bits()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() =>
+            session.Configure(DefaultConfig with { EnableSystemCapture = false, FloatOutput = true }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public async Task AudioSink_SlowConsumerDropsChunksInsteadOfStallingTheFile()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+
+        // Room for one chunk and nobody reading: every chunk after the first is refused.
+        // FullMode stays Wait — the Drop* modes accept-and-discard, so TryWrite would
+        // report success and the session could never tell.
+        var sink = Channel.CreateBounded<float[]>(1);
+        session.SetAudioSink(sink.Writer);
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(600);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var diagnostics = session.Diagnostics;
+        Assert.True(diagnostics.SinkDroppedChunks > 0, "a full sink should drop, not block");
+        Assert.Equal(diagnostics.MixCycles - 1, diagnostics.SinkDroppedChunks);
+        Assert.Equal(diagnostics.BytesWritten, new FileInfo(result.FilePath).Length - 44);
+    }
+
+    [Fact]
+    public async Task SurroundOutput_WritesSixInterleavedChannels()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            () => FileWaveIn.StereoFloat(systemFixture, speedFactor: 20));
+
+        session.Configure(DefaultConfig with { Channels = 6 });
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        using var reader = new WaveFileReader(result.FilePath);
+        Assert.Equal(6, reader.WaveFormat.Channels);
+        Assert.Equal(0, reader.Length % reader.WaveFormat.BlockAlign);
+        Assert.True(reader.Length > 0);
+        Assert.Equal(ChannelLayout.Surround51, result.Metadata.ChannelLayout);
+        Assert.Equal(new WavSpec(48000, 6, 16, IsFloat: false), result.Spec);
+    }
+
+    [Fact]
+    public void Configure_RejectsAnUnsupportedChannelCount()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(
+            () => session.Configure(DefaultConfig with { EnableSystemCapture = false, Channels = 3 }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public void Configure_WithoutWasapi_RecordsOnlyFromInjectedSources()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var injected = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+        using var real = new WasapiCaptureSession();
+
+        // Injected sources never touch WASAPI, so they configure anywhere.
+        injected.Configure(DefaultConfig with { EnableSystemCapture = false });
+        Assert.Equal(CaptureStateKind.Ready, injected.State.Kind);
+
+        Assert.Equal(OperatingSystem.IsWindows(), WasapiCaptureSession.IsSupported);
+        if (!WasapiCaptureSession.IsSupported)
+        {
+            var error = Assert.Throws<CaptureException>(() => real.Configure(DefaultConfig));
+            Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+            Assert.Contains("Windows 10", error.Message);
+        }
+    }
+
+    [Theory]
+    [InlineData(2, MixingStrategy.Separated)]
+    [InlineData(4, MixingStrategy.Blended)]
+    public void Configure_RejectsMicPlacementOutsideABlendedStereoMix(int channels, MixingStrategy strategy)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            Channels = channels,
+            MixingStrategy = strategy,
+            MicPlacement = MicPlacement.Left,
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(true, "{chosen}", DeviceState.Unplugged, true)]
+    [InlineData(true, "{chosen}", DeviceState.NotPresent, true)]
+    [InlineData(true, "{chosen}", DeviceState.Disabled, true)]
+    [InlineData(true, "{chosen}", DeviceState.Active, false)]  // still there: keep it
+    [InlineData(true, null, DeviceState.Unplugged, false)]     // already the default
+    [InlineData(false, "{chosen}", DeviceState.Unplugged, false)]
+    public void Start_FallsBackOnlyFromAChosenMicThatHasGone(
+        bool fallBack, string? micDeviceId, DeviceState state, bool expected)
+    {
+        // Resolving endpoints needs real devices; what a start decides from their
+        // state doesn't.
+        var config = DefaultConfig with { FallBackToDefaultMic = fallBack };
+
+        Assert.Equal(expected, WasapiCaptureSession.NeedsMicFallback(config, micDeviceId, state));
+    }
+
+    [Theory]
+    [InlineData(-45f, 6f, 200)]
+    [InlineData(float.NaN, -30f, 200)]
+    [InlineData(-45f, -30f, -1)]
+    public void Configure_RejectsAnUnusableGate(float thresholdDbfs, float floorDb, int holdMs)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            MicGate = new GateConfiguration
+            {
+                ThresholdDbfs = thresholdDbfs,
+                FloorDb = floorDb,
+                Hold = TimeSpan.FromMilliseconds(holdMs),
+            },
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(0f, 8f, 50)]
+    [InlineData(0.1f, 0.5f, 50)]
+    [InlineData(0.1f, 8f, 0)]
+    public void Configure_RejectsAnUnusableAgc(float targetRms, float maxGain, int attackMs)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            MicAgc = new AgcConfiguration
+            {
+                TargetRms = targetRms,
+                MaxGain = maxGain,
+                Attack = TimeSpan.FromMilliseconds(attackMs),
+            },
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(-50f, -1)]
+    [InlineData(3f, 2000)]
+    [InlineData(float.NaN, 2000)]
+    [InlineData(float.NegativeInfinity, 2000)]
+    public void Configure_RejectsAnUnusableSilenceSkip(float thresholdDbfs, int holdMs)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            SkipSilence = new SilenceSkipConfiguration
+            {
+                ThresholdDbfs = thresholdDbfs,
+                Hold = TimeSpan.FromMilliseconds(holdMs),
+            },
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public async Task StallWatchdog_ReportsTimeoutAndStopsWhenASourceGoesSilent()
+    {
+        // The mic plays once and then delivers nothing, like a hung driver; system
+        // keeps going. Nobody calls stop — the watchdog has to.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.2);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: false),
+            () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+        var events = new RecordingDelegate();
+        session.Delegate = events;
+
+        session.Configure(DefaultConfig with
+        {
+            StallTimeout = TimeSpan.FromMilliseconds(200),
+            StopOnStall = true,
+        });
+
+        var result = await session.StartCaptureAsync().WaitAsync(TimeSpan.FromSeconds(5));
+
+        Assert.Equal(CaptureStateKind.Completed, session.State.Kind);
+        Assert.True(File.Exists(result.FilePath));
+        var timeout = Assert.Single(events.Errors, e => e.ErrorKind == CaptureErrorKind.Timeout);
+        Assert.StartsWith("Microphone delivered no audio", timeout.Message);
+    }
+
+    [Fact]
+    public async Task StallWatchdog_StaysQuietWhileSourcesDeliver()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+        var events = new RecordingDelegate();
+        session.Delegate = events;
+
+        session.Configure(DefaultConfig with { StallTimeout = TimeSpan.FromMilliseconds(200) });
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(700);
+        await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Empty(events.Errors);
+    }
+
+    [Fact]
+    public async Task ImmediateStop_IsFlaggedTooShortAndDeletedWhenAsked()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            systemFactory: null);
+
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            ExportRawPcm = true,
+            MinimumRecordingDuration = TimeSpan.FromSeconds(1),
+            DeleteTooShortRecordings = true,
+        });
+
+        var capture = session.StartCaptureAsync();
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.True(result.IsTooShort);
+        Assert.False(File.Exists(result.FilePath), "a too-short recording should be deleted");
+        Assert.Empty(result.RawPcmFilePaths);
+        Assert.Equal("", result.Checksum);
+    }
+
+    [Fact]
+    public async Task LongEnoughRecording_IsNotFlagged()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            systemFactory: null);
+
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            MinimumRecordingDuration = TimeSpan.FromSeconds(0.1),
+            DeleteTooShortRecordings = true,
+        });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.False(result.IsTooShort);
+        Assert.True(File.Exists(result.FilePath));
+    }
+
+    [Fact]
+    public async Task CaptureToMemory_ReturnsTheWavWithoutWritingAFile()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20),
+            systemFactory: null);
+
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, CaptureToMemory = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal("", result.FilePath);
+        Assert.Empty(Directory.GetFiles(_tempDir, "recording_*"));
+        Assert.NotNull(result.AudioData);
+        Assert.True(result.AudioData.Length > 44, "expected audio after the header");
+        Assert.Equal("RIFF"u8.ToArray(), result.AudioData[..4]);
+        Assert.Equal(result.AudioData.Length - 44, BitConverter.ToInt32(result.AudioData, 40));
+        Assert.Equal(Convert.ToHexStringLower(SHA256.HashData(result.AudioData)), result.Checksum);
+    }
+
+    [Fact]
+    public async Task OutputSink_ReceivesThePcmInsteadOfAFile()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        var sink = new CollectingSink();
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, OutputSink = sink });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.True(sink.Opened);
+        Assert.True(sink.Closed);
+        Assert.Equal("", result.FilePath);
+        Assert.Equal("sink-checksum", result.Checksum);
+        Assert.Empty(Directory.GetFiles(_tempDir, "recording_*"));
+        Assert.Equal(session.Diagnostics.BytesWritten, sink.Writes.Sum(w => w.Length));
+    }
+
+    [Fact]
+    public async Task OutputSink_WithAnEncryptor_ReceivesSealedFrames()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        var sink = new CollectingSink();
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, OutputSink = sink, Encryptor = encryptor });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        await session.StopCaptureAsync();
+        await capture;
+
+        // Each write is one [length][sealed box] frame, as in an .enc.wav data chunk.
+        Assert.NotEmpty(sink.Writes);
+        var plaintext = 0L;
+        foreach (var frame in sink.Writes)
+        {
+            Assert.Equal(frame.Length - 4, BitConverter.ToInt32(frame, 0));
+            plaintext += encryptor.Decrypt(frame[4..]).Length;
+        }
+        Assert.Equal(session.Diagnostics.BytesWritten, plaintext);
+    }
+
+    [Fact]
+    public void OutputSink_IsRejectedWithSidecars()
+    {
+        using var session = new WasapiCaptureSession(() => new SilentWaveIn(), systemFactory: null);
+
+        var ex = Assert.Throws<CaptureException>(() => session.Configure(
+            DefaultConfig with { EnableSystemCapture = false, OutputSink = new CollectingSink(), ExportRawPcm = true }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, ex.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(TimingSource.Auto)]
+    [InlineData(TimingSource.Mic)]
+    public async Task SilentLoopback_DoesNotHoldBackTheMic(TimingSource timing)
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            () => new SilentWaveIn());
+
+        var sink = Channel.CreateUnbounded<float[]>();
+        session.SetAudioSink(sink.Writer);
+        session.Configure(DefaultConfig with { TimingSource = timing });
+
+        var capture = session.StartCaptureAsync();
+        // Under the 0.5s stall guard: waiting on loopback would have written nothing yet.
+        await Task.Delay(400);
+        var midCapture = sink.Reader.Count;
+        await session.StopCaptureAsync();
+        await capture;
+
+        Assert.True(midCapture > 0, "mic audio should be mixed while nothing is playing");
+    }
+
+    [Fact]
+    public async Task Int16Processing_WritesTheMicBitExact()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            Channels = 1,
+            ProcessingFormat = SampleFormat.Int16,
+            ExportRawPcm = true,
+        });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(500); // 0.3s of fixture at 20x drains well inside this
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        // The raw sidecar is exactly what the mic delivered. The float path rescales
+        // every sample on the way to the file; passthrough must not touch a bit.
+        var delivered = File.ReadAllBytes(Assert.Single(result.RawPcmFilePaths));
+        Assert.NotEmpty(delivered);
+        Assert.Equal(delivered, File.ReadAllBytes(result.FilePath)[44..]);
+    }
+
+    [Fact]
+    public void Int16Processing_IsRejectedWhenTheMixerIsNeeded()
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            ProcessingFormat = SampleFormat.Int16, // but stereo output
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(47999.7, null, false, false)] // a fraction off
+    [InlineData(4800, null, false, false)]    // a dropped zero
+    [InlineData(48000, 11025, false, false)]
+    [InlineData(11025, null, true, true)]
+    [InlineData(47999.7, 24000, true, true)]
+    [InlineData(1_000_000, null, true, false)] // allowed or not, beyond what WASAPI takes
+    public void NonStandardSampleRates_NeedTheAdvancedFlag(double rate, int? deliveryRate, bool allow, bool accepted)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+        var config = DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            SampleRate = rate,
+            DeliverySampleRate = deliveryRate,
+            AllowNonStandardSampleRate = allow,
+        };
+
+        if (accepted)
+        {
+            session.Configure(config);
+            return;
+        }
+        var error = Assert.Throws<CaptureException>(() => session.Configure(config));
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Theory]
+    [InlineData(4000, 2, 16, false)] // below the supported rates
+    [InlineData(48000, 0, 16, false)]
+    [InlineData(48000, 2, 20, false)]
+    [InlineData(48000, 2, 24, false)] // not decoded by either capture path
+    [InlineData(48000, 2, 32, false)] // 32-bit means float
+    [InlineData(48000, 2, 16, true)]
+    public void FormatOverride_NoClientCouldOpenIsRejected(int rate, int channels, int bits, bool isFloat)
+    {
+        var fixture = WriteFixture("mic.wav");
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(fixture), systemFactory: null);
+
+        var error = Assert.Throws<CaptureException>(() => session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            MicFormatOverride = new WavSpec(rate, channels, bits, isFloat),
+        }));
+
+        Assert.Equal(CaptureErrorKind.ConfigurationFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public async Task MicOnly_ABacklogIsDrainedInOneTickNotTrickled()
+    {
+        // A full second of mic lands in one buffer — what a stalled mix loop finds
+        // waiting once it wakes. The next tick must write all of it, not one
+        // tick's worth, or the buffer never catches up under load.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 1);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, chunkDuration: TimeSpan.FromSeconds(1)), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Channels = 1 });
+
+        var capture = session.StartCaptureAsync();
+        var deadline = DateTime.UtcNow + TimeSpan.FromSeconds(5);
+        while (session.Diagnostics.BytesWritten == 0 && DateTime.UtcNow < deadline)
+            await Task.Delay(10);
+
+        // The first tick to write anything wrote the lot.
+        var diagnostics = session.Diagnostics;
+        Assert.Equal(1, diagnostics.MixCycles);
+        Assert.Equal(48000 * 2, diagnostics.BytesWritten);
+        await session.StopCaptureAsync();
+        await capture;
+    }
+
+    [Fact]
+    public async Task SilentRecording_IsFlaggedAndReported()
+    {
+        var muted = Path.Combine(_tempDir, "muted.wav");
+        using (var writer = new WaveFileWriter(muted, new WaveFormat(48000, 16, 1)))
+            writer.Write(new byte[48000 * 2 * 3 / 10], 0, 48000 * 2 * 3 / 10); // 0.3s of zeros
+
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(muted, speedFactor: 20), systemFactory: null);
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, ReportSilentRecordings = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.False(result.IsTooShort);
+        Assert.True(result.IsSilent);
+        Assert.True(result.Metadata.IsSilent);
+        Assert.Equal(CaptureErrorKind.DeviceNotAvailable, Assert.Single(observer.Errors).ErrorKind);
+    }
+
+    [Fact]
+    public async Task AudibleRecording_IsNotFlaggedSilent()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, ReportSilentRecordings = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.False(result.IsSilent);
+        Assert.Empty(observer.Errors);
+    }
+
+    [Fact]
+    public async Task QuietFloatRecording_IsNotFlaggedSilent()
+    {
+        // Far below a 16-bit step, but a float file keeps it.
+        var quiet = Path.Combine(_tempDir, "quiet.wav");
+        using (var writer = new WaveFileWriter(quiet, WaveFormat.CreateIeeeFloatWaveFormat(48000, 2)))
+        {
+            for (int i = 0; i < 48000 * 3 / 10; i++)
+            {
+                var sample = (float)(Math.Sin(2 * Math.PI * 440 * i / 48000) * 1e-6);
+                writer.WriteSample(sample);
+                writer.WriteSample(sample);
+            }
+        }
+
+        using var session = new WasapiCaptureSession(
+            micFactory: null, () => FileWaveIn.StereoFloat(quiet, speedFactor: 20));
+        session.Configure(DefaultConfig with { EnableMicCapture = false, BitDepth = 32, FloatOutput = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.False(result.IsSilent);
+    }
+
+    [Fact]
+    public async Task EstimatedFinalSize_MatchesAPlaintextRecordingExactly()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        var config = DefaultConfig with { EnableSystemCapture = false };
+        session.Configure(config);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var audio = TimeSpan.FromSeconds(session.Diagnostics.BytesWritten / config.EstimatedBytesPerSecond());
+        Assert.Equal(192000, config.EstimatedBytesPerSecond());
+        Assert.Equal(new FileInfo(result.FilePath).Length, session.EstimatedFinalSize(audio));
+    }
+
+    [Fact]
+    public async Task EstimatedFinalSize_CountsEncryptionFraming()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        var config = DefaultConfig with { EnableSystemCapture = false, Encryptor = encryptor, EncryptionChunkSize = 4800 };
+        session.Configure(config);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        // 40 frames a second of 4 + 28 bytes framing on top of the PCM.
+        Assert.Equal(192000 + 40 * 32, config.EstimatedBytesPerSecond());
+        var pcmBytes = session.Diagnostics.BytesWritten;
+        var estimate = session.EstimatedFinalSize(TimeSpan.FromSeconds(pcmBytes / 192000.0));
+        // Off by at most the framing of the final, partial chunk.
+        Assert.InRange(new FileInfo(result.FilePath).Length - estimate, -32, 32);
+    }
+
+    [Fact]
+    public async Task PassphraseEncryption_ReadsBackFromTheMetadataAndTheSamePassphraseOnly()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var encryptor = new PassphraseEncryptor("correct horse battery staple", iterations: 1000);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Encryptor = encryptor });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        // Only what the sidecar holds — the passphrase is all that's remembered.
+        var saved = JsonSerializer.Deserialize<RecordingMetadata>(JsonSerializer.Serialize(result.Metadata))!;
+        using var reopened = PassphraseEncryptor.FromKeyMetadata("correct horse battery staple", saved.EncryptionKeyMetadata);
+        Assert.Equal(session.Diagnostics.BytesWritten, EncryptedWavReader.ReadPcm(result.FilePath, reopened.Cipher).Length);
+
+        using var guessed = PassphraseEncryptor.FromKeyMetadata("correct horse battery stapler", saved.EncryptionKeyMetadata);
+        var error = Assert.Throws<CaptureException>(() => EncryptedWavReader.ReadPcm(result.FilePath, guessed.Cipher));
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, error.ErrorKind);
+    }
+
+    [Fact]
+    public async Task RapidStartStop_LeavesEveryFileWholeAndNoCallbackAfterStop()
+    {
+        // Stops land at arbitrary points in the sources' 1 ms callbacks and the
+        // 100 ms mix cycle; any tear between them shows up as a header that
+        // disagrees with the data, a mix error, or a callback counted after stop.
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+        var random = new Random(1889);
+
+        for (int cycle = 0; cycle < 30; cycle++)
+        {
+            using var session = new WasapiCaptureSession(
+                () => FileWaveIn.Mono16(micFixture, chunkDuration: TimeSpan.FromMilliseconds(1), loop: true),
+                () => FileWaveIn.StereoFloat(systemFixture, chunkDuration: TimeSpan.FromMilliseconds(1), loop: true));
+            session.Configure(DefaultConfig);
+
+            var capture = session.StartCaptureAsync();
+            await Task.Delay(random.Next(0, 150));
+            var result = await session.StopCaptureAsync();
+            await capture;
+
+            var stopped = session.Diagnostics;
+            await Task.Delay(10);
+            var later = session.Diagnostics;
+            Assert.Equal(stopped.MicChunks, later.MicChunks);
+            Assert.Equal(stopped.SystemChunks, later.SystemChunks);
+            Assert.Equal(0, stopped.MixErrors);
+
+            var file = File.ReadAllBytes(result.FilePath);
+            Assert.Equal(file.Length - 44, BitConverter.ToInt32(file, 40));
+            Assert.Equal(stopped.BytesWritten, file.Length - 44);
+        }
+    }
+
+    [Fact]
+    public async Task EncryptionFailure_MidRecording_FailsTheSession()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Encryptor = new FailingEncryptor(succeedFor: 2) });
+
+        var capture = session.StartCaptureAsync();
+
+        // No stop from the caller: the failure ends the run on its own.
+        var thrown = await Assert.ThrowsAsync<CaptureException>(() => capture.WaitAsync(TimeSpan.FromSeconds(5)));
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, thrown.ErrorKind);
+        Assert.Equal(CaptureStateKind.Failed, session.State.Kind);
+        Assert.Equal(CaptureErrorKind.EncryptionFailed, Assert.Single(observer.Errors).ErrorKind);
+        Assert.Equal(1, session.Diagnostics.MixErrors);
+    }
+
+    [Fact]
+    public async Task InMemoryRecording_OverItsMemoryBudget_FailsWithAStorageError()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+        var observer = new RecordingDelegate();
+        session.Delegate = observer;
+        // About a third of a second of 48 kHz 16-bit stereo.
+        session.Configure(DefaultConfig with { CaptureToMemory = true, MemoryBudgetBytes = 64_000 });
+
+        var thrown = await Assert.ThrowsAsync<CaptureException>(
+            () => session.StartCaptureAsync().WaitAsync(TimeSpan.FromSeconds(5)));
+
+        Assert.Equal(CaptureErrorKind.StorageError, thrown.ErrorKind);
+        Assert.Equal(CaptureStateKind.Failed, session.State.Kind);
+        Assert.Equal(thrown, Assert.Single(observer.Errors));
+        Assert.True(session.Diagnostics.PeakMemoryBytes > 64_000);
+        Assert.Equal(0, session.Diagnostics.MixErrors); // nothing failed to write
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public async Task FailedRun_KeepsThePartialFileUnlessAskedToDeleteIt(bool delete)
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            Encryptor = new FailingEncryptor(succeedFor: 2),
+            DeletePartialOnFailure = delete,
+        });
+
+        var thrown = await Assert.ThrowsAsync<CaptureException>(
+            () => session.StartCaptureAsync().WaitAsync(TimeSpan.FromSeconds(5)));
+
+        var recordings = Directory.GetFiles(_tempDir, "recording_*.enc.wav");
+        if (delete)
+        {
+            Assert.Empty(recordings);
+            return;
+        }
+        var wavPath = Assert.Single(recordings);
+        var marker = JsonSerializer.Deserialize<PartialRecording>(
+            File.ReadAllText(PartialRecording.MarkerPath(wavPath)))!;
+        Assert.Equal(wavPath, marker.FilePath);
+        Assert.Equal(thrown.ErrorKind, marker.ErrorKind);
+        Assert.True(marker.BytesWritten > 0);
+    }
+
+    [Fact]
+    public async Task Duration_ExcludesPausedTime_OnAManualClock()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var clock = new ManualTimeProvider();
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null, clock);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        clock.Advance(TimeSpan.FromSeconds(2));
+        session.PauseCapture();
+        Assert.Equal(TimeSpan.FromSeconds(2), session.State.Duration);
+
+        clock.Advance(TimeSpan.FromSeconds(5)); // paused: none of this counts
+        session.ResumeCapture();
+        Assert.Equal(TimeSpan.FromSeconds(2), session.State.Duration);
+
+        clock.Advance(TimeSpan.FromSeconds(1));
+        session.PauseCapture();
+        session.ResumeCapture(); // a pause with no time in it costs nothing
+        clock.Advance(TimeSpan.FromSeconds(0.5));
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(3.5, result.DurationSecs);
+        Assert.Equal(3.5, result.Metadata.DurationSecs);
+    }
+
+    [Fact]
+    public async Task Elapsed_IsLiveInEveryState()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var clock = new ManualTimeProvider();
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null, clock);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+        Assert.Equal(TimeSpan.Zero, session.Elapsed);
+
+        var capture = session.StartCaptureAsync();
+        clock.Advance(TimeSpan.FromSeconds(2));
+        Assert.Equal(TimeSpan.FromSeconds(2), session.Elapsed);
+        Assert.Equal(TimeSpan.Zero, session.State.Duration); // stamped at the start
+
+        session.PauseCapture();
+        clock.Advance(TimeSpan.FromSeconds(5));
+        Assert.Equal(TimeSpan.FromSeconds(2), session.Elapsed);
+
+        session.ResumeCapture();
+        clock.Advance(TimeSpan.FromSeconds(1));
+        await session.StopCaptureAsync();
+        await capture;
+        clock.Advance(TimeSpan.FromSeconds(10));
+
+        Assert.Equal(CaptureStateKind.Completed, session.State.Kind);
+        Assert.Equal(TimeSpan.FromSeconds(3), session.Elapsed);
+    }
+
+    [Theory]
+    [InlineData(true)]
+    [InlineData(false)]
+    public async Task StateLog_TimesEachTransitionWhenAskedFor(bool recordStateLog)
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var clock = new ManualTimeProvider();
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null, clock);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, RecordStateLog = recordStateLog });
+
+        clock.Advance(TimeSpan.FromSeconds(1));
+        var capture = session.StartCaptureAsync();
+        clock.Advance(TimeSpan.FromSeconds(2));
+        session.PauseCapture();
+        clock.Advance(TimeSpan.FromSeconds(3));
+        session.ResumeCapture();
+        clock.Advance(TimeSpan.FromSeconds(1));
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        if (!recordStateLog)
+        {
+            Assert.Empty(result.Metadata.StateLog);
+            return;
+        }
+        var at = (double secs) => ManualTimeProvider.Epoch + TimeSpan.FromSeconds(secs);
+        Assert.Equal(
+            [
+                new StateTransition(CaptureStateKind.Configuring, at(0)),
+                new StateTransition(CaptureStateKind.Ready, at(0)),
+                new StateTransition(CaptureStateKind.Capturing, at(1)),
+                new StateTransition(CaptureStateKind.Paused, at(3)),
+                new StateTransition(CaptureStateKind.Capturing, at(6)),
+                new StateTransition(CaptureStateKind.Stopping, at(7)),
+            ],
+            result.Metadata.StateLog);
+        Assert.Contains("\"state_log\":[{\"state\":", JsonSerializer.Serialize(result.Metadata));
+    }
+
+    [Fact]
+    public async Task ConcurrentStarts_OnlyOneGetsThrough()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        for (var round = 0; round < 20; round++)
+        {
+            using var session = new WasapiCaptureSession(
+                () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+            session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+            using var barrier = new Barrier(2);
+            Task<RecordingResult> Start() => Task.Run(() =>
+            {
+                barrier.SignalAndWait();
+                return session.StartCaptureAsync();
+            });
+            var starts = new[] { Start(), Start() };
+            // Bounded, so a start that never gets through fails the test rather than hanging it.
+            await Task.Run(async () =>
+            {
+                while (session.State.Kind != CaptureStateKind.Capturing)
+                    await Task.Delay(5);
+            }).WaitAsync(TimeSpan.FromSeconds(5));
+            await session.StopCaptureAsync();
+
+            var outcomes = await Task.WhenAll(starts.Select(async start =>
+            {
+                try
+                {
+                    await start;
+                    return null;
+                }
+                catch (CaptureException ex)
+                {
+                    return ex;
+                }
+            }));
+            var rejected = Assert.Single(outcomes, ex => ex != null);
+            Assert.Equal(CaptureErrorKind.ConfigurationFailed, rejected!.ErrorKind);
+        }
+    }
+
+    [Theory]
+    [InlineData(false, null)]
+    [InlineData(true, null)]
+    [InlineData(true, 4800)] // fixed-size sealing
+    public async Task MaxFileBytes_StopsTheRecordingAtTheCap(bool encrypted, int? chunkSize)
+    {
+        const long cap = 100_000; // about half a second of 48 kHz stereo
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var encryptor = new AesGcmEncryptor(RandomNumberGenerator.GetBytes(32));
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            MaxFileBytes = cap,
+            Encryptor = encrypted ? encryptor : null,
+            EncryptionChunkSize = chunkSize,
+        });
+
+        var result = await session.StartCaptureAsync().WaitAsync(TimeSpan.FromSeconds(10));
+
+        Assert.Equal(StopReason.MaxSize, result.StopReason);
+        Assert.Equal(CaptureStateKind.Completed, session.State.Kind);
+        // Filled to within a sealed frame's framing, never past it.
+        Assert.InRange(new FileInfo(result.FilePath).Length, cap - 64, cap);
+    }
+
+    [Fact]
+    public async Task StopReason_IsUserStoppedWhenTheCallerStops()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, MaxFileBytes = 10_000_000 });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(StopReason.UserStopped, result.StopReason);
+        Assert.Contains("\"stop_reason\":\"user_stopped\"", JsonSerializer.Serialize(result.Metadata));
+    }
+
+    [Theory]
+    [InlineData(true)]
+    [InlineData(false)]
+    public async Task SkipSilence_LeavesOutLongSilencesAndListsThem(bool skipSilence)
+    {
+        // Speech and silence alternating: a 5 s silence goes, a 0.5 s one stays.
+        var micFixture = Path.Combine(_tempDir, "speech.wav");
+        using (var writer = new WaveFileWriter(micFixture, new WaveFormat(48000, 16, 1)))
+        {
+            foreach (var (seconds, sounding) in new[] { (1.0, true), (5.0, false), (1.0, true), (0.5, false), (1.0, true) })
+            {
+                for (int i = 0; i < 48000 * seconds; i++)
+                    writer.WriteSample(sounding ? (float)(Math.Sin(2 * Math.PI * 440 * i / 48000) * 0.5) : 0f);
+            }
+        }
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, speedFactor: 20), systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            SkipSilence = skipSilence ? new SilenceSkipConfiguration { Hold = TimeSpan.FromSeconds(1) } : null,
+        });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(TimeSpan.FromSeconds(1)); // 8.5 s at 20x, with room to spare
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var wav = MarkerAnalysis.ReadWavChannels(result.FilePath);
+        if (!skipSilence)
+        {
+            Assert.Equal(408000, wav.Left.Length); // all 8.5 s
+            Assert.Empty(result.Metadata.SkippedGaps);
+            return;
+        }
+        // 4 s of the long silence left out, to within a 10 ms block at either edge.
+        Assert.InRange(wav.Left.Length, 216000 - 960, 216000 + 960);
+        var gap = Assert.Single(result.Metadata.SkippedGaps);
+        Assert.Equal(2, gap.StartSecs, 0.02);
+        Assert.Equal(2, gap.FileSecs, 0.02);
+        Assert.Equal(4, gap.DurationSecs, 0.02);
+        Assert.Contains("\"skipped_gaps\":[{\"start_secs\":", JsonSerializer.Serialize(result.Metadata));
+    }
+
+    [Fact]
+    public async Task Tags_FlowIntoMetadataAndRoundTripThroughJson()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        var tags = new Dictionary<string, string> { ["meeting_id"] = "m-42", ["participants"] = "Ada, Grace" };
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Tags = tags });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(200);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(tags, result.Metadata.Tags);
+        var json = JsonSerializer.Serialize(result.Metadata);
+        Assert.Contains("\"tags\":{\"meeting_id\":\"m-42\"", json);
+        var restored = JsonSerializer.Deserialize<RecordingMetadata>(json)!;
+        Assert.Equal(tags, restored.Tags);
+        Assert.Equal(result.Metadata.Id, restored.Id);
+    }
+
+    [Fact]
+    public async Task LevelsSnapshot_IsStampedWithRecordedTimeAndSerializes()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var clock = new ManualTimeProvider();
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null, clock);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        clock.Advance(TimeSpan.FromSeconds(1.5));
+        var snapshot = session.CurrentLevelsSnapshot();
+        await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(1.5, snapshot.ElapsedSecs);
+        Assert.True(snapshot.MicLevel > 0);
+        var json = JsonSerializer.Serialize(snapshot);
+        Assert.Contains("\"elapsed_secs\":1.5", json);
+        Assert.Equal(snapshot, JsonSerializer.Deserialize<AudioLevelsSnapshot>(json));
+    }
+
+    [Fact]
+    public async Task Pause_LeavesEverythingCapturedSoFarOnDisk()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Channels = 1, ExportRawPcm = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(350); // mid-cycle: the pump ticks every 100 ms
+        session.PauseCapture();
+
+        var wavPath = Directory.GetFiles(_tempDir, "recording_*.wav").Single();
+        var micPath = Directory.GetFiles(_tempDir, "*_mic.pcm").Single();
+        byte[] wav = ReadShared(wavPath);
+        byte[] captured = ReadShared(micPath);
+
+        // Mono 16-bit out of a mono 16-bit mic: one byte written per byte captured.
+        Assert.NotEmpty(captured);
+        Assert.Equal((uint)captured.Length, BitConverter.ToUInt32(wav, 40));
+        Assert.Equal(44 + captured.Length, wav.Length);
+        Assert.Equal((uint)(wav.Length - 8), BitConverter.ToUInt32(wav, 4));
+
+        await session.StopCaptureAsync();
+        await capture;
+
+        static byte[] ReadShared(string path)
+        {
+            using var stream = new FileStream(path, FileMode.Open, FileAccess.Read, FileShare.ReadWrite);
+            using var copy = new MemoryStream();
+            stream.CopyTo(copy);
+            return copy.ToArray();
+        }
+    }
+
+    [Fact]
+    public async Task PauseDevices_NoCallbacksFireWhilePaused()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var mic = FileWaveIn.Mono16(micFixture, loop: true);
+        var callbacks = 0;
+        mic.DataAvailable += (_, _) => Interlocked.Increment(ref callbacks);
+        using var session = new WasapiCaptureSession(() => mic, systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false, Channels = 1, PauseDevices = true });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(200);
+        session.PauseCapture();
+        var atPause = Volatile.Read(ref callbacks);
+        await Task.Delay(300);
+        Assert.True(atPause > 0);
+        Assert.Equal(atPause, Volatile.Read(ref callbacks));
+
+        session.ResumeCapture();
+        await Task.Delay(200);
+        Assert.True(Volatile.Read(ref callbacks) > atPause);
+
+        var result = await session.StopCaptureAsync();
+        await capture;
+        Assert.True(result.DurationSecs < 0.7);
+    }
+
+    [Fact]
+    public async Task MicHeldInExclusiveMode_FailsWithDeviceInUse()
+    {
+        using var session = new WasapiCaptureSession(() => new BusyWaveIn(), systemFactory: null);
+        session.Configure(DefaultConfig with { EnableSystemCapture = false });
+
+        var error = await Assert.ThrowsAsync<CaptureException>(session.StartCaptureAsync);
+
+        Assert.Equal(CaptureErrorKind.DeviceInUse, error.ErrorKind);
+        Assert.Equal(CaptureStateKind.Failed, session.State.Kind);
+    }
+
+    [Fact]
+    public async Task TransientlyBusyMic_OpensOnARetry()
+    {
+        var micFixture = WriteFixture("mic.wav");
+        var attempts = 0;
+        using var session = new WasapiCaptureSession(
+            () => ++attempts <= 2 ? new BusyWaveIn() : FileWaveIn.Mono16(micFixture, loop: true),
+            systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            DeviceOpenRetries = 2,
+            DeviceOpenBackoff = TimeSpan.FromMilliseconds(10),
+        });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        Assert.Equal(3, attempts);
+        Assert.True(new FileInfo(result.FilePath).Length > 44, "audio from the retried source should be recorded");
+    }
+
+    [Fact]
+    public async Task BusyMic_FailsOnceRetriesRunOut()
+    {
+        var attempts = 0;
+        using var session = new WasapiCaptureSession(() => { attempts++; return new BusyWaveIn(); }, systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            DeviceOpenRetries = 1,
+            DeviceOpenBackoff = TimeSpan.FromMilliseconds(10),
+        });
+
+        var error = await Assert.ThrowsAsync<CaptureException>(session.StartCaptureAsync);
+
+        Assert.Equal(CaptureErrorKind.DeviceInUse, error.ErrorKind);
+        Assert.Equal(2, attempts);
+    }
+
+    [Fact]
+    public async Task DisposeWhileCapturing_FinalizesTheRecording()
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        var systemFixture = WriteFixture("system.wav", channels: 2, seconds: 0.3);
+        var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(micFixture, loop: true),
+            () => FileWaveIn.StereoFloat(systemFixture, loop: true));
+        session.Configure(DefaultConfig);
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(300);
+        session.Dispose(); // no StopCaptureAsync
+
+        // The pending start completes with a result rather than hanging forever...
+        var result = await capture.WaitAsync(TimeSpan.FromSeconds(5));
+        Assert.Equal(CaptureStateKind.Completed, session.State.Kind);
+
+        // ...and the header describes the audio that made it to disk.
+        var wav = File.ReadAllBytes(result.FilePath);
+        Assert.True(wav.Length > 44, "buffered audio should have been written");
+        Assert.Equal((uint)(wav.Length - 44), BitConverter.ToUInt32(wav, 40));
+    }
+
+    [Theory]
+    [InlineData(SampleFormat.Float32)]
+    [InlineData(SampleFormat.Int16)]
+    public async Task PrimeDuration_MutesTheOpeningOfEachSource(SampleFormat format)
+    {
+        var micFixture = WriteFixture("mic.wav", channels: 1, seconds: 0.3);
+        using var session = new WasapiCaptureSession(() => FileWaveIn.Mono16(micFixture, loop: true), systemFactory: null);
+        session.Configure(DefaultConfig with
+        {
+            EnableSystemCapture = false,
+            Channels = 1,
+            ProcessingFormat = format,
+            PrimeDuration = TimeSpan.FromMilliseconds(100),
+        });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(400);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var samples = MemoryMarshal.Cast<byte, short>(File.ReadAllBytes(result.FilePath).AsSpan(44)).ToArray();
+        Assert.True(samples.Length > 9600, "expected more than 200 ms of audio");
+        Assert.All(samples[..4800], s => Assert.Equal(0, s));
+        Assert.Contains(samples[4800..9600], s => s != 0);
+    }
+
+    [Fact]
+    public async Task ProbeSystemAudio_TellsPlaybackFromAnIdleDevice()
+    {
+        var tone = WriteFixture("tone.wav", channels: 2);
+        var silence = Path.Combine(_tempDir, "silence.wav");
+        using (var writer = new WaveFileWriter(silence, new WaveFormat(48000, 16, 2)))
+            writer.Write(new byte[48000 * 4 / 5], 0, 48000 * 4 / 5);
+
+        using var playing = new WasapiCaptureSession(null, () => FileWaveIn.StereoFloat(tone, loop: true));
+        using var idle = new WasapiCaptureSession(null, () => FileWaveIn.StereoFloat(silence, loop: true));
+
+        var heard = await playing.ProbeSystemAudioAsync(TimeSpan.FromMilliseconds(200));
+        var quiet = await idle.ProbeSystemAudioAsync(TimeSpan.FromMilliseconds(200));
+
+        Assert.True(heard.IsAudible);
+        Assert.False(quiet.IsAudible);
+        Assert.Equal(CaptureStateKind.Idle, idle.State.Kind); // the probe is not a capture
+    }
+
+    [Fact]
+    public async Task SelfTest_ReportsReadyWhenEverySourceDelivers()
+    {
+        var mic = WriteFixture("mic.wav", channels: 1);
+        var system = WriteFixture("system.wav", channels: 2);
+        using var session = new WasapiCaptureSession(
+            () => FileWaveIn.Mono16(mic, loop: true), () => FileWaveIn.StereoFloat(system, loop: true));
+
+        var report = await session.SelfTestAsync(DefaultConfig, TimeSpan.FromSeconds(2));
+
+        Assert.True(report.IsReady);
+        Assert.Equal([AudioTrackType.Mic, AudioTrackType.System], report.Sources.Select(s => s.SourceType));
+        Assert.All(report.Sources, s => Assert.True(s.Delivered));
+        Assert.Equal(CaptureStateKind.Idle, session.State.Kind); // nothing was recorded
+        Assert.Empty(Directory.GetFiles(_tempDir, "*.enc.wav"));
+    }
+
+    [Fact]
+    public async Task SelfTest_ReportsEachProblemWithoutThrowing()
+    {
+        var system = WriteFixture("system.wav", channels: 2);
+        using var session = new WasapiCaptureSession(() => new BusyWaveIn(), () => FileWaveIn.StereoFloat(system));
+        var blocker = Path.Combine(_tempDir, "not-a-directory");
+        File.WriteAllText(blocker, "");
+        var config = DefaultConfig with
+        {
+            DeviceBufferDuration = TimeSpan.Zero,
+            OutputDirectory = Path.Combine(blocker, "recordings"),
+        };
+
+        var report = await session.SelfTestAsync(config, TimeSpan.FromMilliseconds(500));
+
+        Assert.False(report.IsReady);
+        Assert.NotNull(report.ConfigurationError);
+        Assert.NotNull(report.OutputError);
+        var mic = report.Sources.Single(s => s.SourceType == AudioTrackType.Mic);
+        Assert.Equal(SourceStatus.InUse, mic.Status);
+        Assert.False(mic.Delivered);
+        Assert.Equal(SourceStatus.Available, report.Sources.Single(s => s.SourceType == AudioTrackType.System).Status);
+    }
+
+    [Fact]
+    public async Task SelfTest_ReportsAPrivacyBlockedMicAsPermissionDenied()
+    {
+        var system = WriteFixture("system.wav", channels: 2);
+        using var session = new WasapiCaptureSession(() => new DeniedWaveIn(), () => FileWaveIn.StereoFloat(system));
+
+        var report = await session.SelfTestAsync(DefaultConfig, TimeSpan.FromMilliseconds(500));
+
+        // The runtime surfaces E_ACCESSDENIED as UnauthorizedAccessException, not a COMException.
+        Assert.Equal(SourceStatus.PermissionDenied, report.Sources.Single(s => s.SourceType == AudioTrackType.Mic).Status);
+        Assert.Equal(SourceStatus.Available, report.Sources.Single(s => s.SourceType == AudioTrackType.System).Status);
+    }
+
+    [Fact]
+    public async Task SystemActivity_RecordsWhenSomethingWasPlaying()
+    {
+        // 0.4s of tone, 0.4s of silence, 0.4s of tone, then the source runs dry.
+        var systemFixture = Path.Combine(_tempDir, "bursts.wav");
+        using (var writer = new WaveFileWriter(systemFixture, new WaveFormat(48000, 16, 2)))
+        {
+            for (int i = 0; i < 48000 * 1.2; i++)
+            {
+                var silent = i >= 19200 && i < 38400;
+                var sample = silent ? 0f : (float)(Math.Sin(2 * Math.PI * 440 * i / 48000) * 0.5);
+                writer.WriteSample(sample);
+                writer.WriteSample(sample);
+            }
+        }
+
+        using var session = new WasapiCaptureSession(
+            micFactory: null,
+            () => FileWaveIn.StereoFloat(systemFixture));
+        session.Configure(DefaultConfig with { EnableMicCapture = false });
+
+        var capture = session.StartCaptureAsync();
+        await Task.Delay(2000);
+        var result = await session.StopCaptureAsync();
+        await capture;
+
+        var activity = result.Metadata.SystemActivity;
+        Assert.Equal(2, activity.Length);
+        Assert.InRange(activity[0].StartSecs, 0, 0.2);
+        Assert.InRange(activity[1].StartSecs - activity[0].EndSecs, 0.2, 0.6);
+        Assert.InRange(activity[1].EndSecs, 0.9, 1.5); // closed by the gap, not at stop
+    }
+
+    /// <summary>
+    /// A loopback source with nothing playing: WASAPI delivers no packets at all,
+    /// rather than packets of silence.
+    /// </summary>
+    private sealed class SilentWaveIn : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable { add { } remove { } }
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = WaveFormat.CreateIeeeFloatWaveFormat(48000, 2);
+
+        public void StartRecording() { }
+
+        public void StopRecording() => RecordingStopped?.Invoke(this, new StoppedEventArgs());
+
+        public void Dispose() { }
+    }
+
+    /// <summary>A clock that only moves when the test says so.</summary>
+    private sealed class ManualTimeProvider : TimeProvider
+    {
+        private long _ticks;
+
+        public override long TimestampFrequency => TimeSpan.TicksPerSecond;
+
+        public override long GetTimestamp() => Interlocked.Read(ref _ticks);
+
+        public override DateTimeOffset GetUtcNow() => Epoch + TimeSpan.FromTicks(GetTimestamp());
+
+        public void Advance(TimeSpan by) => Interlocked.Add(ref _ticks, by.Ticks);
+
+        /// <summary>Wall-clock time at which the manual clock starts.</summary>
+        public static DateTimeOffset Epoch { get; } = new(2025, 1, 1, 9, 0, 0, TimeSpan.Zero);
+    }
+
+    /// <summary>A device claiming a 0 Hz format, delivering buffers until it's stopped.</summary>
+    private sealed class ZeroRateWaveIn : IWaveIn
+    {
+        private volatile bool _running;
+
+        public event EventHandler<WaveInEventArgs>? DataAvailable;
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = WaveFormat.CreateCustomFormat(
+            WaveFormatEncoding.IeeeFloat, sampleRate: 0, channels: 2, averageBytesPerSecond: 0, blockAlign: 8, bitsPerSample: 32);
+
+        public bool Stopped { get; private set; }
+
+        public void StartRecording()
+        {
+            _running = true;
+            _ = Task.Run(async () =>
+            {
+                var buffer = new byte[4800 * 8];
+                while (_running)
+                {
+                    DataAvailable?.Invoke(this, new WaveInEventArgs(buffer, buffer.Length));
+                    await Task.Delay(10);
+                }
+            });
+        }
+
+        public void StopRecording()
+        {
+            if (!_running) return;
+            Stopped = true;
+            _running = false;
+            RecordingStopped?.Invoke(this, new StoppedEventArgs());
+        }
+
+        public void Dispose() => _running = false;
+    }
+
+    /// <summary>
+    /// A loopback endpoint that goes away shortly after starting, as when the user
+    /// switches the default playback device.
+    /// </summary>
+    private sealed class InvalidatedWaveIn : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable { add { } remove { } }
+        public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+        public WaveFormat WaveFormat { get; set; } = WaveFormat.CreateIeeeFloatWaveFormat(48000, 2);
+
+        public void StartRecording() => Task.Delay(50).ContinueWith(_ => RecordingStopped?.Invoke(
+            this, new StoppedEventArgs(new COMException("AUDCLNT_E_DEVICE_INVALIDATED", unchecked((int)0x88890004)))));
+
+        public void StopRecording() { }
+
+        public void Dispose() { }
+    }
+
+    /// <summary>A mic held in exclusive mode elsewhere: it fails to start, as WASAPI does.</summary>
+    private sealed class BusyWaveIn : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable { add { } remove { } }
+        public event EventHandler<StoppedEventArgs>? RecordingStopped { add { } remove { } }
+
+        public WaveFormat WaveFormat { get; set; } = new(48000, 16, 1);
+
+        public void StartRecording() =>
+            throw new COMException("AUDCLNT_E_DEVICE_IN_USE", unchecked((int)0x8889000A));
+
+        public void StopRecording() { }
+
+        public void Dispose() { }
+    }
+
+    /// <summary>A mic the Windows privacy setting blocks: it fails to start with E_ACCESSDENIED.</summary>
+    private sealed class DeniedWaveIn : IWaveIn
+    {
+        public event EventHandler<WaveInEventArgs>? DataAvailable { add { } remove { } }
+        public event EventHandler<StoppedEventArgs>? RecordingStopped { add { } remove { } }
+
+        public WaveFormat WaveFormat { get; set; } = new(48000, 16, 1);
+
+        public void StartRecording() => throw new UnauthorizedAccessException("Access is denied.");
+
+        public void StopRecording() { }
+
+        public void Dispose() { }
+    }
+
+    /// <summary>Keeps every write, as a network sink would send it.</summary>
+    private sealed class CollectingSink : IOutputSink
+    {
+        private readonly List<byte[]> _writes = [];
+
+        public bool Opened { get; private set; }
+        public bool Closed { get; private set; }
+        public IReadOnlyList<byte[]> Writes => _writes;
+
+        public void Open(CaptureConfiguration config) => Opened = true;
+        public void Write(ReadOnlySpan<byte> data) => _writes.Add(data.ToArray());
+
+        public string Close()
+        {
+            Closed = true;
+            return "sink-checksum";
+        }
+    }
+
+    /// <summary>Encrypts the first few chunks, then throws as a failing HSM or revoked key would.</summary>
+    private sealed class FailingEncryptor(int succeedFor) : ICaptureEncryptor
+    {
+        private readonly AesGcmEncryptor _inner = new(RandomNumberGenerator.GetBytes(32));
+        private int _calls;
+
+        public string Algorithm => _inner.Algorithm;
+        public Dictionary<string, string> KeyMetadata => _inner.KeyMetadata;
+
+        public byte[] Encrypt(byte[] data) =>
+            Interlocked.Increment(ref _calls) <= succeedFor
+                ? _inner.Encrypt(data)
+                : throw new InvalidOperationException("key unavailable");
+    }
+
+    /// <summary>Collects delegate callbacks for assertions.</summary>
+    private sealed class RecordingDelegate : ICaptureDelegate
+    {
+        private readonly List<CaptureException> _errors = [];
+
+        public IReadOnlyList<CaptureException> Errors
+        {
+            get { lock (_errors) return [.. _errors]; }
+        }
+
+        /// <summary>Throw from every levels update, as a buggy UI handler might.</summary>
+        public bool ThrowOnLevels { get; init; }
+
+        public void OnStateChanged(CaptureState state) { }
+
+        public void OnLevelsUpdated(AudioLevels levels)
+        {
+            if (ThrowOnLevels) throw new InvalidOperationException("meter widget disposed");
+        }
+
+        public void OnError(CaptureException error) { lock (_errors) _errors.Add(error); }
+        public void OnCaptureFinished(RecordingResult result) { }
+    }
 }
@@ -0,0 +1,59 @@
+using System.Diagnostics;
+using AudioCapture.Interfaces;
+using AudioCapture.Processing;
+using Xunit;
+using Xunit.Abstractions;
+
+namespace AudioCapture.Tests;
+
+public class WdlAudioResamplerTests(ITestOutputHelper output)
+{
+    [Fact]
+    public void SwitchingBackToARate_ResamplesExactlyAsAFreshResamplerWould()
+    {
+        var wideband = Tone(48000, seconds: 0.2);
+        var handsFree = Tone(16000, seconds: 0.2);
+
+        var switching = new WdlAudioResampler();
+        switching.Process(wideband, 1, 48000, 44100);
+        switching.Process(handsFree, 1, 16000, 44100);
+        var reused = switching.Process(wideband, 1, 48000, 44100);
+
+        // The cached bank carries nothing over from the first stretch at 48 kHz.
+        Assert.Equal(new WdlAudioResampler().Process(wideband, 1, 48000, 44100), reused);
+    }
+
+    [Fact]
+    [Trait("Category", "Benchmark")]
+    public void Benchmark_RateSwitchesAgainstRebuildingAndLinear()
+    {
+        // A headset flapping between wideband and hands-free every 100 ms: the worst
+        // case for rebuilding the filter bank at each switch.
+        const int Switches = 200;
+        var wideband = Tone(48000, seconds: 0.1);
+        var handsFree = Tone(16000, seconds: 0.1);
+
+        var cached = new WdlAudioResampler();
+        var cachedTime = Time(_ => cached);
+        var rebuiltTime = Time(_ => new WdlAudioResampler());
+        var linear = new LinearResampler();
+        var linearTime = Time(_ => linear);
+
+        output.WriteLine($"{Switches} switches, 100 ms each: cached {cachedTime.TotalMilliseconds:F1} ms, " +
+            $"rebuilt {rebuiltTime.TotalMilliseconds:F1} ms, linear {linearTime.TotalMilliseconds:F1} ms");
+
+        TimeSpan Time(Func<int, IAudioResampler> resamplerFor)
+        {
+            var clock = Stopwatch.StartNew();
+            for (int i = 0; i < Switches; i++)
+            {
+                var (chunk, rate) = i % 2 == 0 ? (wideband, 48000) : (handsFree, 16000);
+                Assert.NotEmpty(resamplerFor(i).Process(chunk, 1, rate, 44100));
+            }
+            return clock.Elapsed;
+        }
+    }
+
+    private static float[] Tone(int sampleRate, double seconds) =>
+        [.. Enumerable.Range(0, (int)(sampleRate * seconds)).Select(i => (float)(0.5 * Math.Sin(2 * Math.PI * 440 * i / sampleRate)))];
+}
@@ -0,0 +1,61 @@
+using System.Runtime.InteropServices;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// The <c>AUDCLNT_E_*</c> HRESULTs the capture path tells apart. NAudio surfaces
+/// them as a bare <see cref="COMException"/>, so without this they all read as one
+/// generic failure.
+/// </summary>
+internal static class AudioClientErrors
+{
+    /// <summary>Another application holds the endpoint in exclusive mode.</summary>
+    public const int DeviceInUse = unchecked((int)0x8889000A);
+
+    /// <summary>The client can't be initialized with the requested format.</summary>
+    public const int UnsupportedFormat = unchecked((int)0x88890008);
+
+    /// <summary>The endpoint went away or was reconfigured — mid device switch, for one.</summary>
+    public const int DeviceInvalidated = unchecked((int)0x88890004);
+
+    /// <summary>
+    /// E_ACCESSDENIED: the Windows microphone privacy setting blocks this app. The
+    /// runtime maps it to <see cref="UnauthorizedAccessException"/>, not a
+    /// <see cref="COMException"/>, so matching is by HRESULT alone.
+    /// </summary>
+    public const int AccessDenied = unchecked((int)0x80070005);
+
+    /// <summary>Whether <paramref name="ex"/> is, or wraps, <see cref="DeviceInUse"/>.</summary>
+    public static bool IsDeviceInUse(Exception ex) => HasHResult(ex, DeviceInUse);
+
+    /// <summary>Whether <paramref name="ex"/> is, or wraps, <see cref="DeviceInvalidated"/>.</summary>
+    public static bool IsDeviceInvalidated(Exception ex) => HasHResult(ex, DeviceInvalidated);
+
+    /// <summary>
+    /// Whether opening a client failed in a way that often clears by itself within
+    /// moments: the endpoint busy or invalidated while a device switch or a
+    /// Bluetooth connection settles.
+    /// </summary>
+    public static bool IsTransientOpenFailure(Exception ex) =>
+        HasHResult(ex, DeviceInUse) || HasHResult(ex, DeviceInvalidated);
+
+    /// <summary>
+    /// Whether <paramref name="ex"/> is, or wraps, <see cref="UnsupportedFormat"/>.
+    /// NAudio's own format check is reported the same way by
+    /// <see cref="ConfiguredWasapiCapture"/>.
+    /// </summary>
+    public static bool IsUnsupportedFormat(Exception ex) => HasHResult(ex, UnsupportedFormat);
+
+    /// <summary>Whether <paramref name="ex"/> is, or wraps, <see cref="AccessDenied"/>.</summary>
+    public static bool IsAccessDenied(Exception ex) => HasHResult(ex, AccessDenied);
+
+    private static bool HasHResult(Exception? ex, int hresult)
+    {
+        for (; ex != null; ex = ex.InnerException)
+        {
+            if (ex.HResult == hresult)
+                return true;
+        }
+        return false;
+    }
+}
@@ -0,0 +1,71 @@
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// Where the library's COM work runs: the Core Audio enumerator, endpoint lookups,
+/// and opening and starting WASAPI clients all happen in the multithreaded
+/// apartment, whatever apartment the caller is in.
+/// </summary>
+/// <remarks>
+/// <para><b>The threading contract.</b> Every public entry point may be called from
+/// any thread, STA or MTA, and <see cref="DeviceEnumerator"/>'s synchronous methods
+/// stay in the caller's apartment; docs/API.md spells this out for callers. The
+/// library never calls <c>CoInitializeEx</c> on a caller's thread, so it can't trip
+/// <c>RPC_E_CHANGED_MODE</c> in a host that initialized COM its own way. Other work
+/// that creates or initializes a COM object runs on the calling thread when that is
+/// already MTA, and otherwise on a dedicated MTA thread of its own while the caller
+/// waits. Core Audio's objects are free-threaded once created, so later calls on
+/// them — stopping, disposing — go direct from wherever they're made, and capture
+/// callbacks arrive on NAudio's own MTA capture threads.</para>
+///
+/// <para>Not the thread pool: pool threads' apartment isn't ours to set, and an STA
+/// caller's COM objects would marshal every call back to its blocked thread.</para>
+/// </remarks>
+internal static class ComThread
+{
+    /// <summary>
+    /// Runs <paramref name="work"/> in the MTA: inline unless the caller is STA, else
+    /// on a dedicated thread, blocking until done. A thread whose apartment is still
+    /// unset joins the MTA on its first COM call, so it counts as there already.
+    /// </summary>
+    public static T Run<T>(Func<T> work)
+    {
+        if (Thread.CurrentThread.GetApartmentState() != ApartmentState.STA)
+            return work();
+        return RunAsync(work).GetAwaiter().GetResult();
+    }
+
+    /// <summary>As <see cref="Run{T}"/>, for work with no result.</summary>
+    public static void Run(Action work) => Run(() =>
+    {
+        work();
+        return true;
+    });
+
+    /// <summary>
+    /// Runs <paramref name="work"/> on a fresh background MTA thread, always — for the
+    /// <c>*Async</c> APIs, which mustn't hold up even an MTA caller.
+    /// </summary>
+    public static Task<T> RunAsync<T>(Func<T> work)
+    {
+        var tcs = new TaskCompletionSource<T>(TaskCreationOptions.RunContinuationsAsynchronously);
+        var thread = new Thread(() =>
+        {
+            try
+            {
+                tcs.SetResult(work());
+            }
+            catch (Exception ex)
+            {
+                tcs.SetException(ex);
+            }
+        })
+        {
+            IsBackground = true,
+            Name = "AudioCapture COM",
+        };
+        if (OperatingSystem.IsWindows())
+            thread.SetApartmentState(ApartmentState.MTA);
+        thread.Start();
+        return tcs.Task;
+    }
+}
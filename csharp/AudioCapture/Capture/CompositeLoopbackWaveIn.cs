@@ -0,0 +1,164 @@
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using NAudio.Wave;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// Presents loopback on several render endpoints as one system-audio source — for
+/// setups where sound is split across outputs, speakers and an HDMI display say,
+/// and all of it belongs in the recording.
+///
+/// <para>Each input keeps its own mix format. Its buffers are folded to stereo and
+/// resampled to <see cref="WaveFormat"/>'s rate by a
+/// <see cref="SystemAudioNormalizer"/> of its own, then the inputs are summed frame
+/// for frame at unity gain, as the outputs would sound together. Output is 32-bit
+/// float stereo, which the session's own normalizer passes straight through.</para>
+///
+/// <para><b>Alignment.</b> Loopback sends nothing at all while an output is idle,
+/// so a silent output contributes nothing and mustn't hold the others back: inputs
+/// are summed in lockstep, but one more than 100 ms behind the busiest is
+/// zero-filled. Output therefore trails the busiest input by up to that much
+/// whenever another is idle.</para>
+///
+/// <para><b>Cost.</b> One WASAPI client, capture thread and, for an output not at
+/// the target rate, resampler per endpoint — a few percent of a core apiece.</para>
+/// </summary>
+public sealed class CompositeLoopbackWaveIn : CompositeWaveIn
+{
+    // How far an input may lag before it is treated as idle, in seconds.
+    private const double IdleGuardSeconds = 0.1;
+    private const int Channels = SystemAudioNormalizer.TargetChannels;
+
+    private readonly LoopbackInput[] _inputs;
+    private readonly Func<IAudioResampler>? _resamplerFactory;
+
+    /// <param name="sources">The loopback sources to combine. The composite owns them and disposes them with itself.</param>
+    /// <param name="sampleRate">Rate every input is resampled to before summing.</param>
+    /// <param name="resamplerFactory">
+    /// Makes the resampler for each input whose rate differs; a
+    /// <see cref="WdlAudioResampler"/> when null.
+    /// </param>
+    public CompositeLoopbackWaveIn(
+        IReadOnlyList<IWaveIn> sources, int sampleRate, Func<IAudioResampler>? resamplerFactory = null)
+        : base(CheckSources(sources), WaveFormat.CreateIeeeFloatWaveFormat(ValidRate(sampleRate), Channels))
+    {
+        _inputs = [.. sources.Select(source => new LoopbackInput(source))];
+        _resamplerFactory = resamplerFactory;
+    }
+
+    // --- Composite hooks ---
+
+    private protected override void CheckOutputFormat(WaveFormat format)
+    {
+        if (format.Encoding != WaveFormatEncoding.IeeeFloat || format.BitsPerSample != 32 || format.Channels != Channels)
+            throw new ArgumentException("A composite loopback emits 32-bit float stereo only.", nameof(format));
+    }
+
+    private protected override void ResetInputs(int sampleRate)
+    {
+        foreach (var input in _inputs)
+            input.Reset(sampleRate, _resamplerFactory);
+    }
+
+    private protected override byte[]? AppendAndTake(int input, byte[] buffer, int bytesRecorded)
+    {
+        _inputs[input].Append(buffer, bytesRecorded);
+        return TakeAligned();
+    }
+
+    // Each input's stop counts once.
+    private protected override bool CountsAsStopped(int input, StoppedEventArgs e)
+    {
+        if (_inputs[input].Stopped) return false;
+        _inputs[input].Stopped = true;
+        return true;
+    }
+
+    // --- Private helpers ---
+
+    private static IReadOnlyList<IWaveIn> CheckSources(IReadOnlyList<IWaveIn> sources)
+    {
+        ArgumentNullException.ThrowIfNull(sources);
+        if (sources.Count == 0)
+            throw new ArgumentException("A composite loopback needs at least one source.", nameof(sources));
+        return sources;
+    }
+
+    private static int ValidRate(int sampleRate)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sampleRate);
+        return sampleRate;
+    }
+
+    /// <summary>
+    /// Sums the frames every busy input has delivered as float PCM bytes, or returns
+    /// null when there is nothing to emit yet. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private byte[]? TakeAligned()
+    {
+        var shortest = _inputs.Min(i => i.Pending.Count) / Channels;
+        var longest = _inputs.Max(i => i.Pending.Count) / Channels;
+        var idleGuard = (int)(_waveFormat.SampleRate * IdleGuardSeconds);
+        var frames = Math.Max(shortest, longest - idleGuard);
+        if (frames <= 0) return null;
+
+        var mixed = new float[frames * Channels];
+        foreach (var input in _inputs)
+        {
+            var available = Math.Min(mixed.Length, input.Pending.Count);
+            for (int i = 0; i < available; i++)
+                mixed[i] += input.Pending[i];
+            input.Pending.RemoveRange(0, available);
+        }
+
+        var bytes = new byte[mixed.Length * 4];
+        Buffer.BlockCopy(mixed, 0, bytes, 0, bytes.Length);
+        return bytes;
+    }
+
+    /// <summary>One loopback source, and the stereo samples it has delivered but not yet emitted.</summary>
+    private sealed class LoopbackInput(IWaveIn source)
+    {
+        private SystemAudioNormalizer? _normalizer;
+
+        public IWaveIn Source { get; } = source;
+        public List<float> Pending { get; } = [];
+        public bool Stopped { get; set; }
+
+        /// <summary>Drops carried samples and rebuilds the normalizer for a new run.</summary>
+        public void Reset(int targetRate, Func<IAudioResampler>? resamplerFactory)
+        {
+            Pending.Clear();
+            Stopped = false;
+            var format = Source.WaveFormat;
+            _normalizer = new SystemAudioNormalizer(
+                format.SampleRate, format.Channels, targetRate,
+                format.SampleRate == targetRate ? null : resamplerFactory?.Invoke());
+        }
+
+        public void Append(byte[] buffer, int bytesRecorded) =>
+            Pending.AddRange(_normalizer!.Normalize(Decode(buffer, bytesRecorded, Source.WaveFormat)));
+
+        /// <summary>Decodes 32-bit float or 16-bit PCM, the formats loopback delivers.</summary>
+        private static float[] Decode(byte[] buffer, int bytesRecorded, WaveFormat format)
+        {
+            if (format.Encoding == WaveFormatEncoding.IeeeFloat && format.BitsPerSample == 32)
+            {
+                var floats = new float[bytesRecorded / 4];
+                Buffer.BlockCopy(buffer, 0, floats, 0, floats.Length * 4);
+                return floats;
+            }
+            if (format.BitsPerSample == 16)
+            {
+                var samples = new float[bytesRecorded / 2];
+                for (int i = 0; i < samples.Length; i++)
+                    samples[i] = (short)(buffer[i * 2] | (buffer[i * 2 + 1] << 8)) / (float)short.MaxValue;
+                return samples;
+            }
+            throw CaptureException.DeviceNotAvailable(
+                $"Unsupported loopback format: {format.Encoding} {format.BitsPerSample}-bit");
+        }
+    }
+}
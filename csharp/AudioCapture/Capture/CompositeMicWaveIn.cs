@@ -0,0 +1,291 @@
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+using AudioCapture.Processing;
+using NAudio.Wave;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// Presents several microphones as one mono mic source — for setups where, say, an
+/// interviewer and an interviewee each have their own USB mic and both belong in
+/// the session's mic slot.
+///
+/// <para>Each input keeps its own native format. Its buffers are folded to mono (see
+/// <see cref="MicDownmix"/>) and
+/// resampled to <see cref="WaveFormat"/>'s rate, then the inputs are summed frame
+/// for frame with their gains. Output is 16-bit mono PCM, the shape the session
+/// expects from a mic.</para>
+///
+/// <para><b>Alignment.</b> Inputs are summed in lockstep: only frames every input
+/// has delivered are emitted, the rest carried to the next callback. An input that
+/// falls more than half a second behind is treated as stalled and zero-filled, so
+/// one unplugged mic doesn't hold the others back indefinitely.</para>
+///
+/// <para><b>Pausing.</b> Pauses every input that supports it and drops what the
+/// others deliver meanwhile. Resuming starts alignment afresh, as a new recording
+/// would.</para>
+/// </summary>
+public sealed class CompositeMicWaveIn : CompositeWaveIn, IPausableWaveIn
+{
+    // Same bound WasapiCaptureSession puts on a stalled partner, in seconds.
+    private const double StallGuardSeconds = 0.5;
+
+    private readonly MicInput[] _inputs;
+    private bool _paused;
+
+    /// <param name="sources">The mics to combine. The composite owns them and disposes them with itself.</param>
+    /// <param name="sampleRate">Rate every input is resampled to before summing.</param>
+    /// <param name="gains">
+    /// Per-source gain, in source order. Defaults to 1 / N so the sum of N full-scale
+    /// mics can't clip.
+    /// </param>
+    /// <param name="downmix">How each multichannel input is folded to mono.</param>
+    /// <param name="resamplerFactory">
+    /// Makes the resampler for each input whose rate differs; a
+    /// <see cref="WdlAudioResampler"/> when null.
+    /// </param>
+    public CompositeMicWaveIn(
+        IReadOnlyList<IWaveIn> sources, int sampleRate, IReadOnlyList<float>? gains = null,
+        MicDownmix downmix = MicDownmix.Average, Func<IAudioResampler>? resamplerFactory = null)
+        : base(CheckSources(sources, gains), new WaveFormat(ValidRate(sampleRate), 16, 1))
+    {
+        _inputs = sources
+            .Select((source, i) => new MicInput(source, gains?[i] ?? 1f / sources.Count, downmix, resamplerFactory))
+            .ToArray();
+    }
+
+    /// <summary>
+    /// Stops every source; <see cref="CompositeWaveIn.RecordingStopped"/> fires once all
+    /// have stopped, or at once when paused.
+    /// </summary>
+    public override void StopRecording()
+    {
+        bool wasPaused;
+        lock (_lock)
+        {
+            wasPaused = _paused;
+            _paused = false;
+            if (wasPaused) _isRecording = false;
+        }
+        base.StopRecording();
+
+        // A paused input may have stopped already, with nothing left to report, so
+        // the composite reports the stop itself.
+        if (wasPaused)
+            RaiseRecordingStopped(null);
+    }
+
+    public void Pause()
+    {
+        lock (_lock)
+        {
+            if (!_isRecording || _paused) return;
+            _paused = true;
+        }
+        foreach (var input in _inputs)
+            (input.Source as IPausableWaveIn)?.Pause();
+    }
+
+    public void Resume()
+    {
+        lock (_lock)
+        {
+            if (!_paused) return;
+        }
+
+        // Inputs first: an input's pause may still be reporting its stop, and that
+        // stop is only ignored while paused.
+        foreach (var input in _inputs)
+            (input.Source as IPausableWaveIn)?.Resume();
+
+        lock (_lock) ResetInputs(_waveFormat.SampleRate);
+    }
+
+    // --- Composite hooks ---
+
+    private protected override void CheckOutputFormat(WaveFormat format)
+    {
+        if (format.Encoding != WaveFormatEncoding.Pcm || format.BitsPerSample != 16 || format.Channels != 1)
+            throw new ArgumentException("A composite mic emits 16-bit mono PCM only.", nameof(format));
+    }
+
+    /// <summary>
+    /// Rejects a source whose format <see cref="MicInput"/> can't decode, so a bad
+    /// layout fails the start instead of throwing from a capture callback.
+    /// </summary>
+    /// <exception cref="CaptureException">ConfigurationFailed, naming the mic and its format.</exception>
+    private protected override void ValidateInputs()
+    {
+        for (int i = 0; i < _inputs.Length; i++)
+        {
+            var format = _inputs[i].Source.WaveFormat;
+            if (format.SampleRate <= 0 || format.Channels < 1 || !(IsFloat32(format) || IsPcm16(format)))
+                throw CaptureException.ConfigurationFailed(
+                    $"Mic {i + 1} delivers an unsupported format: {format.Encoding} " +
+                    $"{format.BitsPerSample}-bit, {format.Channels} ch at {format.SampleRate} Hz");
+        }
+    }
+
+    private protected override void ResetInputs(int sampleRate)
+    {
+        _paused = false;
+        foreach (var input in _inputs)
+            input.Reset(sampleRate);
+    }
+
+    private protected override bool IsDelivering => _isRecording && !_paused;
+
+    private protected override byte[]? AppendAndTake(int input, byte[] buffer, int bytesRecorded)
+    {
+        _inputs[input].Append(buffer, bytesRecorded);
+        return TakeAligned();
+    }
+
+    // An input pausing, not ending — or stopping after the composite already has.
+    private protected override bool CountsAsStopped(int input, StoppedEventArgs e) =>
+        !((_paused || !_isRecording) && e.Exception == null);
+
+    // --- Private helpers ---
+
+    private static IReadOnlyList<IWaveIn> CheckSources(IReadOnlyList<IWaveIn> sources, IReadOnlyList<float>? gains)
+    {
+        ArgumentNullException.ThrowIfNull(sources);
+        if (sources.Count == 0)
+            throw new ArgumentException("A composite mic needs at least one source.", nameof(sources));
+        if (gains != null && gains.Count != sources.Count)
+            throw new ArgumentException("Provide one gain per source.", nameof(gains));
+        return sources;
+    }
+
+    private static int ValidRate(int sampleRate)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sampleRate);
+        return sampleRate;
+    }
+
+    // WASAPI mix formats come as WAVE_FORMAT_EXTENSIBLE, where 32 bits means float.
+    private static bool IsFloat32(WaveFormat format) =>
+        format.BitsPerSample == 32 && format.Encoding is WaveFormatEncoding.IeeeFloat or WaveFormatEncoding.Extensible;
+
+    private static bool IsPcm16(WaveFormat format) =>
+        format.BitsPerSample == 16 && format.Encoding is WaveFormatEncoding.Pcm or WaveFormatEncoding.Extensible;
+
+    /// <summary>
+    /// Sums the frames every input has delivered and encodes them as 16-bit PCM, or
+    /// returns null when some input has nothing yet. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private byte[]? TakeAligned()
+    {
+        var shortest = _inputs.Min(i => i.Pending.Count);
+        var longest = _inputs.Max(i => i.Pending.Count);
+        var stallGuard = (int)(_waveFormat.SampleRate * StallGuardSeconds);
+        var frames = Math.Max(shortest, longest - stallGuard);
+        if (frames <= 0) return null;
+
+        var mixed = new float[frames];
+        foreach (var input in _inputs)
+        {
+            var available = Math.Min(frames, input.Pending.Count);
+            for (int i = 0; i < available; i++)
+                mixed[i] += input.Pending[i] * input.Gain;
+            input.Pending.RemoveRange(0, available);
+        }
+        return StereoMixer.ConvertToInt16Pcm(mixed);
+    }
+
+    /// <summary>One source, and the mono frames it has delivered but not yet emitted.</summary>
+    private sealed class MicInput(
+        IWaveIn source, float gain, MicDownmix downmix, Func<IAudioResampler>? resamplerFactory)
+    {
+        // Below about -80 dBFS for a whole buffer, a channel counts as dead for Detect.
+        private const float SilentChannelPeak = 1e-4f;
+
+        private IAudioResampler? _resampler;
+        private int _targetRate;
+
+        public IWaveIn Source { get; } = source;
+        public float Gain { get; } = gain;
+        public List<float> Pending { get; } = [];
+
+        /// <summary>Drops carried frames and rebuilds the resampler for a new run.</summary>
+        public void Reset(int targetRate)
+        {
+            Pending.Clear();
+            _targetRate = targetRate;
+            _resampler = Source.WaveFormat.SampleRate == targetRate
+                ? null
+                : resamplerFactory?.Invoke() ?? new WdlAudioResampler();
+        }
+
+        public void Append(byte[] buffer, int bytesRecorded)
+        {
+            var mono = ToMono(buffer, bytesRecorded, Source.WaveFormat, downmix);
+            if (_resampler == null)
+            {
+                Pending.AddRange(mono);
+                return;
+            }
+
+            Pending.AddRange(_resampler.Process(mono, 1, Source.WaveFormat.SampleRate, _targetRate));
+        }
+
+        /// <summary>
+        /// Decodes 32-bit float or, failing that, 16-bit PCM — <see cref="ValidateInputs"/>
+        /// admits nothing else — and folds the channels per <paramref name="downmix"/>.
+        /// </summary>
+        private static float[] ToMono(byte[] buffer, int bytesRecorded, WaveFormat format, MicDownmix downmix)
+        {
+            var channels = format.Channels;
+            float[] samples;
+            if (IsFloat32(format))
+            {
+                samples = new float[bytesRecorded / 4];
+                Buffer.BlockCopy(buffer, 0, samples, 0, samples.Length * 4);
+            }
+            else
+            {
+                samples = new float[bytesRecorded / 2];
+                for (int i = 0; i < samples.Length; i++)
+                    samples[i] = (short)(buffer[i * 2] | (buffer[i * 2 + 1] << 8)) / (float)short.MaxValue;
+            }
+
+            if (channels == 1) return samples;
+
+            var frames = samples.Length / channels;
+            var included = new bool[channels];
+            for (int ch = 0; ch < channels; ch++)
+                included[ch] = downmix != MicDownmix.First || ch == 0;
+            if (downmix == MicDownmix.Detect)
+                ExcludeSilentChannels(samples, channels, included);
+            var count = included.Count(c => c);
+
+            var mono = new float[frames];
+            for (int i = 0; i < frames; i++)
+            {
+                float sum = 0;
+                for (int ch = 0; ch < channels; ch++)
+                {
+                    if (included[ch])
+                        sum += samples[i * channels + ch];
+                }
+                mono[i] = sum / count;
+            }
+            return mono;
+        }
+
+        /// <summary>
+        /// Clears <paramref name="included"/> for channels silent throughout the buffer,
+        /// unless that would leave none.
+        /// </summary>
+        private static void ExcludeSilentChannels(float[] samples, int channels, bool[] included)
+        {
+            var peaks = new float[channels];
+            for (int i = 0; i < samples.Length; i++)
+                peaks[i % channels] = Math.Max(peaks[i % channels], Math.Abs(samples[i]));
+            if (peaks.All(p => p < SilentChannelPeak))
+                return;
+            for (int ch = 0; ch < channels; ch++)
+                included[ch] = peaks[ch] >= SilentChannelPeak;
+        }
+    }
+}
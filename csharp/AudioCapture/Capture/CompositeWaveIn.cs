@@ -0,0 +1,205 @@
+using NAudio.Wave;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// What <see cref="CompositeMicWaveIn"/> and <see cref="CompositeLoopbackWaveIn"/>
+/// share: several sources started, stopped and disposed as one, their buffers
+/// gathered under one lock, and the combined chunks raised in the order they were
+/// completed whichever input's thread completed them.
+///
+/// <para>Subclasses decide how an input's buffer is decoded and when enough has
+/// arrived to emit; see <see cref="AppendAndTake"/>.</para>
+/// </summary>
+public abstract class CompositeWaveIn : IWaveIn
+{
+    private readonly IWaveIn[] _sources;
+
+    // Held from taking a chunk until it has been raised, so chunks completed on
+    // different inputs' threads can't overtake one another.
+    private readonly object _emitLock = new();
+
+    private bool _disposed;
+
+    // Guards the recording state here and each subclass's inputs.
+    private protected readonly object _lock = new();
+    private protected WaveFormat _waveFormat;
+    private protected bool _isRecording;
+    private int _stoppedInputs;
+    private Exception? _stopFailure;
+
+    public event EventHandler<WaveInEventArgs>? DataAvailable;
+    public event EventHandler<StoppedEventArgs>? RecordingStopped;
+
+    /// <param name="sources">The sources to combine. The composite owns them and disposes them with itself.</param>
+    /// <param name="waveFormat">The output format.</param>
+    private protected CompositeWaveIn(IReadOnlyList<IWaveIn> sources, WaveFormat waveFormat)
+    {
+        _sources = [.. sources];
+        _waveFormat = waveFormat;
+
+        for (int i = 0; i < _sources.Length; i++)
+        {
+            var index = i;
+            _sources[i].DataAvailable += (_, e) => OnInputData(index, e);
+            _sources[i].RecordingStopped += (_, e) => OnInputStopped(index, e);
+        }
+    }
+
+    /// <summary>
+    /// Output format. Only the rate may be changed, and not while recording.
+    /// </summary>
+    public WaveFormat WaveFormat
+    {
+        get { lock (_lock) return _waveFormat; }
+        set
+        {
+            ArgumentNullException.ThrowIfNull(value);
+            CheckOutputFormat(value);
+            lock (_lock)
+            {
+                if (_isRecording)
+                    throw new InvalidOperationException("Cannot change WaveFormat while recording");
+                _waveFormat = value;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Starts every source. If one fails to start, those already started are stopped
+    /// again and the failure propagates — a half-started composite would silently
+    /// leave an input out.
+    /// </summary>
+    public void StartRecording()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ValidateInputs();
+
+        lock (_lock)
+        {
+            if (_isRecording) return;
+            _isRecording = true;
+            _stoppedInputs = 0;
+            _stopFailure = null;
+            ResetInputs(_waveFormat.SampleRate);
+        }
+
+        var started = new List<IWaveIn>();
+        try
+        {
+            foreach (var source in _sources)
+            {
+                source.StartRecording();
+                started.Add(source);
+            }
+        }
+        catch
+        {
+            lock (_lock) _isRecording = false;
+            foreach (var source in started)
+            {
+                try { source.StopRecording(); }
+                catch (Exception) { /* the start failure is the one worth reporting */ }
+            }
+            throw;
+        }
+    }
+
+    /// <summary>Stops every source; <see cref="RecordingStopped"/> fires once all have stopped.</summary>
+    public virtual void StopRecording()
+    {
+        foreach (var source in _sources)
+            source.StopRecording();
+    }
+
+    public void Dispose()
+    {
+        if (_disposed) return;
+        _disposed = true;
+        foreach (var source in _sources)
+            source.Dispose();
+        GC.SuppressFinalize(this);
+    }
+
+    // --- Subclass hooks ---
+
+    /// <summary>Throws <see cref="ArgumentException"/> for an output format the composite can't emit.</summary>
+    private protected abstract void CheckOutputFormat(WaveFormat format);
+
+    /// <summary>Rejects, before any source starts, an input format the composite can't decode.</summary>
+    private protected virtual void ValidateInputs() { }
+
+    /// <summary>Drops carried samples and readies each input for <paramref name="sampleRate"/>. Holds <see cref="_lock"/>.</summary>
+    private protected abstract void ResetInputs(int sampleRate);
+
+    /// <summary>Whether buffers arriving now belong in the output. Holds <see cref="_lock"/>.</summary>
+    private protected virtual bool IsDelivering => _isRecording;
+
+    /// <summary>
+    /// Adds input <paramref name="input"/>'s buffer to what it has pending and takes
+    /// the chunk that completes, or null when there is nothing to emit yet. Holds
+    /// <see cref="_lock"/>.
+    /// </summary>
+    private protected abstract byte[]? AppendAndTake(int input, byte[] buffer, int bytesRecorded);
+
+    /// <summary>
+    /// Whether input <paramref name="input"/> stopping counts toward the composite's
+    /// own stop, noting it if so. Holds <see cref="_lock"/>.
+    /// </summary>
+    private protected abstract bool CountsAsStopped(int input, StoppedEventArgs e);
+
+    /// <summary>Raises <see cref="RecordingStopped"/>, for a stop the composite reports itself.</summary>
+    private protected void RaiseRecordingStopped(Exception? failure) =>
+        RecordingStopped?.Invoke(this, new StoppedEventArgs(failure));
+
+    // --- Private helpers ---
+
+    private void OnInputData(int input, WaveInEventArgs e)
+    {
+        if (e.BytesRecorded == 0) return;
+
+        lock (_emitLock)
+        {
+            byte[]? output;
+            lock (_lock)
+            {
+                if (!IsDelivering) return;
+                output = AppendAndTake(input, e.Buffer, e.BytesRecorded);
+            }
+
+            // Raised outside _lock, so a handler may stop or pause the composite.
+            if (output != null)
+                DataAvailable?.Invoke(this, new WaveInEventArgs(output, output.Length));
+        }
+    }
+
+    private void OnInputStopped(int input, StoppedEventArgs e)
+    {
+        bool allStopped;
+        Exception? failure;
+        lock (_lock)
+        {
+            if (!CountsAsStopped(input, e)) return;
+            _stopFailure ??= e.Exception;
+            allStopped = ++_stoppedInputs == _sources.Length;
+            failure = _stopFailure;
+            if (allStopped) _isRecording = false;
+        }
+
+        // One input failing — unplugged, most likely — ends the composite: carrying
+        // on without it would drop a speaker or an output unnoticed, and the session
+        // can reopen on whatever is left.
+        if (e.Exception != null)
+        {
+            for (int i = 0; i < _sources.Length; i++)
+            {
+                if (i == input) continue;
+                try { _sources[i].StopRecording(); }
+                catch (Exception) { /* already reporting a failure */ }
+            }
+        }
+
+        if (allStopped)
+            RaiseRecordingStopped(failure);
+    }
+}
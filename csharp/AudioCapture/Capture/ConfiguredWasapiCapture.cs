@@ -0,0 +1,119 @@
+using System.Runtime.InteropServices;
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+using NAudio.CoreAudioApi;
+using NAudio.Wave;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// <c>WasapiCapture</c> with a caller-chosen WASAPI buffer length and stream flags,
+/// for both the mic and loopback.
+///
+/// <c>WasapiLoopbackCapture</c> only exposes the 100 ms default. Loopback is
+/// nothing more than <c>WasapiCapture</c> on a render endpoint with the loopback
+/// stream flag set, so this re-adds the flag on top of the base constructor that
+/// does take a buffer length. The remaining flags are NAudio's unless overridden;
+/// the event-callback flag stays NAudio's to add, as it depends on the sync mode.
+///
+/// The packet loop — <c>GetNextPacketSize</c>, <c>GetBuffer</c>, <c>ReleaseBuffer</c> —
+/// is NAudio's and private to it, so how an <c>AUDCLNT_S_BUFFER_EMPTY</c> from
+/// <c>GetBuffer</c> is released can't be changed from here. NAudio only calls
+/// <c>GetBuffer</c> after a nonzero packet size and releases exactly the frames it
+/// was given; a driver that rejects the resulting zero-frame release stops the
+/// source with that error, reported through <c>OnError</c> like any other.
+///
+/// <para>Pausing stops the client with <c>StopRecording</c> and resuming starts it
+/// again. NAudio initializes the client once and only stops it when its capture
+/// thread exits, so the stream survives the pause; the thread does not, and raises
+/// <c>RecordingStopped</c> without an exception as it goes. Resuming waits until
+/// every handler of that event has returned, so none sees it after the resume.</para>
+/// </summary>
+internal sealed class ConfiguredWasapiCapture : WasapiCapture, IPausableWaveIn
+{
+    // The capture thread notices a stop within half a buffer; this is a backstop.
+    private static readonly TimeSpan StopTimeout = TimeSpan.FromSeconds(2);
+
+    private readonly bool _loopback;
+    private readonly WasapiStreamFlags? _streamFlags;
+    private readonly ManualResetEventSlim _pauseStopped = new();
+    private bool _paused;
+
+    /// <param name="device">The capture endpoint, or the render endpoint whose output is captured.</param>
+    /// <param name="bufferMilliseconds">Length of the WASAPI shared-mode buffer.</param>
+    /// <param name="loopback">Capture <paramref name="device"/>'s output rather than its input.</param>
+    /// <param name="streamFlags">Replaces NAudio's default flags; null keeps them.</param>
+    public ConfiguredWasapiCapture(MMDevice device, int bufferMilliseconds, bool loopback, WasapiStreamFlags? streamFlags)
+        : base(device, useEventSync: false, bufferMilliseconds)
+    {
+        _loopback = loopback;
+        _streamFlags = streamFlags;
+    }
+
+    /// <summary>
+    /// Starts the client. NAudio asks <c>IsFormatSupported</c> before initializing
+    /// and refuses a format with a bare <see cref="ArgumentException"/>; that is
+    /// rethrown as the <c>AUDCLNT_E_UNSUPPORTED_FORMAT</c> WASAPI itself would have
+    /// returned, so the session recognizes a format refusal by HRESULT alone.
+    /// </summary>
+    void IWaveIn.StartRecording()
+    {
+        try
+        {
+            StartRecording();
+        }
+        catch (ArgumentException ex)
+        {
+            throw new COMException(ex.Message, AudioClientErrors.UnsupportedFormat);
+        }
+    }
+
+    public void Pause()
+    {
+        if (CaptureState != NAudio.CoreAudioApi.CaptureState.Capturing) return;
+        _paused = true;
+        _pauseStopped.Reset();
+        // Subscribed last, so it runs after every other handler.
+        RecordingStopped += OnPauseStopped;
+        StopRecording();
+    }
+
+    /// <summary>
+    /// Restarts the client once the paused capture thread has exited. Does nothing
+    /// unless <see cref="Pause"/> stopped it.
+    /// </summary>
+    public void Resume()
+    {
+        if (!_paused) return;
+        _paused = false;
+        if (!_pauseStopped.Wait(StopTimeout))
+            throw CaptureException.DeviceNotAvailable("The capture thread did not stop at pause; cannot resume");
+        StartRecording();
+    }
+
+    private void OnPauseStopped(object? sender, StoppedEventArgs e)
+    {
+        RecordingStopped -= OnPauseStopped;
+        _pauseStopped.Set();
+    }
+
+    protected override AudioClientStreamFlags GetAudioClientStreamFlags()
+    {
+        var flags = _streamFlags is { } chosen ? ToAudioClientFlags(chosen) : base.GetAudioClientStreamFlags();
+        return _loopback ? flags | AudioClientStreamFlags.Loopback : flags;
+    }
+
+    private static AudioClientStreamFlags ToAudioClientFlags(WasapiStreamFlags flags)
+    {
+        var mapped = AudioClientStreamFlags.None;
+        if (flags.HasFlag(WasapiStreamFlags.NoPersist))
+            mapped |= AudioClientStreamFlags.NoPersist;
+        if (flags.HasFlag(WasapiStreamFlags.RateAdjust))
+            mapped |= AudioClientStreamFlags.RateAdjust;
+        if (flags.HasFlag(WasapiStreamFlags.AutoConvertPcm))
+            mapped |= AudioClientStreamFlags.AutoConvertPcm;
+        if (flags.HasFlag(WasapiStreamFlags.SrcDefaultQuality))
+            mapped |= AudioClientStreamFlags.SrcDefaultQuality;
+        return mapped;
+    }
+}
@@ -1,62 +1,419 @@
 using AudioCapture.Models;
 using NAudio.CoreAudioApi;
+using NAudio.Wave;
 
 namespace AudioCapture.Capture;
 
 /// <summary>
 /// Enumerates available audio devices via NAudio's MMDeviceEnumerator.
 /// </summary>
+/// <remarks>
+/// No COM setup is needed from callers: the runtime initializes COM on every
+/// managed thread before its first COM call — in the thread's apartment, MTA unless
+/// it was marked STA. The synchronous methods run in the calling thread's apartment,
+/// which on an STA UI thread means blocking it for the duration. The <c>*Async</c>
+/// variants always run on a dedicated MTA thread of their own, whatever the caller;
+/// see <see cref="ComThread"/> for the library-wide contract.
+/// </remarks>
 public static class DeviceEnumerator
 {
+    // What GetSupportedFormats asks about: the rates and depths a format picker offers.
+    private static readonly int[] ProbeSampleRates = [16000, 44100, 48000, 96000];
+    private static readonly int[] ProbeChannelCounts = [1, 2];
+    private static readonly int[] ProbeBitDepths = [16, 24, 32]; // 32 as float
+
     /// <summary>
-    /// Returns all active audio capture (mic) devices.
+    /// Returns all active audio capture (mic) devices, flagging the default for
+    /// <paramref name="role"/>.
     /// </summary>
-    public static AudioSource[] GetCaptureDevices()
+    public static AudioSource[] GetCaptureDevices(DeviceRole role = DeviceRole.Multimedia)
     {
         using var enumerator = new MMDeviceEnumerator();
-        var devices = enumerator.EnumerateAudioEndPoints(DataFlow.Capture, DeviceState.Active);
-        var defaultId = GetDefaultDeviceId(enumerator, DataFlow.Capture);
+        var defaultId = GetDefaultDeviceId(enumerator, DataFlow.Capture, role);
+        return ReadActiveDevices(enumerator, DataFlow.Capture, d => ToAudioSource(d, AudioTrackType.Mic, defaultId));
+    }
 
-        return devices.Select(d => new AudioSource(
-            Id: d.ID,
-            Name: d.FriendlyName,
-            SourceType: AudioTrackType.Mic,
-            IsDefault: d.ID == defaultId,
-            TransportType: null
-        )).ToArray();
+    /// <summary>
+    /// Returns all active audio render (system/loopback) devices, flagging the
+    /// default for <paramref name="role"/>.
+    /// </summary>
+    public static AudioSource[] GetRenderDevices(DeviceRole role = DeviceRole.Multimedia)
+    {
+        using var enumerator = new MMDeviceEnumerator();
+        var defaultId = GetDefaultDeviceId(enumerator, DataFlow.Render, role);
+        return ReadActiveDevices(enumerator, DataFlow.Render, d => ToAudioSource(d, AudioTrackType.System, defaultId));
     }
 
     /// <summary>
-    /// Returns all active audio render (system/loopback) devices.
+    /// Returns the active render endpoints as loopback targets, each flagged with
+    /// whether it is playing anything right now, so a picker can steer the user to
+    /// the output that is actually in use. The default endpoint is the one loopback
+    /// captures when none is chosen; see <see cref="GetDefaultRenderDeviceId"/>.
     /// </summary>
-    public static AudioSource[] GetRenderDevices()
+    /// <param name="role">Whose default endpoint is flagged.</param>
+    public static LoopbackDevice[] GetLoopbackDevices(DeviceRole role = DeviceRole.Multimedia)
     {
         using var enumerator = new MMDeviceEnumerator();
-        var devices = enumerator.EnumerateAudioEndPoints(DataFlow.Render, DeviceState.Active);
-        var defaultId = GetDefaultDeviceId(enumerator, DataFlow.Render);
+        var defaultId = GetDefaultDeviceId(enumerator, DataFlow.Render, role);
+        return ReadActiveDevices(enumerator, DataFlow.Render, d => new LoopbackDevice(
+            ToAudioSource(d, AudioTrackType.System, defaultId),
+            IsRendering(d)));
+    }
+
+    /// <summary>
+    /// The loopback target a picker should preselect from <paramref name="devices"/>:
+    /// the default endpoint if it is rendering, otherwise the first one that is, and
+    /// the default when nothing is playing anywhere. Null for an empty list.
+    /// </summary>
+    public static LoopbackDevice? SuggestLoopbackDevice(IReadOnlyList<LoopbackDevice> devices)
+    {
+        ArgumentNullException.ThrowIfNull(devices);
 
-        return devices.Select(d => new AudioSource(
-            Id: d.ID,
-            Name: d.FriendlyName,
-            SourceType: AudioTrackType.System,
-            IsDefault: d.ID == defaultId,
-            TransportType: null
-        )).ToArray();
+        var fallback = devices.FirstOrDefault(d => d.Device.IsDefault);
+        if (fallback?.IsRendering == true)
+            return fallback;
+        return devices.FirstOrDefault(d => d.IsRendering) ?? fallback ?? devices.FirstOrDefault();
     }
 
     /// <summary>
-    /// Returns all available audio sources (mic + system).
+    /// The ID of the default render endpoint for <paramref name="role"/> — the one
+    /// loopback capture records — or null when there is none.
     /// </summary>
-    public static AudioSource[] GetAllDevices()
+    public static string? GetDefaultRenderDeviceId(DeviceRole role = DeviceRole.Multimedia)
     {
-        return [.. GetCaptureDevices(), .. GetRenderDevices()];
+        using var enumerator = new MMDeviceEnumerator();
+        return GetDefaultDeviceId(enumerator, DataFlow.Render, role);
+    }
+
+    /// <summary>
+    /// The ID of the default capture endpoint for <paramref name="role"/> — the mic
+    /// recorded when none is chosen — or null when there is none.
+    /// </summary>
+    public static string? GetDefaultCaptureDeviceId(DeviceRole role = DeviceRole.Multimedia)
+    {
+        using var enumerator = new MMDeviceEnumerator();
+        return GetDefaultDeviceId(enumerator, DataFlow.Capture, role);
     }
 
-    private static string? GetDefaultDeviceId(MMDeviceEnumerator enumerator, DataFlow flow)
+    /// <summary>
+    /// The active capture device whose friendly name contains
+    /// <paramref name="nameFragment"/>, ignoring case, or null when none does. Names
+    /// survive reboots and driver updates that can change an endpoint's ID, so this
+    /// is what a persisted preference such as "Blue Yeti" should be resolved with.
+    /// See <see cref="SelectByName"/> for how several matches are settled.
+    /// </summary>
+    /// <param name="nameFragment">Part of the device's friendly name.</param>
+    /// <param name="role">Whose default settles a fragment several devices match.</param>
+    /// <exception cref="CaptureException">ConfigurationFailed when the fragment is ambiguous.</exception>
+    public static AudioSource? FindCaptureDeviceByName(string nameFragment, DeviceRole role = DeviceRole.Multimedia) =>
+        SelectByName(GetCaptureDevices(role), nameFragment);
+
+    /// <summary>
+    /// Picks the device <paramref name="nameFragment"/> refers to: an exact name,
+    /// ignoring case, wins outright; otherwise the one name containing it. Among
+    /// several containing it, the default device is taken if it's one of them.
+    /// </summary>
+    /// <exception cref="CaptureException">
+    /// ConfigurationFailed when several devices match and neither rule settles it.
+    /// </exception>
+    public static AudioSource? SelectByName(IReadOnlyList<AudioSource> devices, string nameFragment)
+    {
+        ArgumentNullException.ThrowIfNull(devices);
+        ArgumentException.ThrowIfNullOrWhiteSpace(nameFragment);
+
+        var exact = devices.Where(d => string.Equals(d.Name, nameFragment, StringComparison.OrdinalIgnoreCase)).ToArray();
+        var matches = exact.Length > 0
+            ? exact
+            : devices.Where(d => d.Name.Contains(nameFragment, StringComparison.OrdinalIgnoreCase)).ToArray();
+
+        if (matches.Length <= 1)
+            return matches.FirstOrDefault();
+        return matches.FirstOrDefault(d => d.IsDefault)
+            ?? throw CaptureException.ConfigurationFailed(
+                $"\"{nameFragment}\" matches several devices: {string.Join(", ", matches.Select(d => d.Name))}");
+    }
+
+    /// <summary>
+    /// Returns all available audio sources (mic + system), flagging the defaults
+    /// for <paramref name="role"/>.
+    /// </summary>
+    public static AudioSource[] GetAllDevices(DeviceRole role = DeviceRole.Multimedia)
+    {
+        return [.. GetCaptureDevices(role), .. GetRenderDevices(role)];
+    }
+
+    /// <summary>
+    /// <see cref="GetCaptureDevices"/> off the caller's thread. Enumeration makes
+    /// synchronous COM calls that take tens of milliseconds with many endpoints
+    /// present — long enough to stutter a UI thread — so these variants run it on a
+    /// dedicated MTA thread instead.
+    /// </summary>
+    public static Task<AudioSource[]> GetCaptureDevicesAsync(DeviceRole role = DeviceRole.Multimedia) =>
+        ComThread.RunAsync(() => GetCaptureDevices(role));
+
+    /// <summary><see cref="GetRenderDevices"/> off the caller's thread; see <see cref="GetCaptureDevicesAsync"/>.</summary>
+    public static Task<AudioSource[]> GetRenderDevicesAsync(DeviceRole role = DeviceRole.Multimedia) =>
+        ComThread.RunAsync(() => GetRenderDevices(role));
+
+    /// <summary><see cref="GetAllDevices"/> off the caller's thread; see <see cref="GetCaptureDevicesAsync"/>.</summary>
+    public static Task<AudioSource[]> GetAllDevicesAsync(DeviceRole role = DeviceRole.Multimedia) =>
+        ComThread.RunAsync(() => GetAllDevices(role));
+
+    /// <summary><see cref="GetLoopbackDevices"/> off the caller's thread; see <see cref="GetCaptureDevicesAsync"/>.</summary>
+    public static Task<LoopbackDevice[]> GetLoopbackDevicesAsync(DeviceRole role = DeviceRole.Multimedia) =>
+        ComThread.RunAsync(() => GetLoopbackDevices(role));
+
+    /// <summary><see cref="GetSourceStatuses"/> off the caller's thread; see <see cref="GetCaptureDevicesAsync"/>.</summary>
+    public static Task<AudioSourceStatus[]> GetSourceStatusesAsync(
+        string? micDeviceId = null, DeviceRole role = DeviceRole.Multimedia) =>
+        ComThread.RunAsync(() => GetSourceStatuses(micDeviceId, role));
+
+    /// <summary>
+    /// The common formats the endpoint <paramref name="deviceId"/> accepts, in shared
+    /// and in exclusive mode, for offering only valid choices in a format picker:
+    /// each combination of 16, 44.1, 48 and 96 kHz, mono or stereo, and 16-bit,
+    /// 24-bit or 32-bit float, asked of <c>IAudioClient::IsFormatSupported</c>. A
+    /// bounded probe, not an exhaustive one. Formats neither mode accepts are left
+    /// out. Shared mode accepts little beyond the mix format, since the engine
+    /// converts the rest.
+    /// </summary>
+    /// <exception cref="CaptureException">DeviceNotAvailable when the endpoint can't be found or activated.</exception>
+    public static SupportedFormat[] GetSupportedFormats(string deviceId)
+    {
+        ArgumentNullException.ThrowIfNull(deviceId);
+        try
+        {
+            using var enumerator = new MMDeviceEnumerator();
+            using var device = enumerator.GetDevice(deviceId);
+            using var client = device.AudioClient;
+
+            var formats = new List<SupportedFormat>();
+            foreach (var rate in ProbeSampleRates)
+            foreach (var channels in ProbeChannelCounts)
+            foreach (var bits in ProbeBitDepths)
+            {
+                // Extensible, as exclusive mode wants past 16 bits; 32 means float.
+                var format = new WaveFormatExtensible(rate, bits, channels);
+                var shared = IsFormatSupported(client, AudioClientShareMode.Shared, format);
+                var exclusive = IsFormatSupported(client, AudioClientShareMode.Exclusive, format);
+                if (shared || exclusive)
+                    formats.Add(new SupportedFormat(new WavSpec(rate, channels, bits, IsFloat: bits == 32), shared, exclusive));
+            }
+            return [.. formats];
+        }
+        catch (Exception ex)
+        {
+            throw CaptureException.DeviceNotAvailable($"Cannot probe the formats of device {deviceId}: {ex.Message}");
+        }
+    }
+
+    /// <summary><see cref="GetSupportedFormats"/> off the caller's thread; see <see cref="GetCaptureDevicesAsync"/>.</summary>
+    public static Task<SupportedFormat[]> GetSupportedFormatsAsync(string deviceId) =>
+        ComThread.RunAsync(() => GetSupportedFormats(deviceId));
+
+    /// <summary>
+    /// Whether another application holds the microphone in exclusive mode, so that
+    /// capture from it would fail with <see cref="CaptureErrorKind.DeviceInUse"/>.
+    /// </summary>
+    /// <remarks>
+    /// Probes by briefly initializing a shared-mode client, exactly as capture will.
+    /// Shared-mode clients coexist — a call app or another recorder in shared mode
+    /// does not make this true; only an exclusive-mode holder does.
+    /// </remarks>
+    /// <param name="deviceId">The capture endpoint to probe, or null for the default.</param>
+    /// <param name="role">Whose default to probe when <paramref name="deviceId"/> is null.</param>
+    /// <exception cref="CaptureException">DeviceNotAvailable when there is no such device.</exception>
+    public static bool IsMicrophoneInUse(string? deviceId = null, DeviceRole role = DeviceRole.Multimedia)
+    {
+        using var enumerator = new MMDeviceEnumerator();
+        MMDevice device;
+        try
+        {
+            device = deviceId != null
+                ? enumerator.GetDevice(deviceId)
+                : enumerator.GetDefaultAudioEndpoint(DataFlow.Capture, ToRole(role));
+        }
+        catch (Exception)
+        {
+            throw CaptureException.DeviceNotAvailable("No microphone device found");
+        }
+
+        using (device)
+            return ProbeCaptureDevice(device) == SourceStatus.InUse;
+    }
+
+    /// <summary>
+    /// Says, per source, whether capture would work and why not — so a UI can explain
+    /// a disabled mic toggle instead of just greying it out.
+    /// </summary>
+    /// <remarks>
+    /// The mic is probed like <see cref="IsMicrophoneInUse"/>, which also surfaces a
+    /// privacy-setting denial. Loopback needs no permission and can't be held
+    /// exclusively, so system audio is available whenever a render device exists.
+    /// </remarks>
+    /// <param name="micDeviceId">The capture endpoint to check, or null for the default.</param>
+    /// <param name="role">Whose default endpoints to check.</param>
+    public static AudioSourceStatus[] GetSourceStatuses(
+        string? micDeviceId = null, DeviceRole role = DeviceRole.Multimedia)
+    {
+        using var enumerator = new MMDeviceEnumerator();
+        return
+        [
+            GetStatus(enumerator, DataFlow.Capture, micDeviceId, role),
+            GetStatus(enumerator, DataFlow.Render, deviceId: null, role),
+        ];
+    }
+
+    private static AudioSourceStatus GetStatus(
+        MMDeviceEnumerator enumerator, DataFlow flow, string? deviceId, DeviceRole role)
+    {
+        var type = flow == DataFlow.Capture ? AudioTrackType.Mic : AudioTrackType.System;
+
+        MMDevice device;
+        try
+        {
+            device = deviceId != null
+                ? enumerator.GetDevice(deviceId)
+                : enumerator.GetDefaultAudioEndpoint(flow, ToRole(role));
+        }
+        catch (Exception)
+        {
+            return new AudioSourceStatus(type, null, SourceStatus.NoDevice);
+        }
+
+        using (device)
+        {
+            var source = ToAudioSource(
+                device, type, deviceId == null ? device.ID : GetDefaultDeviceId(enumerator, flow, role));
+            var status = flow == DataFlow.Capture ? ProbeCaptureDevice(device) : SourceStatus.Available;
+            return new AudioSourceStatus(type, source, status);
+        }
+    }
+
+    /// <summary>
+    /// Briefly initializes a shared-mode client on <paramref name="device"/> — the
+    /// same call capture makes — and classifies how it fails, if it does.
+    /// </summary>
+    private static SourceStatus ProbeCaptureDevice(MMDevice device)
+    {
+        try
+        {
+            using var client = device.AudioClient;
+            client.Initialize(AudioClientShareMode.Shared, AudioClientStreamFlags.None,
+                1_000_000, 0, client.MixFormat, Guid.Empty);
+            return SourceStatus.Available;
+        }
+        catch (Exception ex) when (AudioClientErrors.IsDeviceInUse(ex))
+        {
+            return SourceStatus.InUse;
+        }
+        catch (Exception ex) when (AudioClientErrors.IsAccessDenied(ex))
+        {
+            return SourceStatus.PermissionDenied;
+        }
+        catch (Exception)
+        {
+            // Any other init failure means the endpoint can't be captured from.
+            return SourceStatus.NoDevice;
+        }
+    }
+
+    /// <summary>
+    /// One <c>IsFormatSupported</c> answer. Some drivers fail the call outright for a
+    /// format they don't know rather than answering no; that counts as no.
+    /// </summary>
+    private static bool IsFormatSupported(AudioClient client, AudioClientShareMode mode, WaveFormat format)
+    {
+        try
+        {
+            return client.IsFormatSupported(mode, format);
+        }
+        catch (Exception)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Whether any audio session on the endpoint is active (IAudioSessionManager2
+    /// enumeration). Best effort: a device whose sessions can't be read counts as idle.
+    /// </summary>
+    private static bool IsRendering(MMDevice device)
+    {
+        try
+        {
+            var sessions = device.AudioSessionManager.Sessions;
+            for (int i = 0; i < sessions.Count; i++)
+            {
+                if (sessions[i].State == NAudio.CoreAudioApi.Interfaces.AudioSessionState.AudioSessionStateActive)
+                    return true;
+            }
+            return false;
+        }
+        catch (Exception)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Maps every active endpoint of <paramref name="flow"/> through
+    /// <paramref name="read"/>, disposing each one afterwards. Every
+    /// <see cref="MMDevice"/> holds COM references and its property store; left to the
+    /// finalizer, a picker that refreshes on every device change piles them up.
+    /// </summary>
+    private static T[] ReadActiveDevices<T>(MMDeviceEnumerator enumerator, DataFlow flow, Func<MMDevice, T> read)
+    {
+        var devices = enumerator.EnumerateAudioEndPoints(flow, DeviceState.Active);
+        var results = new List<T>(devices.Count);
+        for (int i = 0; i < devices.Count; i++)
+        {
+            using var device = devices[i];
+            results.Add(read(device));
+        }
+        return [.. results];
+    }
+
+    internal static AudioSource ToAudioSource(MMDevice device, AudioTrackType type, string? defaultId) => new(
+        Id: device.ID,
+        Name: ReadStringProperty(device, PropertyKeys.PKEY_Device_FriendlyName) ?? device.ID,
+        SourceType: type,
+        IsDefault: device.ID == defaultId,
+        TransportType: null);
+
+    /// <summary>
+    /// A string property of the endpoint, or null when it is absent or unreadable —
+    /// an endpoint unplugged mid-enumeration throws on its first property read, and
+    /// that shouldn't fail the whole list.
+    /// </summary>
+    private static string? ReadStringProperty(MMDevice device, PropertyKey key)
+    {
+        try
+        {
+            var properties = device.Properties;
+            return properties.Contains(key) ? properties[key].Value as string : null;
+        }
+        catch (Exception)
+        {
+            return null;
+        }
+    }
+
+    /// <summary>NAudio's <see cref="Role"/> for <paramref name="role"/>.</summary>
+    internal static Role ToRole(DeviceRole role) => role switch
+    {
+        DeviceRole.Console => Role.Console,
+        DeviceRole.Communications => Role.Communications,
+        _ => Role.Multimedia,
+    };
+
+    private static string? GetDefaultDeviceId(
+        MMDeviceEnumerator enumerator, DataFlow flow, DeviceRole role = DeviceRole.Multimedia)
     {
         try
         {
-            return enumerator.GetDefaultAudioEndpoint(flow, Role.Multimedia)?.ID;
+            using var device = enumerator.GetDefaultAudioEndpoint(flow, ToRole(role));
+            return device.ID;
         }
         catch
         {
@@ -1,4 +1,5 @@
 using System.Diagnostics;
+using AudioCapture.Interfaces;
 using AudioCapture.Models;
 using NAudio.Wave;
 using NAudio.Wave.SampleProviders;
@@ -33,8 +34,11 @@ namespace AudioCapture.Capture;
 /// <para><b>Pacing.</b> Buffers are delivered in real time on a drift-corrected
 /// schedule by default, so downstream timing behaves as it would with live audio.
 /// Raise <c>speedFactor</c> to replay long fixtures faster than real time in CI.</para>
+///
+/// <para><b>Pausing.</b> <see cref="Pause"/> halts delivery where it is, and
+/// <see cref="Resume"/> carries on from there on a fresh schedule.</para>
 /// </summary>
-public sealed class FileWaveIn : IWaveIn
+public sealed class FileWaveIn : IPausableWaveIn
 {
     private readonly string _filePath;
     private readonly TimeSpan _chunkDuration;
@@ -52,6 +56,7 @@ public sealed class FileWaveIn : IWaveIn
     private CancellationTokenSource? _cts;
     private Task? _pumpTask;
     private bool _isRecording;
+    private bool _paused;
     private bool _disposed;
 
     public event EventHandler<WaveInEventArgs>? DataAvailable;
@@ -161,17 +166,15 @@ public sealed class FileWaveIn : IWaveIn
         // concurrent StopRecording.
         fixture = LoadFixture(_filePath, format);
 
-        CancellationTokenSource cts;
         lock (_lock)
         {
             if (_isRecording) return;
             _fixture = fixture;
             _readOffset = 0;
             _isRecording = true;
-            cts = _cts = new CancellationTokenSource();
+            var cts = _cts = new CancellationTokenSource();
+            _pumpTask = Task.Run(() => PumpAsync(cts.Token));
         }
-
-        _pumpTask = Task.Run(() => PumpAsync(cts.Token));
     }
 
     /// <summary>
@@ -180,28 +183,41 @@ public sealed class FileWaveIn : IWaveIn
     /// </summary>
     public void StopRecording()
     {
-        CancellationTokenSource? cts;
-        Task? pump;
         lock (_lock)
         {
             if (!_isRecording) return;
             _isRecording = false;
-            cts = _cts;
-            _cts = null;
-            pump = _pumpTask;
-            _pumpTask = null;
+            _paused = false;
         }
 
-        cts?.Cancel();
-        // The pump only ever awaits a cancellable delay, so this returns
-        // promptly; the timeout is a backstop against a wedged handler.
-        try { pump?.Wait(TimeSpan.FromSeconds(5)); }
-        catch (AggregateException) { /* surfaced via RecordingStopped */ }
-        cts?.Dispose();
-
+        HaltPump();
         RecordingStopped?.Invoke(this, new StoppedEventArgs());
     }
 
+    /// <summary>Halts delivery, keeping the read position. Does nothing unless recording.</summary>
+    public void Pause()
+    {
+        lock (_lock)
+        {
+            if (!_isRecording || _paused) return;
+            _paused = true;
+        }
+
+        HaltPump();
+    }
+
+    /// <summary>Resumes delivery from where <see cref="Pause"/> left off.</summary>
+    public void Resume()
+    {
+        lock (_lock)
+        {
+            if (!_isRecording || !_paused) return;
+            _paused = false;
+            var cts = _cts = new CancellationTokenSource();
+            _pumpTask = Task.Run(() => PumpAsync(cts.Token));
+        }
+    }
+
     public void Dispose()
     {
         if (_disposed) return;
@@ -213,6 +229,26 @@ public sealed class FileWaveIn : IWaveIn
 
     // --- Private helpers ---
 
+    private void HaltPump()
+    {
+        CancellationTokenSource? cts;
+        Task? pump;
+        lock (_lock)
+        {
+            cts = _cts;
+            _cts = null;
+            pump = _pumpTask;
+            _pumpTask = null;
+        }
+
+        cts?.Cancel();
+        // The pump only ever awaits a cancellable delay, so this returns
+        // promptly; the timeout is a backstop against a wedged handler.
+        try { pump?.Wait(TimeSpan.FromSeconds(5)); }
+        catch (AggregateException) { /* surfaced via RecordingStopped */ }
+        cts?.Dispose();
+    }
+
     /// <summary>
     /// Delivers chunks on a drift-corrected schedule: each chunk's deadline is
     /// computed from the start time rather than by accumulating sleeps, so a slow
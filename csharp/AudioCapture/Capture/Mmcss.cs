@@ -0,0 +1,70 @@
+using System.Runtime.InteropServices;
+
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// Registers the calling thread with the Multimedia Class Scheduler Service, which
+/// keeps it scheduled ahead of ordinary work so capture callbacks aren't starved
+/// under CPU contention.
+/// </summary>
+internal static class Mmcss
+{
+    /// <summary>
+    /// Joins the calling thread to <paramref name="taskName"/> (a key under
+    /// <c>HKLM\...\Multimedia\SystemProfile\Tasks</c>, e.g. "Pro Audio", "Audio",
+    /// "Capture"). Returns null if the service refused — an unknown task name, or
+    /// MMCSS disabled — in which case the thread simply keeps its normal priority
+    /// and <paramref name="refusal"/> says why, since a machine that glitches under
+    /// load is otherwise hard to tell apart from one that never got real-time priority.
+    /// </summary>
+    public static Registration? RegisterCurrentThread(string taskName, out string? refusal)
+    {
+        uint taskIndex = 0;
+        try
+        {
+            var handle = AvSetMmThreadCharacteristics(taskName, ref taskIndex);
+            if (handle != IntPtr.Zero)
+            {
+                refusal = null;
+                return new Registration(handle);
+            }
+
+            refusal = $"MMCSS refused task \"{taskName}\" (error {Marshal.GetLastPInvokeError()})";
+            return null;
+        }
+        catch (Exception ex) when (ex is DllNotFoundException or EntryPointNotFoundException)
+        {
+            // avrt.dll is absent on some server SKUs.
+            refusal = $"MMCSS is unavailable ({ex.Message})";
+            return null;
+        }
+    }
+
+    /// <summary>
+    /// One thread's MMCSS association; disposing reverts it. The revert only means
+    /// anything on the registering thread, so a dispose from any other is a no-op —
+    /// the association lapses anyway when its thread exits.
+    /// </summary>
+    internal sealed class Registration : IDisposable
+    {
+        private readonly int _threadId = Environment.CurrentManagedThreadId;
+        private IntPtr _handle;
+
+        public Registration(IntPtr handle) => _handle = handle;
+
+        public void Dispose()
+        {
+            if (_handle == IntPtr.Zero || Environment.CurrentManagedThreadId != _threadId) return;
+            // A failed revert leaves nothing to do: the association lapses with the thread.
+            AvRevertMmThreadCharacteristics(_handle);
+            _handle = IntPtr.Zero;
+        }
+    }
+
+    [DllImport("avrt.dll", EntryPoint = "AvSetMmThreadCharacteristicsW", CharSet = CharSet.Unicode, SetLastError = true)]
+    private static extern IntPtr AvSetMmThreadCharacteristics(string taskName, ref uint taskIndex);
+
+    [DllImport("avrt.dll")]
+    [return: MarshalAs(UnmanagedType.Bool)]
+    private static extern bool AvRevertMmThreadCharacteristics(IntPtr avrtHandle);
+}
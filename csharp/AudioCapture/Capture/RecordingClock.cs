@@ -0,0 +1,41 @@
+namespace AudioCapture.Capture;
+
+/// <summary>
+/// Recorded time: a stopwatch over a <see cref="TimeProvider"/>, so pause and
+/// resume accounting runs on whatever clock the session was given — the system's
+/// in production, one a test advances by hand.
+/// </summary>
+/// <remarks>Not thread-safe; the session reads and drives it under its own lock.</remarks>
+internal sealed class RecordingClock(TimeProvider time)
+{
+    private TimeSpan _banked; // recorded time up to the current running stretch
+    private long _runningSince;
+    private bool _running;
+
+    /// <summary>Time recorded so far, paused stretches excluded.</summary>
+    public TimeSpan Elapsed => _running ? _banked + time.GetElapsedTime(_runningSince) : _banked;
+
+    /// <summary>Starts over from zero, running.</summary>
+    public void Restart()
+    {
+        _banked = TimeSpan.Zero;
+        _runningSince = time.GetTimestamp();
+        _running = true;
+    }
+
+    /// <summary>Resumes counting; a no-op while already running.</summary>
+    public void Start()
+    {
+        if (_running) return;
+        _runningSince = time.GetTimestamp();
+        _running = true;
+    }
+
+    /// <summary>Stops counting, keeping what was recorded; a no-op while stopped.</summary>
+    public void Stop()
+    {
+        if (!_running) return;
+        _banked += time.GetElapsedTime(_runningSince);
+        _running = false;
+    }
+}
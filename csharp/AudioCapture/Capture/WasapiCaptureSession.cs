@@ -1,4 +1,6 @@
-using System.Diagnostics;
+using System.Runtime.InteropServices;
+using System.Text.Json;
+using System.Threading.Channels;
 using AudioCapture.Interfaces;
 using AudioCapture.Models;
 using AudioCapture.Processing;
@@ -27,6 +29,11 @@ public sealed class WasapiCaptureSession : ICaptureSession
     private readonly Func<IWaveIn>? _systemFactory;
 
     private CaptureState _state = CaptureState.Idle;
+
+    // A start has claimed the session and not yet reached Capturing or failed
+    // (protected by _lock). Ready stays the state while devices open, so this is
+    // what turns a second, concurrent start away.
+    private bool _starting;
     private CaptureConfiguration? _config;
     private AudioLevels _currentLevels = AudioLevels.Zero;
 
@@ -35,19 +42,76 @@ public sealed class WasapiCaptureSession : ICaptureSession
     private IWaveIn? _micCapture;
     private IWaveIn? _systemCapture;
     private MMDevice? _micDevice;
+    // The mic's endpoint ID as resolved at Configure — from MicDeviceName when set.
+    // Null means the default endpoint.
+    private string? _micDeviceId;
+    private MMDevice[] _additionalMicDevices = [];
 
     // Diagnostics counters (protected by _lock).
     private long _mixCycles;
+    private long _micFramesReceived;
+    private long _systemFramesReceived;
+    private long _driftCorrectedFrames;
+
+    // When the last loopback packet came, as a timestamp of the session's clock,
+    // for TimingSource.Auto.
+    private long _lastSystemChunkTimestamp;
+
+    // System-audio activity on the recording timeline (protected by _lock). The
+    // open interval's start, if one is open, and when it last heard sound.
+    private readonly List<ActivityInterval> _systemActivity = [];
+    private double? _systemActiveSince;
+    private double _systemLastActiveAt;
+
+    // Every state entered since the last Configure, oldest first, for
+    // RecordStateLog (protected by _lock). Bounded: the oldest give way past the cap.
+    private const int StateLogCapacity = 64;
+    private readonly List<StateTransition> _stateLog = [];
     private long _bytesWritten;
+
+    // MaxFileBytes accounting for this run (protected by _lock): what the output
+    // held when opened, the PCM and writes handed to it since, and whether the cap
+    // has been reached — after which nothing more is written.
+    private long _fileBytesAtOpen;
+    private long _filePcmBytes;
+    private long _fileWrites;
+    private bool _fileSizeCapReached;
+
+    // Why this run stopped, when the session stopped it, and which enabled sources
+    // have ended for good mid-run (protected by _lock).
+    private StopReason _stopReason;
+    private bool _micEnded;
+    private bool _systemEnded;
     private long _micChunks;
     private long _micBytes;
     private long _systemChunks;
     private long _systemBytes;
     private long _mixErrors;
+    private long _sinkDroppedChunks;
+    private long _clippedSamples;
+    private long _mmcssFailures;
+    private long _callbackFaults;
+    private bool _micCallbackFaulted; // reported this run; later faults are only counted
+    private bool _systemCallbackFaulted;
+    private int _systemDeviceSwitches;
+    private bool _wroteSignal; // anything but digital silence reached the writer this run
+    private bool _wroteClipped; // a sample beyond full scale reached the writer this run
+    private bool? _micFormatValid; // null until the source's first buffer has been checked
+    private bool? _systemFormatValid;
+    private CaptureException? _writeFailure; // the failed write, or exceeded budget, that ended this run
+    private long? _memoryBudget;
+    private long _peakMemoryBytes;
     private int _peakBufferedSamples;
 
+    // Where Diagnostics counts from after ResetDiagnostics: the lifetime counters
+    // at the reset, and high-water marks since (protected by _lock). Null baseline
+    // before any reset.
+    private CaptureDiagnostics? _diagnosticsBaseline;
+    private long _segmentPeakMemoryBytes;
+    private int _segmentPeakBufferedSamples;
+
     // Writers
-    private EncryptedWavWriter? _wavWriter;
+    private IOutputSink? _wavWriter;
     private FileStream? _micPcmWriter;
     private FileStream? _systemPcmWriter;
 
@@ -64,6 +128,26 @@ public sealed class WasapiCaptureSession : ICaptureSession
     // Mixer
     private readonly StereoMixer _mixer = new();
 
+    // Routing for output that isn't stereo (mono, quad, 5.1); null for stereo,
+    // which stays on _mixer. Set in Configure.
+    private MixMatrix? _mixMatrix;
+
+    // Scratch for the mix and its PCM conversion, reused across cycles so a long
+    // session doesn't allocate twice per tick. Only touched inside MixAndWrite, which
+    // _mixLock keeps to one thread — the pump, or a pause or stop flushing.
+    private readonly object _mixLock = new();
+    private float[] _mixScratch = [];
+    private byte[] _pcmScratch = [];
+
+    // SampleFormat.Int16: mic bytes awaiting the next write (protected by _lock).
+    // The pump swaps this with _pcmScratch rather than copying out of it.
+    private bool _passthrough;
+    private byte[] _micPassthrough = [];
+    private int _micPassthroughLength;
+
+    // Optional live tap on the mixed output; see SetAudioSink.
+    private ChannelWriter<float[]>? _audioSink;
+
     // Reconciles the loopback endpoint's mix format to the configured shape.
     // Built once the source's format is known; owned by the capture callback.
     private SystemAudioNormalizer? _systemNormalizer;
@@ -73,28 +157,89 @@ public sealed class WasapiCaptureSession : ICaptureSession
     private readonly List<float> _systemBuffer = [];
 
     // Timing
-    private readonly Stopwatch _durationStopwatch = new();
+    private readonly TimeProvider _time = TimeProvider.System;
+    private readonly RecordingClock _durationClock;
     private TaskCompletionSource<RecordingResult>? _stopTcs;
     private Timer? _maxDurationTimer;
 
+    // Stall watchdog: chunk counts seen at the previous tick (protected by _lock).
+    private Timer? _stallTimer;
+    private long _stallMicChunks;
+    private long _stallSystemChunks;
+    private bool _stallReported;
+
+    // Sources stopped by PauseDevices until resume (protected by _lock); nothing
+    // arriving then is no stall.
+    private bool _devicesPaused;
+
+    // The capture-callback gate StopCaptureAsync closes before the final flush, and
+    // the session whose callback this thread is running, so a stop from inside one
+    // (a delegate reacting to levels, say) doesn't wait on itself.
+    private bool _callbacksClosed;
+    private int _callbacksInFlight;
+    [ThreadStatic] private static WasapiCaptureSession? t_callbackSession;
+
     // The mix loop. A single pump task drives every mix/write, which is what keeps
     // writes serialized — see RunMixPumpAsync.
     private PeriodicTimer? _mixTimer;
     private Task? _mixPump;
     private CancellationTokenSource? _mixCts;
 
-    // File path for the main WAV recording (set in StartCaptureAsync)
+    // File paths for the main WAV recording and its sidecars (set in StartCaptureAsync)
     private string? _wavFilePath;
 
+    // Backing buffer when capturing to memory, read out at stop.
+    private MemoryStream? _memoryTarget;
+    private string? _micPcmPath;
+    private string? _systemPcmPath;
+
+    // MMCSS task still to be joined by each capture thread, cleared once it has
+    // tried. Only ever set for real WASAPI sources (set in StartCaptureAsync).
+    private string? _pendingMicMmcssTask;
+    private string? _pendingSystemMmcssTask;
+    // Each capture thread's MMCSS association, reverted when that thread's source stops.
+    private Mmcss.Registration? _micMmcss;
+    private Mmcss.Registration? _systemMmcss;
+
+    // Mic processing, rebuilt per run so no state leaks from an earlier recording.
+    // Owned by the mic capture callback.
+    private NoiseGate? _micGate;
+    private AutomaticGainControl? _micAgc;
+
+    // Final resample of the mix to DeliverySampleRate; null when delivering at the
+    // capture rate. Owned by the mix loop, rebuilt per run.
+    private IAudioResampler? _deliveryResampler;
+
+    // Drops long silences from the mix (SkipSilence). Owned by the mix loop, rebuilt per run.
+    private SilenceSkipper? _silenceSkipper;
+
+    // How the mic reached the configured format (set in StartCaptureAsync).
+    private MicConversion _micConversion;
+
+    // Frames still to mute at the start of each source (PrimeDuration). Each is
+    // owned by its source's capture callback.
+    private int _micPrimeFrames;
+    private int _systemPrimeFrames;
+
     // Level metering
     private float _micRms;
     private float _systemRms;
     private float _peakMic;
     private float _peakSystem;
+    private float _truePeakMic;
+    private float _truePeakSystem;
+    private bool _truePeakMetering;
+    private float _stereoCorrelation; // of the last mixed chunk, written by the mix loop
+
+    // Meter ballistics (LevelBallistics), each owned by its source's capture callback.
+    private MeterBallistics? _micBallistics;
+    private MeterBallistics? _systemBallistics;
+    private double _meterSampleRate;
 
     /// <summary>Captures from the real WASAPI mic and loopback endpoints.</summary>
     public WasapiCaptureSession()
     {
+        _durationClock = new RecordingClock(_time);
     }
 
     /// <summary>
@@ -116,25 +261,70 @@ public sealed class WasapiCaptureSession : ICaptureSession
     /// <param name="micFactory">Builds the mic source, or null to use the real WASAPI mic.</param>
     /// <param name="systemFactory">Builds the system-audio source, or null to use real loopback.</param>
     public WasapiCaptureSession(Func<IWaveIn>? micFactory, Func<IWaveIn>? systemFactory)
+        : this(micFactory, systemFactory, TimeProvider.System)
+    {
+    }
+
+    /// <summary>
+    /// As the injected-source constructor, on <paramref name="timeProvider"/>'s clock:
+    /// recorded duration, pause accounting, activity intervals and the loopback
+    /// silence fallback all read it, so a test can advance time by hand instead of
+    /// sleeping. The mix pump and the max-duration and stall timers stay on real time.
+    /// </summary>
+    public WasapiCaptureSession(Func<IWaveIn>? micFactory, Func<IWaveIn>? systemFactory, TimeProvider timeProvider)
     {
+        ArgumentNullException.ThrowIfNull(timeProvider);
         _micFactory = micFactory;
         _systemFactory = systemFactory;
+        _time = timeProvider;
+        _durationClock = new RecordingClock(timeProvider);
     }
 
+    /// <summary>
+    /// Whether WASAPI capture works on this OS at all — Windows 10 1809 or later —
+    /// as opposed to whether a device is plugged in right now. A version check, with
+    /// no device enumeration, so it is cheap to ask as often as a UI likes. Where it
+    /// is false only injected sources can record, and <see cref="Configure"/> refuses
+    /// anything that needs a real endpoint.
+    /// </summary>
+    public static bool IsSupported => OperatingSystem.IsWindowsVersionAtLeast(10, 0, 17763);
+
     public CaptureState State
     {
         get { lock (_lock) return _state; }
     }
 
+    /// <summary>
+    /// Recorded time so far, paused stretches excluded — live, unlike
+    /// <see cref="CaptureState.Duration"/>, which is stamped at each transition and
+    /// absent outside Capturing and Paused. Readable in any state: zero before the
+    /// first start, and the last recording's length once it has stopped, until the
+    /// next start.
+    /// </summary>
+    public TimeSpan Elapsed
+    {
+        get { lock (_lock) return _durationClock.Elapsed; }
+    }
+
     public AudioLevels CurrentLevels
     {
         get { lock (_lock) return _currentLevels; }
     }
 
     /// <summary>
-    /// A snapshot of what has flowed through this session so far, readable during
-    /// and after capture. Counters accumulate from construction — a session runs
-    /// at most one capture, since no path leads back out of a terminal state.
+    /// <see cref="CurrentLevels"/> with the recorded time and wall-clock time they
+    /// were read at, for sending to a frontend as they are.
+    /// </summary>
+    public AudioLevelsSnapshot CurrentLevelsSnapshot()
+    {
+        lock (_lock)
+            return AudioLevelsSnapshot.From(_currentLevels, _durationClock.Elapsed, _time.GetUtcNow());
+    }
+
+    /// <summary>
+    /// A snapshot of what has flowed through this session since the last
+    /// <see cref="ResetDiagnostics"/>, readable during and after capture. Without a
+    /// reset it is <see cref="LifetimeDiagnostics"/>.
     /// </summary>
     public CaptureDiagnostics Diagnostics
     {
@@ -142,30 +332,162 @@ public sealed class WasapiCaptureSession : ICaptureSession
         {
             lock (_lock)
             {
-                return new CaptureDiagnostics
+                var lifetime = ReadLifetimeDiagnostics();
+                if (_diagnosticsBaseline is not { } since) return lifetime;
+
+                // Counters less what they stood at when reset; the source's format
+                // and conversion describe the run as it is now, so they stay.
+                return lifetime with
                 {
-                    MixCycles = _mixCycles,
-                    BytesWritten = _bytesWritten,
-                    MicChunks = _micChunks,
-                    MicBytes = _micBytes,
-                    SystemChunks = _systemChunks,
-                    SystemBytes = _systemBytes,
-                    MixErrors = _mixErrors,
-                    PeakBufferedSamples = _peakBufferedSamples,
-                    SystemSourceSampleRate = _systemNormalizer?.SourceSampleRate ?? 0,
-                    SystemSourceChannels = _systemNormalizer?.SourceChannels ?? 0,
-                    SystemNormalized = _systemNormalizer is { IsPassthrough: false },
+                    MixCycles = lifetime.MixCycles - since.MixCycles,
+                    BytesWritten = lifetime.BytesWritten - since.BytesWritten,
+                    MicChunks = lifetime.MicChunks - since.MicChunks,
+                    MicBytes = lifetime.MicBytes - since.MicBytes,
+                    SystemChunks = lifetime.SystemChunks - since.SystemChunks,
+                    SystemBytes = lifetime.SystemBytes - since.SystemBytes,
+                    MixErrors = lifetime.MixErrors - since.MixErrors,
+                    SinkDroppedChunks = lifetime.SinkDroppedChunks - since.SinkDroppedChunks,
+                    ClippedSamples = lifetime.ClippedSamples - since.ClippedSamples,
+                    MmcssRegistrationFailures = lifetime.MmcssRegistrationFailures - since.MmcssRegistrationFailures,
+                    CallbackFaults = lifetime.CallbackFaults - since.CallbackFaults,
+                    PeakBufferedSamples = _segmentPeakBufferedSamples,
+                    PeakMemoryBytes = _segmentPeakMemoryBytes,
+                    SourceDriftFrames = lifetime.SourceDriftFrames - since.SourceDriftFrames,
+                    DriftCorrectedFrames = lifetime.DriftCorrectedFrames - since.DriftCorrectedFrames,
+                    SystemDeviceSwitches = lifetime.SystemDeviceSwitches - since.SystemDeviceSwitches,
                 };
             }
         }
     }
 
+    /// <summary>
+    /// As <see cref="Diagnostics"/>, but counted from construction whatever
+    /// <see cref="ResetDiagnostics"/> has done.
+    /// </summary>
+    public CaptureDiagnostics LifetimeDiagnostics
+    {
+        get { lock (_lock) return ReadLifetimeDiagnostics(); }
+    }
+
+    /// <summary>
+    /// Starts <see cref="Diagnostics"/> counting afresh from now — per stretch of a
+    /// long recording, say — so one segment's numbers don't bury another's.
+    /// <see cref="LifetimeDiagnostics"/> keeps the totals. The recording itself is
+    /// unaffected. Nothing calls this for you: a recording is one file from start to
+    /// stop, with no rotation to reset at, so call it wherever a segment should begin.
+    /// </summary>
+    public void ResetDiagnostics()
+    {
+        lock (_lock)
+        {
+            _diagnosticsBaseline = ReadLifetimeDiagnostics();
+            _segmentPeakBufferedSamples = 0;
+            _segmentPeakMemoryBytes = 0;
+        }
+    }
+
+    /// <summary>The counters since construction. Must hold <see cref="_lock"/>.</summary>
+    private CaptureDiagnostics ReadLifetimeDiagnostics()
+    {
+        return new CaptureDiagnostics
+        {
+            MixCycles = _mixCycles,
+            BytesWritten = _bytesWritten,
+            MicChunks = _micChunks,
+            MicBytes = _micBytes,
+            SystemChunks = _systemChunks,
+            SystemBytes = _systemBytes,
+            MixErrors = _mixErrors,
+            SinkDroppedChunks = _sinkDroppedChunks,
+            ClippedSamples = _clippedSamples,
+            MmcssRegistrationFailures = _mmcssFailures,
+            CallbackFaults = _callbackFaults,
+            PeakBufferedSamples = _peakBufferedSamples,
+            PeakMemoryBytes = _peakMemoryBytes,
+            SourceDriftFrames = _micFramesReceived - _systemFramesReceived,
+            DriftCorrectedFrames = _driftCorrectedFrames,
+            SystemSourceSampleRate = _systemNormalizer?.SourceSampleRate ?? 0,
+            SystemSourceChannels = _systemNormalizer?.SourceChannels ?? 0,
+            SystemDeviceSwitches = _systemDeviceSwitches,
+            SystemNormalized = _systemNormalizer is { IsPassthrough: false },
+            MicConversion = _micConversion,
+        };
+    }
+
     public ICaptureDelegate? Delegate { get; set; }
 
+    // Sample rates a configuration may ask for: narrowband telephony up to
+    // high-resolution studio rates.
+    private const int MinSampleRate = 8000;
+    private const int MaxSampleRate = 384000;
+
+    /// <summary>
+    /// Estimated size of the recording after <paramref name="duration"/> of audio
+    /// with the current configuration, header included — for "about X MB a minute"
+    /// displays and low-disk warnings. Sidecars aren't counted.
+    /// </summary>
+    public long EstimatedFinalSize(TimeSpan duration)
+    {
+        CaptureConfiguration config;
+        lock (_lock) config = _config ?? throw CaptureException.ConfigurationFailed("Not configured");
+        return EncryptedWavWriter.HeaderLength(config)
+            + (long)Math.Round(config.EstimatedBytesPerSecond() * duration.TotalSeconds);
+    }
+
+    /// <summary>
+    /// Streams each mixed chunk — interleaved float at the configured channel count,
+    /// exactly what is handed to the WAV writer — to <paramref name="sink"/> as the mix loop produces it. Pass
+    /// null to detach. Can be set before or during capture.
+    /// </summary>
+    /// <remarks>
+    /// The sink is written with <see cref="ChannelWriter{T}.TryWrite"/>, never awaited:
+    /// the mix loop is what keeps the file's writes serialized, and a live consumer
+    /// (a transcription socket, say) must not be able to stall it. A consumer that
+    /// falls behind a bounded channel therefore loses chunks rather than blocking —
+    /// counted in <see cref="CaptureDiagnostics.SinkDroppedChunks"/>. The recording
+    /// itself is unaffected. Each chunk is a fresh array the consumer may keep.
+    /// </remarks>
+    public void SetAudioSink(ChannelWriter<float[]>? sink)
+    {
+        lock (_lock) _audioSink = sink;
+    }
+
+    /// <summary>
+    /// Points the next recording at <paramref name="directory"/> instead of the
+    /// configured <see cref="CaptureConfiguration.OutputDirectory"/>, for apps that
+    /// only know the destination (a per-meeting folder, say) once the user starts.
+    /// Only valid while <see cref="CaptureStateKind.Ready"/>, and only for a new
+    /// file-backed recording. The directory is created and probed as Configure
+    /// would; if it can't be written, a StorageError is thrown and the session stays
+    /// Ready with its previous directory.
+    /// </summary>
+    public void SetOutputDirectory(string directory)
+    {
+        ArgumentException.ThrowIfNullOrWhiteSpace(directory);
+
+        // Checked and applied under one lock, so a start can't slip in between and
+        // open its file in the directory this call was about to replace.
+        lock (_lock)
+        {
+            if (_starting)
+                throw CaptureException.ConfigurationFailed("Cannot change the output directory while capture is starting");
+            if (_state.Kind != CaptureStateKind.Ready || _config == null)
+                throw CaptureException.ConfigurationFailed($"Cannot change the output directory in state {_state.Kind}");
+            if (_config.CaptureToMemory || _config.ResumeFilePath != null || _config.OutputSink != null)
+                throw CaptureException.ConfigurationFailed(
+                    "In-memory, sink and resumed recordings don't write to the output directory");
+
+            ProbeOutputDirectory(directory);
+            _config = _config with { OutputDirectory = directory };
+        }
+    }
+
     public void Configure(CaptureConfiguration configuration)
     {
         lock (_lock)
         {
+            if (_starting)
+                throw CaptureException.ConfigurationFailed("Cannot configure while capture is starting");
             if (!_state.CanTransitionTo(CaptureStateKind.Configuring))
                 throw CaptureException.ConfigurationFailed($"Cannot configure in state {_state.Kind}");
 
@@ -174,22 +496,38 @@ public sealed class WasapiCaptureSession : ICaptureSession
 
         try
         {
+            Validate(configuration);
+            if (!IsSupported
+                && ((configuration.EnableMicCapture && _micFactory == null)
+                    || (configuration.EnableSystemCapture && _systemFactory == null)))
+                throw CaptureException.ConfigurationFailed(
+                    "WASAPI capture needs Windows 10 1809 or later; inject sources to record elsewhere");
+
+            _mixMatrix = configuration.Channels == 2
+                ? null
+                : MixMatrix.ForLayout(configuration.Channels, configuration.MixingStrategy);
             _config = configuration;
-            Directory.CreateDirectory(configuration.OutputDirectory);
+            if (!configuration.CaptureToMemory && configuration.OutputSink == null)
+                ProbeOutputDirectory(configuration.OutputDirectory);
 
             // Resolve mic device. Skipped entirely when a mic source is injected:
             // MMDeviceEnumerator throws on machines with no audio endpoints, and
             // this runs before StartCaptureAsync — so guarding only the start path
             // would still fail here.
+            // Core Audio is reached from the MTA whatever the caller's apartment; see ComThread.
             if (configuration.EnableMicCapture && _micFactory == null)
             {
-                using var enumerator = new MMDeviceEnumerator();
-                _micDevice = configuration.MicDeviceId != null
-                    ? enumerator.GetDevice(configuration.MicDeviceId)
-                    : enumerator.GetDefaultAudioEndpoint(DataFlow.Capture, Role.Multimedia);
-
-                if (_micDevice == null)
-                    throw CaptureException.DeviceNotAvailable("No microphone device found");
+                ComThread.Run(() =>
+                {
+                    (_micDevice, _micDeviceId) = ResolveMicDeviceOrFallBack(configuration);
+                    if (configuration.MicFormatOverride is { } micFormat)
+                        ResolveFormatOverride(_micDevice, micFormat, "microphone");
+
+                    using var enumerator = new MMDeviceEnumerator();
+                    _additionalMicDevices = configuration.AdditionalMicDeviceIds
+                        .Select(enumerator.GetDevice)
+                        .ToArray();
+                });
             }
 
             lock (_lock) TransitionTo(CaptureState.Ready);
@@ -207,122 +545,529 @@ public sealed class WasapiCaptureSession : ICaptureSession
         }
     }
 
+    /// <summary>
+    /// Rejects a configuration that can't record as asked, before anything is opened.
+    /// </summary>
+    /// <exception cref="CaptureException">ConfigurationFailed, saying what is wrong.</exception>
+    private static void Validate(CaptureConfiguration configuration)
+    {
+        // Nothing to record would otherwise start anyway and write an empty file until stopped.
+        if (!configuration.EnableMicCapture && !configuration.EnableSystemCapture)
+            throw CaptureException.ConfigurationFailed("No sources enabled: enable the mic, system audio or both");
+        if (configuration.MemoryBudgetBytes is <= 0)
+            throw CaptureException.ConfigurationFailed(
+                $"Memory budget must be positive, got {configuration.MemoryBudgetBytes}");
+        if (configuration.DeviceBufferDuration <= TimeSpan.Zero)
+            throw CaptureException.ConfigurationFailed(
+                $"Device buffer duration must be positive, got {configuration.DeviceBufferDuration}");
+        if (configuration.MicDeviceId != null && configuration.MicDeviceName != null)
+            throw CaptureException.ConfigurationFailed("Select the mic by ID or by name, not both");
+        if (configuration.PrimeDuration < TimeSpan.Zero)
+            throw CaptureException.ConfigurationFailed(
+                $"Prime duration must not be negative, got {configuration.PrimeDuration}");
+        if (configuration.DeviceOpenRetries < 0 || configuration.DeviceOpenBackoff < TimeSpan.Zero)
+            throw CaptureException.ConfigurationFailed(
+                "Device open retries and backoff must not be negative");
+        if (configuration.EncryptionChunkSize is <= 0)
+            throw CaptureException.ConfigurationFailed(
+                $"Encryption chunk size must be positive, got {configuration.EncryptionChunkSize}");
+        if (configuration.WriteCombineBytes is <= 0)
+            throw CaptureException.ConfigurationFailed(
+                $"Write-combine size must be positive, got {configuration.WriteCombineBytes}");
+        if (configuration.ResumeFilePath != null && !File.Exists(configuration.ResumeFilePath))
+            throw CaptureException.ConfigurationFailed(
+                $"Cannot resume {Path.GetFileName(configuration.ResumeFilePath)}: file does not exist");
+        // The header can't say whether the data is sealed, so the name has to:
+        // appending plaintext to an encrypted file (or the reverse) ruins both runs.
+        if (configuration.ResumeFilePath is { } resumePath
+            && (!resumePath.EndsWith(".wav", StringComparison.Ordinal)
+                || resumePath.EndsWith(".enc.wav", StringComparison.Ordinal) != (configuration.Encryptor != null)))
+            throw CaptureException.ConfigurationFailed(
+                "Resume target must be a .enc.wav when an encryptor is set and a plain .wav otherwise");
+        if (configuration.CaptureToMemory && (configuration.ResumeFilePath != null || configuration.ExportRawPcm))
+            throw CaptureException.ConfigurationFailed(
+                "In-memory capture has no file to resume or to name sidecars after");
+        if (configuration.SequenceBoundChunks
+            && (configuration.Encryptor == null || configuration.ResumeFilePath != null || configuration.OutputSink != null))
+            throw CaptureException.ConfigurationFailed(
+                "Sequence-bound chunks need an encryptor, and a new file rather than a resumed one or a sink");
+        if (configuration.OutputSink != null
+            && (configuration.CaptureToMemory || configuration.ResumeFilePath != null
+                || configuration.ExportRawPcm || configuration.EncryptionChunkSize != null))
+            throw CaptureException.ConfigurationFailed(
+                "An output sink has no file to resume, keep in memory, name sidecars after or seal in fixed chunks");
+        if (configuration.ProcessingFormat == SampleFormat.Int16
+            && (!configuration.EnableMicCapture || configuration.EnableSystemCapture
+                || configuration.Channels != 1 || configuration.BitDepth != 16
+                || configuration.MicGate != null || configuration.MicAgc != null
+                || configuration.TruePeakMetering || configuration.SkipSilence != null))
+            throw CaptureException.ConfigurationFailed(
+                "Int16 processing needs a mic-only, mono, 16-bit recording with no gate, AGC, " +
+                "true-peak metering or silence skipping");
+        // Checked here rather than left to the gate's, AGC's and silence skipper's
+        // constructors, which run only once the file and sidecars are already open.
+        if (configuration.MicGate is { } gate
+            && (!float.IsFinite(gate.ThresholdDbfs) || gate.ThresholdDbfs > 0f || !(gate.FloorDb <= 0f)
+                || gate.Attack <= TimeSpan.Zero || gate.Release <= TimeSpan.Zero || gate.Hold < TimeSpan.Zero))
+            throw CaptureException.ConfigurationFailed(
+                "Mic gate needs a threshold and floor of at most 0 dB, positive attack and release, and a non-negative hold");
+        if (configuration.MicAgc is { } agc
+            && (!(agc.TargetRms > 0f) || !(agc.MaxGain >= 1f) || agc.Attack <= TimeSpan.Zero || agc.Release <= TimeSpan.Zero))
+            throw CaptureException.ConfigurationFailed(
+                "Mic AGC needs a positive target RMS, a max gain of at least 1, and positive attack and release");
+        if (configuration.SkipSilence is { } skip
+            && (!float.IsFinite(skip.ThresholdDbfs) || skip.ThresholdDbfs > 0f || skip.Hold < TimeSpan.Zero))
+            throw CaptureException.ConfigurationFailed(
+                "Silence skipping needs a threshold of at most 0 dBFS and a non-negative hold");
+        if (configuration.MicPlacement != MicPlacement.Center
+            && (configuration.Channels != 2 || configuration.MixingStrategy != MixingStrategy.Blended))
+            throw CaptureException.ConfigurationFailed(
+                "Mic placement applies only to a blended stereo mix");
+        if (configuration.SampleRate is < MinSampleRate or > MaxSampleRate)
+            throw CaptureException.ConfigurationFailed(
+                $"Sample rate must be {MinSampleRate}-{MaxSampleRate} Hz, got {configuration.SampleRate}");
+        if (configuration.DeliverySampleRate is < MinSampleRate or > MaxSampleRate)
+            throw CaptureException.ConfigurationFailed(
+                $"Delivery sample rate must be {MinSampleRate}-{MaxSampleRate} Hz, got {configuration.DeliverySampleRate}");
+        if (!configuration.AllowNonStandardSampleRate)
+        {
+            ValidateStandardSampleRate(configuration.SampleRate, "Sample rate");
+            if (configuration.DeliverySampleRate is { } deliveryRate)
+                ValidateStandardSampleRate(deliveryRate, "Delivery sample rate");
+        }
+        if (configuration.DeliverySampleRate != null && configuration.ProcessingFormat == SampleFormat.Int16)
+            throw CaptureException.ConfigurationFailed(
+                "Int16 processing writes the mic as captured; it can't deliver at another rate");
+        ValidateFormatOverride(configuration.MicFormatOverride, "Mic");
+        ValidateFormatOverride(configuration.SystemFormatOverride, "System");
+        if (configuration.MicFormatOverride != null && configuration.ProcessingFormat == SampleFormat.Int16)
+            throw CaptureException.ConfigurationFailed(
+                "Int16 processing writes the mic as captured; it can't take a format override");
+        if (configuration.MaxFileBytes is { } maxFileBytes && maxFileBytes <= EncryptedWavWriter.HeaderLength(configuration))
+            throw CaptureException.ConfigurationFailed(
+                $"Max file size must leave room past the {EncryptedWavWriter.HeaderLength(configuration)}-byte header, got {maxFileBytes}");
+        if (configuration.PreallocateFile && configuration.MaxDuration == null && configuration.MaxFileBytes == null)
+            throw CaptureException.ConfigurationFailed(
+                "Preallocating the file needs MaxDuration or MaxFileBytes to size it");
+        if (configuration.FloatOutput && configuration.BitDepth != 32)
+            throw CaptureException.ConfigurationFailed(
+                $"Float output needs a bit depth of 32, not {configuration.BitDepth}");
+        if (!MixMatrix.SupportedChannelCounts.Contains(configuration.Channels))
+            throw CaptureException.ConfigurationFailed(
+                $"Unsupported output channel count {configuration.Channels}; " +
+                $"supported: {string.Join(", ", MixMatrix.SupportedChannelCounts)}");
+    }
+
+    /// <summary>Rejects a rate outside <see cref="CaptureConfiguration.StandardSampleRates"/>, naming the likely intended one.</summary>
+    private static void ValidateStandardSampleRate(double rate, string name)
+    {
+        if (CaptureConfiguration.StandardSampleRates.Contains((int)rate) && rate == (int)rate)
+            return;
+        throw CaptureException.ConfigurationFailed(
+            $"{name} must be one of {string.Join(", ", CaptureConfiguration.StandardSampleRates)} Hz, got {rate} " +
+            $"(nearest is {CaptureConfiguration.NearestStandardSampleRate(rate)}); " +
+            "set AllowNonStandardSampleRate to use it anyway");
+    }
+
+    /// <summary>
+    /// Rejects an override no WASAPI client could be opened in — whether the endpoint
+    /// takes a plausible one is only known once it's asked.
+    /// </summary>
+    private static void ValidateFormatOverride(WavSpec? spec, string name)
+    {
+        if (spec == null)
+            return;
+        if (spec.SampleRate is < MinSampleRate or > MaxSampleRate)
+            throw CaptureException.ConfigurationFailed(
+                $"{name} format override rate must be {MinSampleRate}-{MaxSampleRate} Hz, got {spec.SampleRate}");
+        if (spec.Channels is < 1 or > 8)
+            throw CaptureException.ConfigurationFailed(
+                $"{name} format override must have 1-8 channels, got {spec.Channels}");
+        // 24-bit is a real WASAPI format, but neither capture path decodes it.
+        if (spec.BitDepth is not (16 or 32) || spec.IsFloat != (spec.BitDepth == 32))
+            throw CaptureException.ConfigurationFailed(
+                $"{name} format override must be 16-bit or 32-bit float, got {spec.BitDepth}-bit" +
+                (spec.IsFloat ? " float" : ""));
+    }
+
+    /// <summary>
+    /// The format override as the WASAPI client takes it, once
+    /// <paramref name="device"/> has said it accepts it in shared mode.
+    /// </summary>
+    private static WaveFormat ResolveFormatOverride(MMDevice device, WavSpec spec, string name)
+    {
+        // Extensible, as GetSupportedFormats probes with; 32 means float.
+        var format = new WaveFormatExtensible(spec.SampleRate, spec.BitDepth, spec.Channels);
+        bool supported;
+        try
+        {
+            using var client = device.AudioClient;
+            supported = client.IsFormatSupported(AudioClientShareMode.Shared, format);
+        }
+        catch (Exception)
+        {
+            // Some drivers fail the call for a format they don't know rather than answering no.
+            supported = false;
+        }
+        if (!supported)
+            throw CaptureException.ConfigurationFailed(
+                $"The {name} doesn't accept its format override " +
+                $"({spec.SampleRate} Hz, {spec.Channels} ch, {spec.BitDepth}-bit) in shared mode");
+        return format;
+    }
+
+    /// <summary>
+    /// The mic endpoint <paramref name="configuration"/> selects — by ID, by name, or
+    /// the default for its role — and the ID it was chosen by, null for the default.
+    /// </summary>
+    private static (MMDevice Device, string? Id) ResolveMicDevice(CaptureConfiguration configuration)
+    {
+        var micDeviceId = configuration.MicDeviceId;
+        if (configuration.MicDeviceName is { } micName)
+        {
+            micDeviceId = DeviceEnumerator.FindCaptureDeviceByName(micName, configuration.DeviceRole)?.Id
+                ?? throw CaptureException.DeviceNotAvailable($"No microphone named like \"{micName}\"");
+        }
+
+        using var enumerator = new MMDeviceEnumerator();
+        var device = micDeviceId != null
+            ? enumerator.GetDevice(micDeviceId)
+            : enumerator.GetDefaultAudioEndpoint(DataFlow.Capture, DeviceEnumerator.ToRole(configuration.DeviceRole));
+        if (device == null)
+            throw CaptureException.DeviceNotAvailable("No microphone device found");
+        // GetDevice finds an unplugged or disabled endpoint too; it just can't record.
+        if (micDeviceId != null && device.State != DeviceState.Active)
+            throw CaptureException.DeviceNotAvailable($"Microphone {micDeviceId} is {device.State}");
+        return (device, micDeviceId);
+    }
+
+    /// <summary>
+    /// As <see cref="ResolveMicDevice"/>, but with
+    /// <see cref="CaptureConfiguration.FallBackToDefaultMic"/> a chosen mic that can't
+    /// be found gives way to the default one, reported to the delegate. Null ID, as
+    /// for any default mic, so an invalidated fallback follows the default too.
+    /// </summary>
+    private (MMDevice Device, string? Id) ResolveMicDeviceOrFallBack(CaptureConfiguration configuration)
+    {
+        var requested = configuration.MicDeviceId ?? configuration.MicDeviceName;
+        try
+        {
+            return ResolveMicDevice(configuration);
+        }
+        catch (Exception) when (configuration.FallBackToDefaultMic && requested != null)
+        {
+            var fallback = ResolveMicDevice(configuration with { MicDeviceId = null, MicDeviceName = null });
+            var actual = DeviceEnumerator.ToAudioSource(fallback.Device, AudioTrackType.Mic, defaultId: fallback.Device.ID);
+            Delegate?.OnDeviceFallback(requested, actual);
+            return fallback;
+        }
+    }
+
+    /// <summary>
+    /// Whether a start should trade the mic Configure resolved for the default: only a
+    /// chosen one, only with <see cref="CaptureConfiguration.FallBackToDefaultMic"/>,
+    /// and only once it can no longer record. A default mic is re-resolved by the
+    /// capture itself.
+    /// </summary>
+    internal static bool NeedsMicFallback(CaptureConfiguration config, string? micDeviceId, DeviceState state) =>
+        config.FallBackToDefaultMic && micDeviceId != null && state != DeviceState.Active;
+
+    /// <summary>An endpoint's state; one removed outright may not answer at all.</summary>
+    private static DeviceState MicDeviceState(MMDevice device)
+    {
+        try
+        {
+            return device.State;
+        }
+        catch (COMException)
+        {
+            return DeviceState.NotPresent;
+        }
+    }
+
     public async Task<RecordingResult> StartCaptureAsync()
     {
+        // Checked and claimed in one go: two starts racing must not both get past.
         lock (_lock)
         {
+            if (_starting)
+                throw CaptureException.ConfigurationFailed("Capture is already starting");
             if (!_state.CanTransitionTo(CaptureStateKind.Capturing))
                 throw CaptureException.ConfigurationFailed($"Cannot start capture in state {_state.Kind}");
+            _starting = true;
+        }
+
+        Task<RecordingResult> finished;
+        try
+        {
+            finished = await BeginCaptureAsync().ConfigureAwait(false);
+        }
+        finally
+        {
+            lock (_lock) _starting = false;
         }
+        return await finished;
+    }
 
+    /// <summary>
+    /// Opens the writers and sources and starts the mix loop, for a start that has
+    /// claimed the session.
+    /// </summary>
+    /// <returns>The recording's result, completed when it stops.</returns>
+    private async Task<Task<RecordingResult>> BeginCaptureAsync()
+    {
         var config = _config ?? throw CaptureException.ConfigurationFailed("Not configured");
+
+        // The chosen mic may have been unplugged since Configure resolved it: fall back
+        // now, before any file is opened, as Configure would have.
+        if (config.EnableMicCapture && _micFactory == null && _micDevice != null)
+        {
+            ComThread.Run(() =>
+            {
+                if (NeedsMicFallback(config, _micDeviceId, MicDeviceState(_micDevice)))
+                    (_micDevice, _micDeviceId) = ResolveMicDeviceOrFallBack(config);
+            });
+        }
+
         _stopTcs = new TaskCompletionSource<RecordingResult>();
 
-        // Build file path
-        var timestamp = DateTime.Now.ToString("yyyyMMdd_HHmmss");
+        // Build file path. Resuming continues an earlier recording in place — its
+        // sidecars too — instead of starting a new timestamped one.
+        var resuming = config.ResumeFilePath != null;
         var ext = config.Encryptor != null ? ".enc.wav" : ".wav";
-        var filePath = Path.Combine(config.OutputDirectory, $"recording_{timestamp}{ext}");
+        var filePath = config.ResumeFilePath ?? Path.Combine(
+            config.OutputDirectory, $"recording_{DateTime.Now:yyyyMMdd_HHmmss}{ext}");
 
-        // Open WAV writer
-        _wavFilePath = filePath;
-        _wavWriter = new EncryptedWavWriter(filePath, config.Encryptor);
-        _wavWriter.Open(config);
+        // Open WAV writer, or the caller's sink in its place
+        if (config.OutputSink is { } sink)
+        {
+            _wavFilePath = null;
+            _wavWriter = config.Encryptor != null ? new SealingOutputSink(sink, config.Encryptor) : sink;
+            _wavWriter.Open(config);
+        }
+        else
+        {
+            EncryptedWavWriter writer;
+            if (config.CaptureToMemory)
+            {
+                _wavFilePath = null;
+                _memoryTarget = new MemoryStream();
+                writer = new EncryptedWavWriter(_memoryTarget, config.Encryptor, leaveOpen: true);
+            }
+            else
+            {
+                _wavFilePath = filePath;
+                writer = new EncryptedWavWriter(filePath, config.Encryptor);
+            }
+            _wavWriter = writer;
+            if (resuming)
+                writer.OpenAppend(config);
+            else
+                writer.Open(config);
+        }
 
-        // Open raw PCM sidecar files if requested
+        // Open raw PCM sidecar files if requested, named after the WAV
+        // ({stem}_mic / {stem}_system) and alongside it.
         // When encrypted, uses .enc.pcm extension with length-prefixed encrypted chunks
         // matching the macOS Swift implementation (no plaintext PCM on disk).
-        string? micPcmPath = null;
-        string? systemPcmPath = null;
+        _micPcmPath = null;
+        _systemPcmPath = null;
         if (config.ExportRawPcm)
         {
             var pcmExt = config.Encryptor != null ? "enc.pcm" : "pcm";
+            var directory = Path.GetDirectoryName(filePath) ?? config.OutputDirectory;
+            var stem = Path.GetFileName(filePath)[..^ext.Length];
+            var mode = resuming ? FileMode.Append : FileMode.Create;
             if (config.EnableMicCapture)
             {
-                micPcmPath = Path.Combine(config.OutputDirectory, $"recording_{timestamp}_mic.{pcmExt}");
-                _micPcmWriter = new FileStream(micPcmPath, FileMode.Create);
+                _micPcmPath = Path.Combine(directory, $"{stem}_mic.{pcmExt}");
+                _micPcmWriter = new FileStream(_micPcmPath, mode);
             }
             if (config.EnableSystemCapture)
             {
-                systemPcmPath = Path.Combine(config.OutputDirectory, $"recording_{timestamp}_system.{pcmExt}");
-                _systemPcmWriter = new FileStream(systemPcmPath, FileMode.Create);
+                _systemPcmPath = Path.Combine(directory, $"{stem}_system.{pcmExt}");
+                _systemPcmWriter = new FileStream(_systemPcmPath, mode);
             }
         }
 
+        _micPrimeFrames = _systemPrimeFrames = (int)(config.PrimeDuration.TotalSeconds * config.SampleRate);
+        _passthrough = config.ProcessingFormat == SampleFormat.Int16;
+        lock (_lock)
+        {
+            _wroteSignal = false;
+            _wroteClipped = false;
+            _stopReason = StopReason.UserStopped;
+            _fileBytesAtOpen = _wavWriter is EncryptedWavWriter opened ? opened.BytesWritten : 0;
+            _filePcmBytes = _fileWrites = 0;
+            _fileSizeCapReached = false;
+            _micCallbackFaulted = _systemCallbackFaulted = false;
+            _micEnded = _systemEnded = false;
+            _writeFailure = null;
+            _memoryBudget = config.MemoryBudgetBytes;
+            _callbacksClosed = false;
+        }
+        _truePeakMetering = config.TruePeakMetering;
+        _micBallistics = config.LevelBallistics != null ? new MeterBallistics(config.LevelBallistics) : null;
+        _systemBallistics = config.LevelBallistics != null ? new MeterBallistics(config.LevelBallistics) : null;
+        _meterSampleRate = config.SampleRate;
+        lock (_lock) _micPassthroughLength = 0;
+        _micGate = config.MicGate != null ? new NoiseGate(config.MicGate, (int)config.SampleRate) : null;
+        _micAgc = config.MicAgc != null ? new AutomaticGainControl(config.MicAgc, (int)config.SampleRate) : null;
+        _deliveryResampler = config.DeliverySampleRate is { } deliveryRate && deliveryRate != (int)config.SampleRate
+            ? config.ResamplerFactory?.Invoke() ?? new WdlAudioResampler()
+            : null;
+        _silenceSkipper = config.SkipSilence != null
+            ? new SilenceSkipper(config.SkipSilence, (int)config.OutputSampleRate, config.Channels)
+            : null;
+
+        _micFormatValid = _systemFormatValid = null;
+
+        // Injected sources run on pool threads, which must not be left boosted.
+        _pendingMicMmcssTask = _micFactory == null ? config.MicMmcssTask : null;
+        _pendingSystemMmcssTask = _systemFactory == null ? config.SystemMmcssTask : null;
+
         // Start mic capture. An injected factory stands in for the endpoint, so
         // there's no _micDevice to require in that case.
+        lock (_lock) _micConversion = MicConversion.None;
         if (config.EnableMicCapture && (_micFactory != null || _micDevice != null))
         {
-            _micCapture = _micFactory?.Invoke() ?? CreateWasapiMic(config);
-            _micCapture.DataAvailable += OnMicDataAvailable;
-            _micCapture.RecordingStopped += OnMicRecordingStopped;
-            _micCapture.StartRecording();
+            try
+            {
+                // A downmix other than Average is ours to do, so the engine mustn't fold
+                // first; an overridden format is one the engine wasn't asked to convert.
+                var convertHere = config.MicDownmix != MicDownmix.Average || config.MicFormatOverride != null;
+                if (_micFactory == null)
+                    lock (_lock) _micConversion = convertHere ? MicConversion.Resampler : MicConversion.AudioEngine;
+                try
+                {
+                    await StartSourceAsync(
+                        () => _micCapture = _micFactory?.Invoke() ?? CreateWasapiMic(config, resample: convertHere),
+                        OnMicDataAvailable, OnMicRecordingStopped, config).ConfigureAwait(false);
+                }
+                catch (Exception ex) when (_micFactory == null && !convertHere && AudioClientErrors.IsUnsupportedFormat(ex))
+                {
+                    // The engine won't convert to the configured format — its stream
+                    // flags may leave AutoConvertPcm out. Open at the mix format and
+                    // resample here instead. No source to release if building it failed.
+                    if (_micCapture is { } refused)
+                    {
+                        refused.DataAvailable -= OnMicDataAvailable;
+                        refused.RecordingStopped -= OnMicRecordingStopped;
+                        refused.Dispose();
+                        _micCapture = null;
+                    }
+                    lock (_lock) _micConversion = MicConversion.Resampler;
+                    await StartSourceAsync(
+                        () => _micCapture = CreateWasapiMic(config, resample: true),
+                        OnMicDataAvailable, OnMicRecordingStopped, config).ConfigureAwait(false);
+                }
+            }
+            catch (Exception ex) when (AudioClientErrors.IsDeviceInUse(ex))
+            {
+                // Exclusive-mode holders are the one start failure the user can fix
+                // themselves, so say which it is rather than a generic init error.
+                var error = CaptureException.DeviceInUse("Microphone is in use by another application");
+                AbortStart(error);
+                throw error;
+            }
         }
 
         // Start system loopback capture
         if (config.EnableSystemCapture)
         {
-            _systemCapture = _systemFactory?.Invoke() ?? new WasapiLoopbackCapture();
-
-            // Built before the first callback can fire: the endpoint's format is
-            // only knowable now, and OnSystemDataAvailable relies on this being set.
-            _systemNormalizer = new SystemAudioNormalizer(
-                _systemCapture.WaveFormat.SampleRate,
-                _systemCapture.WaveFormat.Channels,
-                (int)config.SampleRate);
-
-            _systemCapture.DataAvailable += OnSystemDataAvailable;
-            _systemCapture.RecordingStopped += OnSystemRecordingStopped;
-            _systemCapture.StartRecording();
+            await StartSourceAsync(
+                () => CreateSystemSource(config),
+                OnSystemDataAvailable, OnSystemRecordingStopped, config).ConfigureAwait(false);
         }
 
+        // Counts as a packet: Auto shouldn't take the clock from system audio before
+        // it has had a chance to deliver.
+        lock (_lock) _lastSystemChunkTimestamp = _time.GetTimestamp();
+
         // Start the mix loop (every 100ms, mix buffered audio and write)
         _mixCts = new CancellationTokenSource();
-        _mixTimer = new PeriodicTimer(TimeSpan.FromMilliseconds(100));
+        _mixTimer = new PeriodicTimer(CaptureConfiguration.MixInterval);
         _mixPump = Task.Run(() => RunMixPumpAsync(_mixTimer, _mixCts.Token));
 
         // Start duration tracking
-        _durationStopwatch.Restart();
+        lock (_lock)
+        {
+            _systemActivity.Clear();
+            _systemActiveSince = null;
+            _durationClock.Restart();
+        }
 
         // Max duration timer
         if (config.MaxDuration.HasValue)
         {
             _maxDurationTimer = new Timer(
-                _ => { _ = StopCaptureAsync(); },
+                _ => StopFor(StopReason.MaxDuration),
                 null,
                 config.MaxDuration.Value,
                 Timeout.InfiniteTimeSpan);
         }
 
+        if (config.StallTimeout is { } stallTimeout)
+            _stallTimer = new Timer(CheckForStall, null, stallTimeout, stallTimeout);
+
         lock (_lock)
         {
             TransitionTo(CaptureState.Capturing(TimeSpan.Zero));
         }
 
-        return await _stopTcs.Task;
+        return _stopTcs.Task;
     }
 
     public void PauseCapture()
     {
+        bool pauseDevices;
         lock (_lock)
         {
             if (!_state.CanTransitionTo(CaptureStateKind.Paused))
                 return;
 
-            _durationStopwatch.Stop();
-            TransitionTo(CaptureState.Paused(_durationStopwatch.Elapsed));
+            _durationClock.Stop();
+            TransitionTo(CaptureState.Paused(_durationClock.Elapsed));
+            pauseDevices = _devicesPaused = _config!.PauseDevices;
+        }
+        if (pauseDevices)
+            PauseSources();
+
+        // Callbacks drop audio from here on, so whatever is buffered is everything up
+        // to the pause. Write it all and make the file whole on disk, so a segment
+        // uploaded now ends exactly where the pause began.
+        MixAndWrite(flush: true);
+        try
+        {
+            _wavWriter?.Flush();
+            lock (_sidecarLock)
+            {
+                _micPcmWriter?.Flush(flushToDisk: true);
+                _systemPcmWriter?.Flush(flushToDisk: true);
+            }
+        }
+        catch (Exception ex)
+        {
+            Delegate?.OnError(CaptureException.StorageError($"Flush at pause failed: {ex.Message}"));
         }
     }
 
     public void ResumeCapture()
     {
+        bool devicesPaused;
         lock (_lock)
         {
             if (!_state.CanTransitionTo(CaptureStateKind.Capturing))
                 return;
 
-            _durationStopwatch.Start();
-            TransitionTo(CaptureState.Capturing(_durationStopwatch.Elapsed));
+            _durationClock.Start();
+            TransitionTo(CaptureState.Capturing(_durationClock.Elapsed));
+            devicesPaused = _devicesPaused;
         }
+        if (!devicesPaused) return;
+
+        ResumeSources();
+        lock (_lock) _devicesPaused = false;
     }
 
     public async Task<RecordingResult> StopCaptureAsync()
@@ -332,26 +1077,58 @@ public sealed class WasapiCaptureSession : ICaptureSession
             if (_state.Kind != CaptureStateKind.Capturing && _state.Kind != CaptureStateKind.Paused)
                 throw CaptureException.ConfigurationFailed($"Cannot stop in state {_state.Kind}");
 
-            _durationStopwatch.Stop();
+            _durationClock.Stop();
             TransitionTo(CaptureState.Stopping);
         }
 
         _maxDurationTimer?.Dispose();
         _maxDurationTimer = null;
-
-        // Stop the sources first so no new samples arrive, then drain the pump. Only
-        // once the drain returns is this thread the sole writer, which is what makes
-        // the final flush and Close below safe.
+        _stallTimer?.Dispose();
+        _stallTimer = null;
+
+        // Shutdown runs in a fixed order, each step happening-before the next:
+        //  1. Stop the sources. A source's thread may still be mid-callback after
+        //     StopRecording returns — WASAPI's does until its loop notices.
+        //  2. Close the callback gate and wait out any callback in flight, so from
+        //     here on no capture thread touches the buffers.
+        //  3. Drain the pump. Only once it returns is this thread the sole writer.
+        //  4. Flush what's buffered and close the writer. Nothing mixes after this.
         _micCapture?.StopRecording();
         _systemCapture?.StopRecording();
+        CloseCallbacks();
 
         await StopMixPumpAsync().ConfigureAwait(false);
 
         // Flush remaining buffered audio
         MixAndWrite(flush: true);
 
-        // Close writers
-        var checksum = _wavWriter?.Close() ?? "";
+        // Close writers. After a failed write — in the flush above too — closing may
+        // fail the same way; the file is best-effort and its checksum meaningless.
+        // A close that fails on its own fails the run just the same.
+        CaptureException? writeFailure;
+        lock (_lock) writeFailure = _writeFailure;
+        var spec = _wavWriter?.Spec;
+        var checksum = "";
+        ChunkManifest? manifest = null;
+        if (writeFailure == null)
+        {
+            try
+            {
+                checksum = _wavWriter?.Close() ?? "";
+                manifest = (_wavWriter as EncryptedWavWriter)?.Manifest;
+            }
+            catch (Exception ex)
+            {
+                writeFailure = ex as CaptureException
+                    ?? CaptureException.StorageError($"Finalizing the recording failed: {ex.Message}");
+                DiscardWriter();
+            }
+        }
+        else
+        {
+            DiscardWriter();
+        }
+        _wavWriter = null;
 
         // Close the sidecars under the lock the capture callbacks write through, so a
         // handler still running as we stop can't write into a disposed stream. After
@@ -366,21 +1143,64 @@ public sealed class WasapiCaptureSession : ICaptureSession
         }
 
         var config = _config!;
-        var duration = _durationStopwatch.Elapsed;
+        var duration = _durationClock.Elapsed;
         var filePath = _wavFilePath ?? "";
 
         var (tracks, channelLayout) = BuildTrackMetadata(config);
 
+        // A start immediately followed by a stop leaves a header and little or no
+        // audio, which some downstream tools reject outright. Flag it, and drop the
+        // files when asked — never when resuming, where the file holds an earlier run.
+        long writtenBytes;
+        bool silent;
+        bool clipped;
+        StopReason stopReason;
+        lock (_lock)
+        {
+            writtenBytes = _bytesWritten;
+            silent = !_wroteSignal;
+            clipped = _wroteClipped;
+            stopReason = _stopReason;
+        }
+        var bytesPerSecond = config.OutputSampleRate * config.Channels * config.BitDepth / 8.0;
+        var tooShort = writtenBytes == 0
+            || writtenBytes / bytesPerSecond < config.MinimumRecordingDuration.TotalSeconds;
+        if (tooShort && config.DeleteTooShortRecordings && config.ResumeFilePath == null)
+        {
+            DeleteIfExists(filePath);
+            DeleteIfExists(_micPcmPath);
+            DeleteIfExists(_systemPcmPath);
+            _memoryTarget?.SetLength(0);
+            checksum = "";
+            manifest = null;
+        }
+
+        var audioData = _memoryTarget is { Length: > 0 } ? _memoryTarget.ToArray() : null;
+        _memoryTarget?.Dispose();
+        _memoryTarget = null;
+
         var rawPcmPaths = new List<string>();
-        if (_config!.ExportRawPcm)
+        if (_micPcmPath != null && File.Exists(_micPcmPath)) rawPcmPaths.Add(_micPcmPath);
+        if (_systemPcmPath != null && File.Exists(_systemPcmPath)) rawPcmPaths.Add(_systemPcmPath);
+
+        ActivityInterval[] systemActivity;
+        StateTransition[] stateLog;
+        lock (_lock)
         {
-            var pcmExt = config.Encryptor != null ? "enc.pcm" : "pcm";
-            var timestamp = Path.GetFileNameWithoutExtension(filePath)
-                .Replace("recording_", "").Replace(".enc", "");
-            var micPcm = Path.Combine(config.OutputDirectory, $"recording_{timestamp}_mic.{pcmExt}");
-            var sysPcm = Path.Combine(config.OutputDirectory, $"recording_{timestamp}_system.{pcmExt}");
-            if (File.Exists(micPcm)) rawPcmPaths.Add(micPcm);
-            if (File.Exists(sysPcm)) rawPcmPaths.Add(sysPcm);
+            if (_systemActiveSince is { } openSince)
+                _systemActivity.Add(new ActivityInterval(openSince, duration.TotalSeconds));
+            _systemActiveSince = null;
+            systemActivity = [.. _systemActivity];
+            stateLog = config.RecordStateLog ? [.. _stateLog] : [];
+        }
+        SkippedGap[] skippedGaps = [];
+        if (_silenceSkipper != null)
+        {
+            lock (_mixLock)
+            {
+                _silenceSkipper.Finish();
+                skippedGaps = [.. _silenceSkipper.Gaps];
+            }
         }
 
         var metadata = new RecordingMetadata(
@@ -393,23 +1213,52 @@ public sealed class WasapiCaptureSession : ICaptureSession
             Tracks: [.. tracks],
             EncryptionAlgorithm: config.Encryptor?.Algorithm,
             EncryptionKeyId: config.Encryptor?.KeyMetadata.GetValueOrDefault("keyId"),
-            ChannelLayout: channelLayout);
+            ChannelLayout: channelLayout)
+        {
+            SystemActivity = systemActivity,
+            IsSilent = silent,
+            ChunkManifest = manifest,
+            StateLog = stateLog,
+            SkippedGaps = skippedGaps,
+            StopReason = stopReason,
+            Tags = new Dictionary<string, string>(config.Tags),
+            EncryptionKeyMetadata = config.Encryptor?.KeyMetadata ?? new Dictionary<string, string>(),
+        };
 
         var result = new RecordingResult(
             FilePath: filePath,
             DurationSecs: duration.TotalSeconds,
             Metadata: metadata,
             Checksum: checksum,
-            RawPcmFilePaths: [.. rawPcmPaths]);
+            RawPcmFilePaths: [.. rawPcmPaths])
+        {
+            IsTooShort = tooShort,
+            IsSilent = silent,
+            DidClip = clipped,
+            StopReason = stopReason,
+            AudioData = audioData,
+            Spec = spec,
+        };
 
         // Clean up NAudio resources
         DisposeCapture();
 
+        if (writeFailure != null)
+            SettlePartialRecording(config, filePath, writeFailure, writtenBytes, duration);
+
         lock (_lock)
         {
-            TransitionTo(CaptureState.Completed(result));
+            TransitionTo(writeFailure != null ? CaptureState.Failed(writeFailure) : CaptureState.Completed(result));
+        }
+        if (writeFailure != null)
+        {
+            _stopTcs?.TrySetException(writeFailure);
+            throw writeFailure;
         }
 
+        if (silent && config.ReportSilentRecordings)
+            Delegate?.OnError(CaptureException.DeviceNotAvailable(
+                "The recording is silent: check the input device and its mute, and that something was playing"));
         Delegate?.OnCaptureFinished(result);
         _stopTcs?.TrySetResult(result);
 
@@ -417,55 +1266,609 @@ public sealed class WasapiCaptureSession : ICaptureSession
     }
 
     public Task<AudioSource[]> GetAvailableAudioSourcesAsync() =>
-        Task.FromResult(DeviceEnumerator.GetAllDevices());
+        DeviceEnumerator.GetAllDevicesAsync(_config?.DeviceRole ?? DeviceRole.Multimedia);
 
-    public void Dispose()
+    /// <summary>
+    /// Per-source availability with a reason when unavailable, for the configured
+    /// mic device. See <see cref="DeviceEnumerator.GetSourceStatuses"/>.
+    /// </summary>
+    public Task<AudioSourceStatus[]> GetSourceStatusesAsync() =>
+        DeviceEnumerator.GetSourceStatusesAsync(
+            _micDeviceId ?? _config?.MicDeviceId, _config?.DeviceRole ?? DeviceRole.Multimedia);
+
+    /// <summary>
+    /// Listens to system audio for <paramref name="window"/> and reports whether
+    /// anything audible played, with the default render device's name — so the app
+    /// can say "nothing is playing" instead of implying loopback is broken. Opens its
+    /// own loopback source, so it works in any state, mid-capture included.
+    /// </summary>
+    public async Task<SystemAudioProbe> ProbeSystemAudioAsync(TimeSpan window)
     {
-        // A session disposed without a stop can still have a pump running. Wait it out
-        // before the writers go away underneath it. After a normal stop the pump is
-        // already drained and this is a no-op. The pump never captures a sync context,
-        // so the wait cannot deadlock on one.
-        //
-        // Best effort throughout: Dispose must not throw, and racing a concurrent stop
-        // can hand us an already-disposed source or a faulted pump.
-        try
+        ArgumentOutOfRangeException.ThrowIfLessThanOrEqual(window, TimeSpan.Zero);
+
+        string? deviceName = null;
+        if (_systemFactory == null)
         {
-            _mixCts?.Cancel();
-            _mixTimer?.Dispose();
-            _mixPump?.Wait(TimeSpan.FromSeconds(5));
-            _mixCts?.Dispose();
+            var renderDevices = await DeviceEnumerator.GetRenderDevicesAsync(_config?.DeviceRole ?? DeviceRole.Multimedia);
+            deviceName = renderDevices.FirstOrDefault(d => d.IsDefault)?.Name;
+            if (deviceName == null)
+                return new SystemAudioProbe(IsAudible: false, RenderDeviceName: null);
         }
-        catch
+
+        using var source = _systemFactory?.Invoke()
+            ?? ComThread.Run(() => CreateWasapiLoopback(_config ?? new CaptureConfiguration()));
+        var audible = false;
+        var stopped = new TaskCompletionSource(TaskCreationOptions.RunContinuationsAsynchronously);
+        source.DataAvailable += (_, e) =>
         {
-            // A faulted pump has already reported via OnError.
-        }
+            if (e.BytesRecorded > 0 && IsAudible(ReadLoopbackSamples(e, source.WaveFormat)))
+                Volatile.Write(ref audible, true);
+        };
+        source.RecordingStopped += (_, _) => stopped.TrySetResult();
+
+        ComThread.Run(source.StartRecording);
+        await Task.Delay(window);
+        source.StopRecording();
+        // The answer is already in; a source slow to confirm its stop doesn't change it.
+        await Task.WhenAny(stopped.Task, Task.Delay(TimeSpan.FromSeconds(1)));
+
+        return new SystemAudioProbe(Volatile.Read(ref audible), deviceName);
+    }
 
-        _maxDurationTimer?.Dispose();
-        DisposeCapture();
-        _wavWriter?.Dispose();
-        lock (_sidecarLock)
+    /// <summary>
+    /// Checks whether <paramref name="configuration"/> could record here and now,
+    /// without recording: validates it, checks the output directory takes writes, and
+    /// opens each enabled source to see a buffer arrive within
+    /// <paramref name="timeout"/> — the "ready to record?" step of a setup wizard.
+    /// Leaves the session's state and configuration alone, so it can run before
+    /// <see cref="Configure"/> or between recordings, but not during one.
+    /// </summary>
+    public async Task<SelfTestReport> SelfTestAsync(CaptureConfiguration configuration, TimeSpan timeout)
+    {
+        ArgumentNullException.ThrowIfNull(configuration);
+        ArgumentOutOfRangeException.ThrowIfLessThanOrEqual(timeout, TimeSpan.Zero);
+        lock (_lock)
         {
-            _sidecarsClosed = true;
-            _micPcmWriter?.Dispose();
-            _micPcmWriter = null;
-            _systemPcmWriter?.Dispose();
-            _systemPcmWriter = null;
+            if (_state.Kind is CaptureStateKind.Capturing or CaptureStateKind.Paused or CaptureStateKind.Stopping)
+                throw CaptureException.ConfigurationFailed($"Cannot self-test in state {_state.Kind}");
         }
-    }
 
-    // --- Private helpers ---
+        string? configurationError = null;
+        try { Validate(configuration); }
+        catch (CaptureException ex) { configurationError = ex.Message; }
 
-    /// <summary>Builds the real WASAPI mic source in the configured capture format.</summary>
-    private IWaveIn CreateWasapiMic(CaptureConfiguration config)
-    {
-        var capture = new WasapiCapture(_micDevice);
-        capture.WaveFormat = new WaveFormat((int)config.SampleRate, config.BitDepth, 1);
+        string? outputError = null;
+        if (!configuration.CaptureToMemory && configuration.OutputSink == null)
+        {
+            try { ProbeOutputDirectory(configuration.OutputDirectory); }
+            catch (CaptureException ex) { outputError = ex.Message; }
+        }
+
+        var sources = new List<SourceSelfTest>();
+        if (configuration.EnableMicCapture)
+        {
+            sources.Add(await SelfTestSourceAsync(
+                AudioTrackType.Mic,
+                () => _micFactory?.Invoke() ?? CreateSelfTestMic(configuration),
+                timeout).ConfigureAwait(false));
+        }
+        if (configuration.EnableSystemCapture)
+        {
+            sources.Add(await SelfTestSourceAsync(
+                AudioTrackType.System,
+                () => _systemFactory?.Invoke() ?? CreateWasapiLoopback(configuration),
+                timeout).ConfigureAwait(false));
+        }
+
+        return new SelfTestReport(configurationError, outputError, [.. sources]);
+    }
+
+    private static ConfiguredWasapiCapture CreateSelfTestMic(CaptureConfiguration configuration)
+    {
+        var device = ResolveMicDevice(configuration).Device;
+        var bufferMs = ResolveDeviceBufferMilliseconds(device, configuration.DeviceBufferDuration);
+        var capture = new ConfiguredWasapiCapture(device, bufferMs, loopback: false, configuration.MicStreamFlags);
+        if (configuration.MicFormatOverride is { } micFormat)
+            capture.WaveFormat = ResolveFormatOverride(device, micFormat, "microphone");
+        return capture;
+    }
+
+    /// <summary>Opens a source, waits up to <paramref name="timeout"/> for a buffer, and closes it.</summary>
+    private static async Task<SourceSelfTest> SelfTestSourceAsync(
+        AudioTrackType type, Func<IWaveIn> create, TimeSpan timeout)
+    {
+        var delivered = new TaskCompletionSource(TaskCreationOptions.RunContinuationsAsynchronously);
+        IWaveIn? source = null;
+        try
+        {
+            source = ComThread.Run(create);
+            source.DataAvailable += (_, e) =>
+            {
+                if (e.BytesRecorded > 0) delivered.TrySetResult();
+            };
+            ComThread.Run(source.StartRecording);
+        }
+        catch (Exception ex)
+        {
+            source?.Dispose();
+            var status = AudioClientErrors.IsAccessDenied(ex) ? SourceStatus.PermissionDenied
+                : AudioClientErrors.IsDeviceInUse(ex) ? SourceStatus.InUse
+                : SourceStatus.NoDevice;
+            return new SourceSelfTest(type, status, Delivered: false, ex.Message);
+        }
+
+        using (source)
+        {
+            var arrived = await Task.WhenAny(delivered.Task, Task.Delay(timeout)).ConfigureAwait(false) == delivered.Task;
+            source.StopRecording();
+            return new SourceSelfTest(type, SourceStatus.Available, arrived, Error: null);
+        }
+    }
+
+    /// <summary>
+    /// Stops a session still capturing or paused as <see cref="StopCaptureAsync"/>
+    /// would — buffered audio written, header patched, sidecars closed, the pending
+    /// <see cref="StartCaptureAsync"/> completed — then releases everything. Best
+    /// effort and bounded: a stop that can't finish in a few seconds is abandoned
+    /// to the teardown below, which still closes the file.
+    /// </summary>
+    public void Dispose()
+    {
+        bool running;
+        lock (_lock) running = _state.Kind is CaptureStateKind.Capturing or CaptureStateKind.Paused;
+        if (running)
+        {
+            try
+            {
+                // StopCaptureAsync never resumes on a captured context, so blocking
+                // here can't deadlock on one.
+                StopCaptureAsync().Wait(TimeSpan.FromSeconds(5));
+            }
+            catch
+            {
+                // Reported via OnError; fall through to the teardown.
+            }
+        }
+
+        // A session disposed without a stop can still have a pump running. Wait it out
+        // before the writers go away underneath it. After a normal stop the pump is
+        // already drained and this is a no-op. The pump never captures a sync context,
+        // so the wait cannot deadlock on one.
+        //
+        // Best effort throughout: Dispose must not throw, and racing a concurrent stop
+        // can hand us an already-disposed source or a faulted pump.
+        try
+        {
+            _mixCts?.Cancel();
+            _mixTimer?.Dispose();
+            _mixPump?.Wait(TimeSpan.FromSeconds(5));
+            _mixCts?.Dispose();
+        }
+        catch
+        {
+            // A faulted pump has already reported via OnError.
+        }
+
+        _maxDurationTimer?.Dispose();
+        _stallTimer?.Dispose();
+        DisposeCapture();
+        DiscardWriter();
+        lock (_sidecarLock)
+        {
+            _sidecarsClosed = true;
+            _micPcmWriter?.Dispose();
+            _micPcmWriter = null;
+            _systemPcmWriter?.Dispose();
+            _systemPcmWriter = null;
+        }
+    }
+
+    // --- Private helpers ---
+
+    /// <summary>
+    /// Joins the calling capture thread to <paramref name="pendingTask"/> once, then
+    /// clears it. A refusal is counted and reported through
+    /// <see cref="ICaptureDelegate.OnError"/>, and capture carries on — priority is an
+    /// optimization.
+    /// </summary>
+    private void JoinMmcss(ref string? pendingTask, ref Mmcss.Registration? registration)
+    {
+        var task = pendingTask!;
+        pendingTask = null;
+        var joined = Mmcss.RegisterCurrentThread(task, out var refusal);
+        if (joined == null)
+        {
+            lock (_lock) _mmcssFailures++;
+            Delegate?.OnError(CaptureException.ConfigurationFailed(
+                $"{refusal}; the capture thread runs at normal priority"));
+        }
+        Interlocked.Exchange(ref registration, joined)?.Dispose();
+    }
+
+    /// <summary>
+    /// Reverts a capture thread's MMCSS association as its source stops. NAudio raises
+    /// RecordingStopped on the capture thread itself as its loop exits. When it can't
+    /// revert there — the source was started under a synchronization context, or
+    /// teardown detached the handler first — the association lapses with the thread.
+    /// </summary>
+    private static void LeaveMmcss(ref Mmcss.Registration? registration) =>
+        Interlocked.Exchange(ref registration, null)?.Dispose();
+
+    /// <summary>
+    /// Creates <paramref name="directory"/> and proves it takes a write, so a
+    /// read-only or missing volume fails here rather than after the devices are
+    /// opened and the threads started.
+    /// </summary>
+    private static void ProbeOutputDirectory(string directory)
+    {
+        var probe = Path.Combine(directory, $".write-probe-{Guid.NewGuid():N}");
+        try
+        {
+            Directory.CreateDirectory(directory);
+            using (new FileStream(probe, FileMode.CreateNew, FileAccess.Write, FileShare.None, 1, FileOptions.DeleteOnClose))
+            {
+            }
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            throw CaptureException.StorageError(
+                $"Output directory {directory} is not writable: {ex.Message}");
+        }
+    }
+
+    private static void DeleteIfExists(string? path)
+    {
+        if (path != null && File.Exists(path))
+            File.Delete(path);
+    }
+
+    /// <summary>
+    /// Builds a source with <paramref name="create"/>, wires it up and starts it. A
+    /// transient open failure is retried up to
+    /// <see cref="CaptureConfiguration.DeviceOpenRetries"/> times on a freshly built
+    /// source — a client that failed to initialize isn't reused — with the backoff
+    /// doubling each time. The last failure propagates.
+    /// </summary>
+    private static async Task StartSourceAsync(
+        Func<IWaveIn> create,
+        EventHandler<WaveInEventArgs> onData,
+        EventHandler<StoppedEventArgs> onStopped,
+        CaptureConfiguration config)
+    {
+        var backoff = config.DeviceOpenBackoff;
+        for (var attempt = 0; ; attempt++)
+        {
+            // Opening a WASAPI client activates and initializes it: COM work for the MTA.
+            var source = ComThread.Run(create);
+            source.DataAvailable += onData;
+            source.RecordingStopped += onStopped;
+            try
+            {
+                ComThread.Run(source.StartRecording);
+                return;
+            }
+            catch (Exception ex) when (attempt < config.DeviceOpenRetries && AudioClientErrors.IsTransientOpenFailure(ex))
+            {
+                source.DataAvailable -= onData;
+                source.RecordingStopped -= onStopped;
+                source.Dispose();
+            }
+
+            await Task.Delay(backoff).ConfigureAwait(false);
+            backoff *= 2;
+        }
+    }
+
+    /// <summary>
+    /// Unwinds a start that failed after the writers were opened, leaving the
+    /// session Failed with <paramref name="error"/>.
+    /// </summary>
+    private void AbortStart(CaptureException error)
+    {
+        DisposeCapture();
+        DiscardWriter();
+        _wavWriter = null;
+        lock (_sidecarLock)
+        {
+            _sidecarsClosed = true;
+            _micPcmWriter?.Dispose();
+            _micPcmWriter = null;
+            _systemPcmWriter?.Dispose();
+            _systemPcmWriter = null;
+        }
+        lock (_lock) TransitionTo(CaptureState.Failed(error));
+    }
+
+    /// <summary>
+    /// Builds the real WASAPI mic source in the configured capture format — or, with
+    /// additional mics configured, a <see cref="CompositeMicWaveIn"/> over all of them,
+    /// the extras left at their own mix format for it to resample. With
+    /// <paramref name="resample"/>, the primary mic stays at its mix format too and
+    /// always goes through the composite, which does the conversion and the
+    /// configured <see cref="CaptureConfiguration.MicDownmix"/> — or at
+    /// <see cref="CaptureConfiguration.MicFormatOverride"/>, when one is set.
+    /// </summary>
+    private IWaveIn CreateWasapiMic(CaptureConfiguration config, bool resample)
+    {
+        var bufferMs = ResolveDeviceBufferMilliseconds(_micDevice!, config.DeviceBufferDuration);
+        var capture = new ConfiguredWasapiCapture(_micDevice!, bufferMs, loopback: false, config.MicStreamFlags);
+        if (!resample)
+            capture.WaveFormat = new WaveFormat((int)config.SampleRate, config.FloatOutput ? 16 : config.BitDepth, 1);
+        else if (config.MicFormatOverride is { } micFormat)
+            capture.WaveFormat = ResolveFormatOverride(_micDevice!, micFormat, "microphone");
+        if (_additionalMicDevices.Length == 0 && !resample)
+            return capture;
+
+        var sources = new List<IWaveIn> { capture };
+        foreach (var device in _additionalMicDevices)
+            sources.Add(new ConfiguredWasapiCapture(
+                device, ResolveDeviceBufferMilliseconds(device, config.DeviceBufferDuration),
+                loopback: false, config.MicStreamFlags));
+        return new CompositeMicWaveIn(
+            sources, (int)config.SampleRate, downmix: config.MicDownmix,
+            resamplerFactory: SourceResamplerFactory(config, config.MicAntiImaging));
+    }
+
+    /// <summary>
+    /// Builds the system source — injected, or loopback on the current default render
+    /// endpoint — and the normalizer for its format.
+    /// </summary>
+    private IWaveIn CreateSystemSource(CaptureConfiguration config)
+    {
+        if (_systemFactory == null && config.ExcludeOwnAudio && !SupportsProcessLoopback)
+            Delegate?.OnError(CaptureException.ConfigurationFailed(
+                "This Windows version can't leave the app's own audio out of loopback " +
+                "(it needs build 20348 or later); recording everything that plays instead"));
+
+        var capture = _systemFactory?.Invoke() ?? CreateWasapiLoopback(config);
+
+        // Built before the first callback can fire: the endpoint's format is
+        // only knowable now, and OnSystemDataAvailable relies on this being set.
+        // A format with no rate or channels gets none; the first buffer rejects it.
+        var format = capture.WaveFormat;
+        var normalizer = IsUsableFormat(format)
+            ? new SystemAudioNormalizer(
+                format.SampleRate, format.Channels, (int)config.SampleRate,
+                SourceResamplerFactory(config, config.SystemAntiImaging)?.Invoke())
+            : null;
+
+        // Under the lock: a device switch builds this on the COM thread while a stop
+        // may be taking the old source away on another.
+        lock (_lock)
+        {
+            _systemFormatValid = null;
+            _systemNormalizer = normalizer;
+            _systemCapture = capture;
+        }
+        return capture;
+    }
+
+    /// <summary>
+    /// The resampler factory for one source: the configured one, wrapped in an
+    /// <see cref="AntiImagingResampler"/> when that source asks for it.
+    /// </summary>
+    private static Func<IAudioResampler>? SourceResamplerFactory(CaptureConfiguration config, bool antiImaging) =>
+        antiImaging ? () => new AntiImagingResampler(config.ResamplerFactory?.Invoke()) : config.ResamplerFactory;
+
+    /// <summary>Process-exclude loopback arrived in Windows 10 build 20348 (Server 2022, Windows 11).</summary>
+    private static bool SupportsProcessLoopback => OperatingSystem.IsWindowsVersionAtLeast(10, 0, 20348);
+
+    /// <summary>
+    /// Builds the real loopback source on the default render endpoint for the
+    /// configured role, in <see cref="CaptureConfiguration.SystemFormatOverride"/> when
+    /// set; on every render endpoint with <see cref="CaptureConfiguration.CaptureAllOutputs"/>;
+    /// or, with <see cref="CaptureConfiguration.ExcludeOwnAudio"/> where Windows
+    /// supports it, process loopback of everything but this process tree.
+    /// </summary>
+    private static IWaveIn CreateWasapiLoopback(CaptureConfiguration config)
+    {
+        if (config.ExcludeOwnAudio && SupportsProcessLoopback)
+            return CreateOwnAudioExcludingLoopback(config);
+
+        using var enumerator = new MMDeviceEnumerator();
+        if (config.CaptureAllOutputs)
+            return CreateAllOutputsLoopback(enumerator, config);
+
+        var device = enumerator.GetDefaultAudioEndpoint(DataFlow.Render, DeviceEnumerator.ToRole(config.DeviceRole));
+        var bufferMs = ResolveDeviceBufferMilliseconds(device, config.DeviceBufferDuration);
+        var capture = new ConfiguredWasapiCapture(device, bufferMs, loopback: true, config.SystemStreamFlags);
+        if (config.SystemFormatOverride is { } systemFormat)
+            capture.WaveFormat = ResolveFormatOverride(device, systemFormat, "system output");
+        return capture;
+    }
+
+    /// <summary>
+    /// Loopback on every active render endpoint, mixed into one stereo stream at the
+    /// configured rate. A lone endpoint still goes through the composite, so the
+    /// stream's format doesn't depend on how many outputs happen to be plugged in.
+    /// </summary>
+    private static IWaveIn CreateAllOutputsLoopback(MMDeviceEnumerator enumerator, CaptureConfiguration config)
+    {
+        // Built one by one, so those already open can be released if a later one can't be.
+        var sources = new List<IWaveIn>();
+        try
+        {
+            foreach (var device in enumerator.EnumerateAudioEndPoints(DataFlow.Render, DeviceState.Active))
+            {
+                sources.Add(new ConfiguredWasapiCapture(
+                    device, ResolveDeviceBufferMilliseconds(device, config.DeviceBufferDuration),
+                    loopback: true, config.SystemStreamFlags));
+            }
+            if (sources.Count == 0)
+                throw CaptureException.DeviceNotAvailable("No active output device to capture");
+            return new CompositeLoopbackWaveIn(
+                sources, (int)config.SampleRate, SourceResamplerFactory(config, config.SystemAntiImaging));
+        }
+        catch
+        {
+            foreach (var source in sources)
+                source.Dispose();
+            throw;
+        }
+    }
+
+    /// <summary>
+    /// Process loopback excluding this process tree. It has no endpoint and so no mix
+    /// format to report: the client takes the format it is given, so ask for the
+    /// configured rate as stereo float — exactly what the normalizer passes through.
+    /// Activation completes on a COM worker thread; it runs off the caller's
+    /// synchronization context so a UI thread blocking here can't deadlock it.
+    /// </summary>
+    private static IWaveIn CreateOwnAudioExcludingLoopback(CaptureConfiguration config)
+    {
+        var capture = Task.Run(() => WasapiCapture.CreateForProcessCaptureAsync(
+            Environment.ProcessId, includeProcessTree: false)).GetAwaiter().GetResult();
+        capture.WaveFormat = WaveFormat.CreateIeeeFloatWaveFormat((int)config.SampleRate, 2);
         return capture;
     }
 
+    /// <summary>
+    /// Converts the configured buffer length to the milliseconds NAudio takes,
+    /// raised to the device's minimum period when it falls below it — WASAPI
+    /// rejects or silently rounds a buffer shorter than one period, and a clear
+    /// floor beats either. When the period can't be queried the request stands.
+    /// </summary>
+    private static int ResolveDeviceBufferMilliseconds(MMDevice device, TimeSpan requested)
+    {
+        var requestedMs = (int)Math.Ceiling(requested.TotalMilliseconds);
+        try
+        {
+            using var client = device.AudioClient;
+            // GetDevicePeriod reports in 100 ns units, the same REFERENCE_TIME
+            // Initialize takes — TimeSpan ticks happen to be that unit too.
+            var minimumMs = (int)Math.Ceiling(TimeSpan.FromTicks(client.MinimumDevicePeriod).TotalMilliseconds);
+            return Math.Max(requestedMs, minimumMs);
+        }
+        catch (Exception)
+        {
+            return requestedMs;
+        }
+    }
+
+    /// <summary>
+    /// Admits a capture callback unless stop has closed the gate, counting it in
+    /// flight until <see cref="ExitCallback"/>.
+    /// </summary>
+    private bool EnterCallback()
+    {
+        lock (_lock)
+        {
+            if (_callbacksClosed) return false;
+            _callbacksInFlight++;
+        }
+        t_callbackSession = this;
+        return true;
+    }
+
+    private void ExitCallback()
+    {
+        t_callbackSession = null;
+        lock (_lock)
+        {
+            if (--_callbacksInFlight == 0)
+                Monitor.PulseAll(_lock);
+        }
+    }
+
+    /// <summary>
+    /// Turns away every later capture callback and waits out those already running.
+    /// Once this returns, nothing touches the buffers, meters or sidecars from a
+    /// capture thread again this run — however late a source's thread winds down.
+    /// </summary>
+    private void CloseCallbacks()
+    {
+        lock (_lock)
+        {
+            _callbacksClosed = true;
+            var own = t_callbackSession == this ? 1 : 0;
+            while (_callbacksInFlight > own)
+                Monitor.Wait(_lock);
+        }
+    }
+
     private void OnMicDataAvailable(object? sender, WaveInEventArgs e)
     {
+        if (!EnterCallback()) return;
+        try { HandleMicData(e); }
+        catch (Exception ex) { ContainCallbackFault(ex, sender as IWaveIn, AudioTrackType.Mic, ref _micCallbackFaulted); }
+        finally { ExitCallback(); }
+    }
+
+    private void OnSystemDataAvailable(object? sender, WaveInEventArgs e)
+    {
+        if (!EnterCallback()) return;
+        try { HandleSystemData(e); }
+        catch (Exception ex) { ContainCallbackFault(ex, sender as IWaveIn, AudioTrackType.System, ref _systemCallbackFaulted); }
+        finally { ExitCallback(); }
+    }
+
+    /// <summary>
+    /// Handles an exception out of a capture callback — a throwing delegate, most
+    /// often — instead of letting it unwind the capture thread: NAudio would report
+    /// that as the device stopping, and an injected source's thread might not survive
+    /// it at all. Reported once per source per run, then dealt with per
+    /// <see cref="CaptureConfiguration.CallbackFaultPolicy"/>.
+    /// </summary>
+    private void ContainCallbackFault(Exception ex, IWaveIn? source, AudioTrackType track, ref bool reported)
+    {
+        var name = track == AudioTrackType.Mic ? "Mic" : "System audio";
+        bool first;
+        lock (_lock)
+        {
+            _callbackFaults++;
+            first = !reported;
+            reported = true;
+        }
+
+        var stopSource = _config?.CallbackFaultPolicy != CallbackFaultPolicy.Continue;
+        if (first)
+        {
+            var outcome = stopSource ? "source stopped" : "buffer dropped";
+            try
+            {
+                Delegate?.OnError(new CaptureException(
+                    CaptureErrorKind.Unknown, $"{name} capture callback threw ({outcome}): {ex.Message}", ex));
+            }
+            catch (Exception) { /* a delegate that throws again mustn't escape either */ }
+        }
+
+        // Off this thread: a source may wait for its own capture thread to finish,
+        // which is the one running this.
+        if (stopSource && source != null)
+        {
+            _ = Task.Run(() =>
+            {
+                try { source.StopRecording(); }
+                catch (Exception) { /* best effort; stop tears it down regardless */ }
+            });
+            if (first)
+                EndSource(track);
+        }
+    }
+
+    /// <summary>
+    /// Notes that an enabled source has ended for good mid-run, so a resume leaves it
+    /// be. The recording carries on until stopped, with whatever source is left.
+    /// </summary>
+    private void EndSource(AudioTrackType track)
+    {
+        lock (_lock)
+        {
+            if (track == AudioTrackType.Mic)
+                _micEnded = true;
+            else
+                _systemEnded = true;
+        }
+    }
+
+    private void HandleMicData(WaveInEventArgs e)
+    {
+        // NAudio owns the capture thread; its first callback is our first chance on it.
+        if (_pendingMicMmcssTask != null)
+            JoinMmcss(ref _pendingMicMmcssTask, ref _micMmcss);
+
         if (e.BytesRecorded == 0) return;
+        if (!CheckSourceFormat(ref _micFormatValid, _micCapture!, "Mic")) return;
 
         lock (_lock)
         {
@@ -474,49 +1877,60 @@ public sealed class WasapiCaptureSession : ICaptureSession
             if (_state.Kind == CaptureStateKind.Paused) return;
         }
 
+        if (_passthrough)
+        {
+            BufferPassthrough(e.Buffer, e.BytesRecorded);
+            WritePcmSidecar(_micPcmWriter, e.Buffer, e.BytesRecorded);
+            return;
+        }
+
         // Convert 16-bit PCM to float and buffer
         var samples = ConvertToFloat(e.Buffer, e.BytesRecorded);
+        MutePrimeFrames(samples, channels: 1, ref _micPrimeFrames);
+        _micGate?.Process(samples);
+        _micAgc?.Process(samples);
 
         // Update mic level
         var rms = ComputeRms(samples);
         _micRms = rms;
+        SmoothLevel(_micBallistics, rms, samples.Length);
         _peakMic = Math.Max(_peakMic, samples.Max(Math.Abs));
+        if (_truePeakMetering)
+            _truePeakMic = Math.Max(_truePeakMic, StereoMixer.TruePeakLevel(samples));
         UpdateLevels();
 
+        bool overBudget;
         lock (_lock)
         {
             _micBuffer.AddRange(samples);
-            TrackPeakBuffered();
+            _micFramesReceived += samples.Length;
+            overBudget = TrackPeakBuffered();
         }
+        if (overBudget) FailOverMemoryBudget();
 
         // Write raw PCM sidecar (encrypted if encryptor configured)
         WritePcmSidecar(_micPcmWriter, e.Buffer, e.BytesRecorded);
     }
 
-    private void OnSystemDataAvailable(object? sender, WaveInEventArgs e)
+    private void HandleSystemData(WaveInEventArgs e)
     {
+        if (_pendingSystemMmcssTask != null)
+            JoinMmcss(ref _pendingSystemMmcssTask, ref _systemMmcss);
+
         if (e.BytesRecorded == 0) return;
+        if (!CheckSourceFormat(ref _systemFormatValid, _systemCapture!, "System audio")) return;
 
         lock (_lock)
         {
             _systemChunks++;
             _systemBytes += e.BytesRecorded;
+            _lastSystemChunkTimestamp = _time.GetTimestamp();
             if (_state.Kind == CaptureStateKind.Paused) return;
         }
 
-        // System loopback is typically 32-bit float stereo — convert as needed
-        var format = _systemCapture!.WaveFormat;
-        float[] captured;
-
-        if (format.Encoding == WaveFormatEncoding.IeeeFloat && format.BitsPerSample == 32)
-        {
-            captured = new float[e.BytesRecorded / 4];
-            Buffer.BlockCopy(e.Buffer, 0, captured, 0, e.BytesRecorded);
-        }
-        else
-        {
-            captured = ConvertToFloat(e.Buffer, e.BytesRecorded);
-        }
+        var captured = ReadLoopbackSamples(e, _systemCapture!.WaveFormat);
+        var active = IsAudible(captured);
+        lock (_lock) TrackSystemActivity(active);
 
         // Reconcile the endpoint's mix format to the configured rate and stereo
         // layout before anything downstream sees it. Everything past this point —
@@ -524,6 +1938,7 @@ public sealed class WasapiCaptureSession : ICaptureSession
         // is stamped with the configured rate regardless of what the device chose.
         var samples = _systemNormalizer!.Normalize(captured);
         const int channels = SystemAudioNormalizer.TargetChannels;
+        MutePrimeFrames(samples, channels, ref _systemPrimeFrames);
 
         // For stereo system audio, compute RMS from mono-fold
         var frameCount = samples.Length / channels;
@@ -543,110 +1958,436 @@ public sealed class WasapiCaptureSession : ICaptureSession
             peak = Math.Max(peak, Math.Abs(val));
         }
         _systemRms = frameCount > 0 ? MathF.Sqrt(sum / frameCount) : 0;
+        SmoothLevel(_systemBallistics, _systemRms, frameCount);
         _peakSystem = Math.Max(_peakSystem, peak);
+        if (_truePeakMetering)
+            _truePeakSystem = Math.Max(_truePeakSystem, StereoMixer.TruePeakLevel(samples, channels));
         UpdateLevels();
 
+        bool overBudget;
         lock (_lock)
         {
             // If system audio is stereo, store as interleaved stereo
             _systemBuffer.AddRange(samples);
-            TrackPeakBuffered();
+            _systemFramesReceived += samples.Length / 2;
+            overBudget = TrackPeakBuffered();
+        }
+        if (overBudget) FailOverMemoryBudget();
+
+        // Write raw PCM sidecar as i16 LE (matching macOS format), encrypted if configured
+        if (_systemPcmWriter != null)
+        {
+            var pcmBytes = ConvertFloatToInt16Pcm(samples);
+            WritePcmSidecar(_systemPcmWriter, pcmBytes, pcmBytes.Length);
+        }
+    }
+
+    /// <summary>
+    /// Watchdog tick: an enabled source whose chunk count hasn't moved since the
+    /// last tick has stalled. Reports once per stall — a source that recovers
+    /// re-arms it — and stops the session when configured to.
+    /// </summary>
+    private void CheckForStall(object? _)
+    {
+        string stalled;
+        lock (_lock)
+        {
+            if (_state.Kind is not (CaptureStateKind.Capturing or CaptureStateKind.Paused)) return;
+            if (_devicesPaused) return;
+
+            var micStalled = _config!.EnableMicCapture && _micChunks == _stallMicChunks;
+            var systemStalled = _config.EnableSystemCapture && _systemChunks == _stallSystemChunks;
+            _stallMicChunks = _micChunks;
+            _stallSystemChunks = _systemChunks;
+
+            if (!micStalled && !systemStalled)
+            {
+                _stallReported = false;
+                return;
+            }
+            if (_stallReported) return;
+            _stallReported = true;
+
+            stalled = micStalled && systemStalled ? "Microphone and system audio"
+                : micStalled ? "Microphone" : "System audio";
+        }
+
+        Delegate?.OnError(CaptureException.TimeoutError(
+            $"{stalled} delivered no audio for {_config.StallTimeout!.Value.TotalSeconds:0.#}s"));
+
+        if (_config.StopOnStall)
+            StopFor(StopReason.Stalled);
+    }
+
+    /// <summary>
+    /// Drives mixing on one task, so a tick's mix and write always complete before
+    /// the next tick begins. Serializing here is what makes the writes safe: encrypt
+    /// plus disk write has no obligation to fit inside the 100ms interval, and two
+    /// ticks interleaving in the writer would desync the length-prefix framing and
+    /// cost every byte after the tear.
+    ///
+    /// A tick that overruns delays the next one instead of racing it; the sample
+    /// buffers hold the surplus and the following mix picks it up.
+    /// </summary>
+    private async Task RunMixPumpAsync(PeriodicTimer timer, CancellationToken ct)
+    {
+        try
+        {
+            while (await timer.WaitForNextTickAsync(ct).ConfigureAwait(false))
+                MixAndWrite();
+        }
+        catch (OperationCanceledException)
+        {
+            // Stop asked for the pump to end; the stopping thread does the final flush.
+        }
+    }
+
+    /// <summary>
+    /// Ends the mix loop and waits out any in-flight mix. This is a barrier: once it
+    /// returns, no other thread is inside the writer, which is what lets the caller
+    /// flush and close the file safely. Disposing the timer alone would not wait —
+    /// it would leave a tick running into a closed writer.
+    /// </summary>
+    private async Task StopMixPumpAsync()
+    {
+        _mixCts?.Cancel();
+        _mixTimer?.Dispose();
+
+        if (_mixPump != null)
+        {
+            try
+            {
+                await _mixPump.ConfigureAwait(false);
+            }
+            catch (OperationCanceledException)
+            {
+                // Expected on cancellation.
+            }
+        }
+
+        _mixPump = null;
+        _mixTimer = null;
+        _mixCts?.Dispose();
+        _mixCts = null;
+    }
+
+    private void MixAndWrite(bool flush = false)
+    {
+        lock (_mixLock) MixAndWriteCore(flush);
+    }
+
+    private void MixAndWriteCore(bool flush)
+    {
+        if (_passthrough)
+        {
+            WritePassthrough();
+            return;
+        }
+
+        float[] mic;
+        float[] system;
+
+        lock (_lock)
+        {
+            CloseSystemActivityOnGap();
+
+            var micFrames = _micBuffer.Count;
+            var systemFrames = _systemBuffer.Count / 2;
+
+            // Consume mic and system in lockstep, leaving the remainder for the next
+            // cycle. Draining both buffers to empty and padding the shorter to max()
+            // each cycle — as this did before — fabricates silence on whichever side
+            // lagged and walks the two streams out of alignment over a long session.
+            // Taking the common minimum and carrying the rest keeps them frame-locked.
+            // ResolveFramesToConsume bounds a genuinely stalled partner; flush drains
+            // whatever is left at stop, where a final ragged edge is unavoidable and
+            // harmless.
+            var frames = ResolveFramesToConsume(micFrames, systemFrames, flush);
+            if (frames == 0) return;
+
+            mic = TakeFront(_micBuffer, Math.Min(frames, micFrames));
+            system = TakeFront(_systemBuffer, Math.Min(frames, systemFrames) * 2);
+            _mixCycles++;
+
+            if (!flush && micFrames > 0 && systemFrames > 0)
+                CorrectDrift(frames);
+        }
+
+        if (_wavWriter == null || _config == null) return;
+
+        try
+        {
+            var mixedLength = _mixMatrix != null
+                ? _mixMatrix.MixInto(mic, system, ref _mixScratch)
+                : _mixer.MixInto(mic, system, _config.MixingStrategy, ref _mixScratch, _config.MicPlacement);
+            var mixed = _mixScratch.AsSpan(0, mixedLength);
+            if (_deliveryResampler != null)
+                mixed = _deliveryResampler.Process(
+                    mixed.ToArray(), _config.Channels, (int)_config.SampleRate, (int)_config.OutputSampleRate);
+            if (_silenceSkipper != null)
+            {
+                mixed = mixed[.._silenceSkipper.Process(mixed)];
+                if (mixed.IsEmpty) return;
+            }
+            if (_config.Channels == 2)
+                _stereoCorrelation = StereoMixer.StereoCorrelation(mixed);
+            var pcmLength = _config.FloatOutput
+                ? StereoMixer.ConvertToFloat32PcmInto(mixed, ref _pcmScratch)
+                : StereoMixer.ConvertToInt16PcmInto(mixed, ref _pcmScratch);
+            var sampleBytes = _config.FloatOutput ? 4 : 2;
+            var fitted = FitUnderFileSizeCap(pcmLength, _config.Channels * sampleBytes);
+            if (fitted < pcmLength)
+            {
+                pcmLength = fitted;
+                mixed = mixed[..(fitted / sampleBytes)];
+            }
+            if (pcmLength == 0 || !TryWrite(_pcmScratch.AsSpan(0, pcmLength))) return;
+            var signal = HasSignal(mixed, _config.FloatOutput);
+            var clippedSamples = StereoMixer.CountClipped(mixed);
+            bool overBudget;
+            lock (_lock)
+            {
+                _bytesWritten += pcmLength;
+                _wroteSignal |= signal;
+                _clippedSamples += clippedSamples;
+                _wroteClipped |= clippedSamples > 0;
+                overBudget = TrackPeakBuffered();
+            }
+            if (overBudget) FailOverMemoryBudget();
+            PublishToSink(mixed);
+        }
+        catch (Exception ex)
+        {
+            lock (_lock) _mixErrors++;
+            var error = CaptureException.EncodingFailed($"Mix/write failed: {ex.Message}");
+            Delegate?.OnError(error);
+        }
+    }
+
+    /// <summary>
+    /// Writes one mix cycle's PCM, or ends the run when that fails — see
+    /// <see cref="FailWrite"/>. False once the run has failed, so nothing is counted
+    /// or published for audio that never reached the file.
+    /// </summary>
+    private bool TryWrite(ReadOnlySpan<byte> pcm)
+    {
+        lock (_lock)
+        {
+            if (_writeFailure != null) return false;
+        }
+        try
+        {
+            _wavWriter!.Write(pcm);
+            return true;
         }
-
-        // Write raw PCM sidecar as i16 LE (matching macOS format), encrypted if configured
-        if (_systemPcmWriter != null)
+        catch (Exception ex)
         {
-            var pcmBytes = ConvertFloatToInt16Pcm(samples);
-            WritePcmSidecar(_systemPcmWriter, pcmBytes, pcmBytes.Length);
+            FailWrite(ex);
+            return false;
         }
     }
 
     /// <summary>
-    /// Drives mixing on one task, so a tick's mix and write always complete before
-    /// the next tick begins. Serializing here is what makes the writes safe: encrypt
-    /// plus disk write has no obligation to fit inside the 100ms interval, and two
-    /// ticks interleaving in the writer would desync the length-prefix framing and
-    /// cost every byte after the tear.
-    ///
-    /// A tick that overruns delays the next one instead of racing it; the sample
-    /// buffers hold the surplus and the following mix picks it up.
+    /// Reports a failed write and stops the session, which then ends Failed. A
+    /// failed encrypt or disk write loses that cycle's audio for good, and the next
+    /// would most likely fail the same way: carrying on would leave a file missing
+    /// audio while every indicator said it was recording.
     /// </summary>
-    private async Task RunMixPumpAsync(PeriodicTimer timer, CancellationToken ct)
+    private void FailWrite(Exception ex)
+    {
+        lock (_lock) _mixErrors++;
+        FailRun(ex as CaptureException ?? CaptureException.StorageError($"Write failed: {ex.Message}"));
+    }
+
+    /// <summary>
+    /// Reports <paramref name="error"/> and stops the session, which then ends
+    /// Failed with it. Only the first failure of a run counts.
+    /// </summary>
+    private void FailRun(CaptureException error)
+    {
+        bool stop;
+        lock (_lock)
+        {
+            if (_writeFailure != null) return;
+            _writeFailure = error;
+            stop = _state.Kind is CaptureStateKind.Capturing or CaptureStateKind.Paused;
+        }
+        Delegate?.OnError(error);
+
+        // Off the mix pump, which the stop has to drain.
+        if (stop)
+            _ = Task.Run(StopInBackgroundAsync);
+    }
+
+    private async Task StopInBackgroundAsync()
     {
         try
         {
-            while (await timer.WaitForNextTickAsync(ct).ConfigureAwait(false))
-                MixAndWrite();
+            await StopCaptureAsync().ConfigureAwait(false);
         }
-        catch (OperationCanceledException)
+        catch (CaptureException)
         {
-            // Stop asked for the pump to end; the stopping thread does the final flush.
+            // Expected: a failed write's stop ends Failed, or the caller stopped first.
         }
     }
 
     /// <summary>
-    /// Ends the mix loop and waits out any in-flight mix. This is a barrier: once it
-    /// returns, no other thread is inside the writer, which is what lets the caller
-    /// flush and close the file safely. Disposing the timer alone would not wait —
-    /// it would leave a tick running into a closed writer.
+    /// Stops the recording on the session's own account, noting
+    /// <paramref name="reason"/> for <see cref="RecordingResult.StopReason"/>. Only
+    /// the first reason counts, and none once a stop is already under way.
     /// </summary>
-    private async Task StopMixPumpAsync()
+    private void StopFor(StopReason reason)
     {
-        _mixCts?.Cancel();
-        _mixTimer?.Dispose();
+        lock (_lock)
+        {
+            if (_stopReason != StopReason.UserStopped
+                || _state.Kind is not (CaptureStateKind.Capturing or CaptureStateKind.Paused))
+                return;
+            _stopReason = reason;
+        }
+        // Off the caller — the mix pump among them, which the stop has to drain.
+        _ = Task.Run(StopInBackgroundAsync);
+    }
 
-        if (_mixPump != null)
+    /// <summary>
+    /// How much of a <paramref name="length"/>-byte write fits under
+    /// <see cref="CaptureConfiguration.MaxFileBytes"/>, and for a WAV under
+    /// <see cref="EncryptedWavWriter.MaxWavBytes"/>, in whole
+    /// <paramref name="frameBytes"/> frames; all of it with no cap. The write that
+    /// reaches the cap is cut short, nothing after it is written, and the session
+    /// stops with <see cref="StopReason.MaxSize"/> — reporting a
+    /// <see cref="CaptureErrorKind.StorageError"/> too when it was the WAV's limit.
+    /// </summary>
+    private int FitUnderFileSizeCap(int length, int frameBytes)
+    {
+        int fitted;
+        var wavLimitReached = false;
+        lock (_lock)
         {
-            try
-            {
-                await _mixPump.ConfigureAwait(false);
-            }
-            catch (OperationCanceledException)
+            var wavLimit = _wavWriter is EncryptedWavWriter ? EncryptedWavWriter.MaxWavBytes : long.MaxValue;
+            var cap = Math.Min(_config?.MaxFileBytes ?? long.MaxValue, wavLimit);
+            if (cap == long.MaxValue) return length;
+            if (_fileSizeCapReached) return 0;
+
+            fitted = length;
+            if (ProjectedFileBytes(_filePcmBytes + length, _fileWrites + 1) > cap)
             {
-                // Expected on cancellation.
+                // The room left after this write's own framing. Fixed-size sealing
+                // adds a frame per chunk the cut still covers, so back off until it fits.
+                fitted = (int)Math.Clamp(cap - ProjectedFileBytes(_filePcmBytes, _fileWrites + 1), 0, length)
+                    / frameBytes * frameBytes;
+                while (fitted > 0 && ProjectedFileBytes(_filePcmBytes + fitted, _fileWrites + 1) > cap)
+                    fitted -= frameBytes;
+                _fileSizeCapReached = true;
+                wavLimitReached = cap == wavLimit;
             }
+            _filePcmBytes += fitted;
+            if (fitted > 0)
+                _fileWrites++;
         }
+        if (wavLimitReached)
+            Delegate?.OnError(CaptureException.StorageError(
+                "The recording reached the 4 GB WAV size limit; stopping with what fits"));
+        if (fitted < length)
+            StopFor(StopReason.MaxSize);
+        return fitted;
+    }
 
-        _mixPump = null;
-        _mixTimer = null;
-        _mixCts?.Dispose();
-        _mixCts = null;
+    /// <summary>
+    /// The output's size with <paramref name="pcmBytes"/> of this run's audio
+    /// delivered in <paramref name="writes"/> writes: what it held when opened, plus
+    /// the audio, plus each sealed frame's length prefix and encryption overhead.
+    /// Must hold <see cref="_lock"/>.
+    /// </summary>
+    private long ProjectedFileBytes(long pcmBytes, long writes)
+    {
+        var config = _config!;
+        var sealing = config.Encryptor is { } encryptor ? 4L + encryptor.Overhead : 0;
+        var frames = config.SealedChunkBytes is { } chunkBytes ? (pcmBytes + chunkBytes - 1) / chunkBytes : writes;
+        return _fileBytesAtOpen + pcmBytes + frames * sealing;
     }
 
-    private void MixAndWrite(bool flush = false)
+    /// <summary>
+    /// Meters a 16-bit mic packet and queues its bytes for the file as they are —
+    /// the <see cref="SampleFormat.Int16"/> path's stand-in for decode and buffer.
+    /// </summary>
+    private void BufferPassthrough(byte[] buffer, int bytesRecorded)
     {
-        float[] mic;
-        float[] system;
+        var pcm = MemoryMarshal.Cast<byte, short>(buffer.AsSpan(0, bytesRecorded & ~1));
+        var muted = Math.Min(Math.Max(_micPrimeFrames, 0), pcm.Length);
+        _micPrimeFrames -= muted;
 
-        lock (_lock)
+        double sumSquares = 0;
+        var peak = 0;
+        foreach (var sample in pcm[muted..])
         {
-            var micFrames = _micBuffer.Count;
-            var systemFrames = _systemBuffer.Count / 2;
+            sumSquares += sample * sample;
+            peak = Math.Max(peak, Math.Abs((int)sample));
+        }
+        if (pcm.Length > 0)
+        {
+            _micRms = (float)(Math.Sqrt(sumSquares / pcm.Length) / short.MaxValue);
+            SmoothLevel(_micBallistics, _micRms, pcm.Length);
+            _peakMic = Math.Max(_peakMic, peak / (float)short.MaxValue);
+            UpdateLevels();
+        }
 
-            // Consume mic and system in lockstep, leaving the remainder for the next
-            // cycle. Draining both buffers to empty and padding the shorter to max()
-            // each cycle — as this did before — fabricates silence on whichever side
-            // lagged and walks the two streams out of alignment over a long session.
-            // Taking the common minimum and carrying the rest keeps them frame-locked.
-            // ResolveFramesToConsume bounds a genuinely stalled partner; flush drains
-            // whatever is left at stop, where a final ragged edge is unavoidable and
-            // harmless.
-            var frames = ResolveFramesToConsume(micFrames, systemFrames, flush);
-            if (frames == 0) return;
+        bool overBudget;
+        lock (_lock)
+        {
+            var needed = _micPassthroughLength + pcm.Length * 2;
+            if (_micPassthrough.Length < needed)
+                Array.Resize(ref _micPassthrough, Math.Max(needed, _micPassthrough.Length * 2));
+            var queued = _micPassthrough.AsSpan(_micPassthroughLength, pcm.Length * 2);
+            buffer.AsSpan(0, queued.Length).CopyTo(queued);
+            queued[..(muted * 2)].Clear();
+            _micPassthroughLength = needed;
+            _micFramesReceived += pcm.Length;
+            overBudget = TrackPeakBuffered();
+        }
+        if (overBudget) FailOverMemoryBudget();
+    }
 
-            mic = TakeFront(_micBuffer, Math.Min(frames, micFrames));
-            system = TakeFront(_systemBuffer, Math.Min(frames, systemFrames) * 2);
+    /// <summary>
+    /// Writes the queued mic bytes unchanged. Swaps the queue with the PCM scratch so
+    /// the capture thread can keep appending while the write runs. The live sink
+    /// still gets floats, converted only when one is attached.
+    /// </summary>
+    private void WritePassthrough()
+    {
+        byte[] pcm;
+        int length;
+        lock (_lock)
+        {
+            if (_micPassthroughLength == 0) return;
+            pcm = _micPassthrough;
+            length = _micPassthroughLength;
+            _micPassthrough = _pcmScratch;
+            _micPassthroughLength = 0;
+            _pcmScratch = pcm;
             _mixCycles++;
         }
 
-        if (_wavWriter == null || _config == null) return;
+        if (_wavWriter == null) return;
 
         try
         {
-            var mixed = _mixer.Mix(mic, system, _config.MixingStrategy);
-            var pcmData = StereoMixer.ConvertToInt16Pcm(mixed);
-            _wavWriter.Write(pcmData);
-            lock (_lock) _bytesWritten += pcmData.Length;
+            length = FitUnderFileSizeCap(length, frameBytes: 2);
+            if (length == 0 || !TryWrite(pcm.AsSpan(0, length))) return;
+            var signal = MemoryMarshal.Cast<byte, short>(pcm.AsSpan(0, length & ~1)).ContainsAnyExcept((short)0);
+            bool overBudget;
+            lock (_lock)
+            {
+                _bytesWritten += length;
+                _wroteSignal |= signal;
+                overBudget = TrackPeakBuffered();
+            }
+            if (overBudget) FailOverMemoryBudget();
+
+            bool hasSink;
+            lock (_lock) hasSink = _audioSink != null;
+            if (hasSink)
+                PublishToSink(ConvertToFloat(pcm, length));
         }
         catch (Exception ex)
         {
@@ -656,6 +2397,11 @@ public sealed class WasapiCaptureSession : ICaptureSession
         }
     }
 
+    // How long loopback must be silent before TimingSource.Auto hands the clock to
+    // the mic. Comfortably above a loopback period, so a late packet isn't mistaken
+    // for silence.
+    private static readonly TimeSpan SystemSilenceFallback = TimeSpan.FromMilliseconds(200);
+
     /// <summary>
     /// How many frames this mix cycle should consume from each buffer, keeping mic and
     /// system aligned: take the common minimum, carry the remainder, so the two stay
@@ -666,7 +2412,9 @@ public sealed class WasapiCaptureSession : ICaptureSession
     /// zero-fills a fully-empty system buffer. The extra symmetry is deliberate: these
     /// are growable List buffers, not macOS's drop-on-overflow ring buffers, so a
     /// partner that stalls has to be bounded here rather than by overflow — and it
-    /// covers a stalled mic, which macOS handles not at all. Must hold <see cref="_lock"/>.
+    /// covers a stalled mic, which macOS handles not at all. That is the
+    /// <see cref="TimingSource.Auto"/> behaviour; the other settings fix one source as
+    /// the clock, as macOS does. Must hold <see cref="_lock"/>.
     /// </summary>
     private int ResolveFramesToConsume(int micFrames, int systemFrames, bool flush)
     {
@@ -692,6 +2440,22 @@ public sealed class WasapiCaptureSession : ICaptureSession
         if (!systemEnabled)
             return micFrames;
 
+        // Loopback sends no packets at all while nothing plays. Waiting on it would
+        // hold the mic back until the stall guard fires, half a second behind; once
+        // it has been quiet a while, let the mic drive until it speaks again.
+        var timing = _config?.TimingSource ?? TimingSource.Auto;
+        if (timing == TimingSource.Auto && systemFrames == 0
+            && _time.GetElapsedTime(_lastSystemChunkTimestamp) >= SystemSilenceFallback)
+            timing = TimingSource.Mic;
+
+        // A fixed clock takes everything its source has and zero-fills the other
+        // behind it. The other's surplus is still bounded by stallGuard so it can't
+        // grow without limit if it runs fast.
+        if (timing == TimingSource.Mic)
+            return Math.Max(micFrames, systemFrames - stallGuard);
+        if (timing == TimingSource.System)
+            return Math.Max(systemFrames, micFrames - stallGuard);
+
         // Steady state: consume the aligned common minimum and carry the remainder.
         //
         // Guard: if one side has surged more than stallGuard ahead of the other, its
@@ -705,6 +2469,86 @@ public sealed class WasapiCaptureSession : ICaptureSession
         return Math.Max(aligned, Math.Max(surplus, 0));
     }
 
+    /// <summary>
+    /// Opens an activity interval on the first sounding packet and closes it on the
+    /// first silent one. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private void TrackSystemActivity(bool active)
+    {
+        var now = _durationClock.Elapsed.TotalSeconds;
+        if (active)
+        {
+            _systemActiveSince ??= now;
+            _systemLastActiveAt = now;
+        }
+        else if (_systemActiveSince is { } since)
+        {
+            _systemActivity.Add(new ActivityInterval(since, now));
+            _systemActiveSince = null;
+        }
+    }
+
+    /// <summary>
+    /// Closes an open activity interval when loopback has stopped sending packets
+    /// altogether — what it does once playback ends, rather than send silent ones.
+    /// The interval ends where sound was last heard. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private void CloseSystemActivityOnGap()
+    {
+        if (_systemActiveSince is not { } since
+            || _time.GetElapsedTime(_lastSystemChunkTimestamp) < SystemSilenceFallback)
+            return;
+
+        _systemActivity.Add(new ActivityInterval(since, _systemLastActiveAt));
+        _systemActiveSince = null;
+    }
+
+    /// <summary>
+    /// Keeps mic and system from drifting apart. Taking the common minimum each cycle
+    /// keeps them aligned only while both clocks agree; when one device runs slightly
+    /// fast its surplus carries over and grows, cycle after cycle, until the stall
+    /// guard drains it in one half-second jump. Instead, once the carried lead exceeds
+    /// what buffer jitter explains, trim a little from the front of the leading
+    /// buffer — at most 1 frame in 20 of this cycle's, so a correction is a few short
+    /// skips rather than one audible one.
+    ///
+    /// Auto timing only: a fixed clock source is never trimmed, and the other side
+    /// already zero-fills behind it. Called only when both sides delivered, so a
+    /// silent loopback isn't mistaken for drift. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private void CorrectDrift(int framesThisCycle)
+    {
+        if (_config is not { TimingSource: TimingSource.Auto } config) return;
+
+        // Each device delivers in buffer-sized bursts, so a lead of up to two buffers
+        // is just arrival order, not drift.
+        var tolerance = (int)(config.SampleRate * config.DeviceBufferDuration.TotalSeconds * 2);
+        var lead = _micBuffer.Count - (_systemBuffer.Count / 2);
+        if (Math.Abs(lead) <= tolerance) return;
+
+        var trim = Math.Min(Math.Abs(lead) - tolerance, Math.Max(1, framesThisCycle / 20));
+        if (lead > 0)
+            _micBuffer.RemoveRange(0, trim);
+        else
+            _systemBuffer.RemoveRange(0, trim * 2);
+        _driftCorrectedFrames += trim;
+    }
+
+    /// <summary>
+    /// Offers a mixed chunk to the live sink, if one is attached. Never blocks: a full
+    /// or completed channel drops the chunk and counts it. The sink gets its own copy;
+    /// the scratch it came from is overwritten next cycle.
+    /// </summary>
+    private void PublishToSink(ReadOnlySpan<float> mixed)
+    {
+        ChannelWriter<float[]>? sink;
+        lock (_lock) sink = _audioSink;
+        if (sink == null || mixed.Length == 0) return;
+
+        if (!sink.TryWrite(mixed.ToArray()))
+            lock (_lock) _sinkDroppedChunks++;
+    }
+
     /// <summary>
     /// Removes and returns the first <paramref name="count"/> items, leaving the rest
     /// in place for the next cycle. Must hold <see cref="_lock"/>.
@@ -727,61 +2571,294 @@ public sealed class WasapiCaptureSession : ICaptureSession
     /// mic is Left and system is Right — genuinely channel-separated.</item>
     /// <item>Blended: mic is summed into both channels (phantom Center) while system
     /// keeps its stereo image, so neither source owns a channel.</item>
+    /// <item>Mono, quad and 5.1 (see <see cref="MixMatrix.ForLayout"/>): mic is the
+    /// centre or rear feed and system keeps its front pair, so mic reads as Center
+    /// and system as Stereo whatever the strategy.</item>
+    /// <item>Four-channel Multichannel: discrete tracks, mic on channels 1–2 and
+    /// system on 3–4, labelled by channel since no speaker position names them.</item>
     /// </list>
     /// The tracks stay gated on which sources are enabled.
     /// </summary>
     private static (AudioTrack[] Tracks, ChannelLayout Layout) BuildTrackMetadata(CaptureConfiguration config)
     {
-        var separated = config.MixingStrategy is MixingStrategy.Separated or MixingStrategy.Multichannel;
+        var layout = config.Channels switch
+        {
+            1 => ChannelLayout.Mono,
+            4 when config.MixingStrategy == MixingStrategy.Multichannel => ChannelLayout.Multitrack,
+            4 => ChannelLayout.Quad,
+            6 => ChannelLayout.Surround51,
+            _ when config.MixingStrategy is MixingStrategy.Separated or MixingStrategy.Multichannel =>
+                ChannelLayout.SeparatedStereo,
+            _ => ChannelLayout.Blended,
+        };
+        var separated = layout == ChannelLayout.SeparatedStereo;
+        var multitrack = layout == ChannelLayout.Multitrack;
 
         var tracks = new List<AudioTrack>();
         if (config.EnableMicCapture)
             tracks.Add(separated
                 ? new AudioTrack(AudioTrackType.Mic, AudioChannel.Left, "Mic (Local)")
-                : new AudioTrack(AudioTrackType.Mic, AudioChannel.Center, null));
+                : new AudioTrack(AudioTrackType.Mic, MicChannel(config.MicPlacement), multitrack ? "Mic (Ch 1-2)" : null));
         if (config.EnableSystemCapture)
             tracks.Add(separated
                 ? new AudioTrack(AudioTrackType.System, AudioChannel.Right, "System (Remote, mono-fold)")
-                : new AudioTrack(AudioTrackType.System, AudioChannel.Stereo, null));
+                : new AudioTrack(AudioTrackType.System, AudioChannel.Stereo, multitrack ? "System (Ch 3-4)" : null));
 
-        var layout = separated ? ChannelLayout.SeparatedStereo : ChannelLayout.Blended;
         return ([.. tracks], layout);
     }
 
     /// <summary>
-    /// Records the high-water mark of samples awaiting a mix cycle. Must be called
-    /// with <see cref="_lock"/> held.
+    /// The channel a blended mix puts the mic in. Only stereo blends allow a placement
+    /// other than Center, so anywhere else this is Center.
+    /// </summary>
+    private static AudioChannel MicChannel(MicPlacement placement) => placement switch
+    {
+        MicPlacement.Left => AudioChannel.Left,
+        MicPlacement.Right => AudioChannel.Right,
+        _ => AudioChannel.Center,
+    };
+
+    /// <summary>
+    /// Updates the buffering high-water marks and says whether the run has just gone
+    /// over <see cref="CaptureConfiguration.MemoryBudgetBytes"/>. Counts the audio
+    /// waiting for a mix cycle and, when recording to memory, the recording itself.
+    /// Must hold <see cref="_lock"/>.
     /// </summary>
-    private void TrackPeakBuffered()
+    private bool TrackPeakBuffered()
     {
         var buffered = _micBuffer.Count + _systemBuffer.Count;
         if (buffered > _peakBufferedSamples)
             _peakBufferedSamples = buffered;
+
+        var inMemory = (long)buffered * sizeof(float) + _micPassthroughLength
+            + (_memoryTarget != null ? _bytesWritten : 0);
+        _peakMemoryBytes = Math.Max(_peakMemoryBytes, inMemory);
+        _segmentPeakBufferedSamples = Math.Max(_segmentPeakBufferedSamples, buffered);
+        _segmentPeakMemoryBytes = Math.Max(_segmentPeakMemoryBytes, inMemory);
+        return inMemory > _memoryBudget && _writeFailure == null;
     }
 
+    /// <summary>
+    /// Ends the run Failed once it holds more audio in memory than its budget —
+    /// storage too slow to keep up, or an in-memory recording run long — rather than
+    /// growing until the process runs out of memory.
+    /// </summary>
+    private void FailOverMemoryBudget() =>
+        FailRun(CaptureException.StorageError(
+            $"Audio held in memory passed the {_memoryBudget} byte budget: storage isn't keeping up, " +
+            "or the in-memory recording outgrew it"));
+
+    /// <summary>Feeds a callback's RMS, covering <paramref name="frames"/> frames, to a source's meter.</summary>
+    private void SmoothLevel(MeterBallistics? ballistics, float rms, int frames) =>
+        ballistics?.Update(rms, TimeSpan.FromSeconds(frames / _meterSampleRate));
+
     private void UpdateLevels()
     {
-        var levels = new AudioLevels(_micRms, _systemRms, _peakMic, _peakSystem);
+        var levels = new AudioLevels(_micRms, _systemRms, _peakMic, _peakSystem)
+        {
+            TruePeakMicLevel = _truePeakMic,
+            TruePeakSystemLevel = _truePeakSystem,
+            SmoothedMicLevel = _micBallistics?.Current ?? 0f,
+            SmoothedSystemLevel = _systemBallistics?.Current ?? 0f,
+            StereoCorrelation = _stereoCorrelation,
+        };
         lock (_lock) _currentLevels = levels;
         Delegate?.OnLevelsUpdated(levels);
     }
 
     private void TransitionTo(CaptureState newState)
     {
+        if (newState.Kind == CaptureStateKind.Configuring)
+            _stateLog.Clear();
+        if (_stateLog.Count == StateLogCapacity)
+            _stateLog.RemoveAt(0);
+        _stateLog.Add(new StateTransition(newState.Kind, _time.GetUtcNow()));
+
         _state = newState;
         Delegate?.OnStateChanged(newState);
     }
 
+    /// <summary>
+    /// Pauses the sources that support it, for <see cref="CaptureConfiguration.PauseDevices"/>.
+    /// Each capture thread leaves its MMCSS task as it exits; a failure is reported
+    /// and leaves that source running, its buffers dropped as for any other.
+    /// </summary>
+    private void PauseSources()
+    {
+        foreach (var (source, name) in new[] { (_micCapture, "Mic"), (_systemCapture, "System audio") })
+        {
+            if (source is not IPausableWaveIn pausable) continue;
+            try
+            {
+                ComThread.Run(pausable.Pause);
+            }
+            catch (Exception ex)
+            {
+                Delegate?.OnError(CaptureException.DeviceNotAvailable($"{name} could not pause: {ex.Message}"));
+            }
+        }
+    }
+
+    /// <summary>
+    /// Resumes what <see cref="PauseSources"/> paused. The new capture threads join
+    /// the MMCSS tasks again on their first buffer. A source that can't resume has
+    /// ended, as if its device were lost.
+    /// </summary>
+    private void ResumeSources()
+    {
+        var config = _config!;
+        bool micEnded, systemEnded;
+        lock (_lock) (micEnded, systemEnded) = (_micEnded, _systemEnded);
+        if (_micCapture is IPausableWaveIn mic && !micEnded)
+        {
+            _pendingMicMmcssTask = _micFactory == null ? config.MicMmcssTask : null;
+            ResumeSource(mic, AudioTrackType.Mic, "Mic");
+        }
+        if (_systemCapture is IPausableWaveIn system && !systemEnded)
+        {
+            _pendingSystemMmcssTask = _systemFactory == null ? config.SystemMmcssTask : null;
+            ResumeSource(system, AudioTrackType.System, "System audio");
+        }
+    }
+
+    private void ResumeSource(IPausableWaveIn source, AudioTrackType track, string name)
+    {
+        try
+        {
+            ComThread.Run(source.Resume);
+        }
+        catch (Exception ex)
+        {
+            Delegate?.OnError(CaptureException.DeviceNotAvailable($"{name} could not resume: {ex.Message}"));
+            EndSource(track);
+        }
+    }
+
     private void OnMicRecordingStopped(object? sender, StoppedEventArgs e)
     {
-        if (e.Exception != null)
-            Delegate?.OnError(CaptureException.DeviceNotAvailable($"Mic stopped: {e.Exception.Message}"));
+        LeaveMmcss(ref _micMmcss);
+        if (e.Exception == null) return;
+        Delegate?.OnError(CaptureException.DeviceNotAvailable($"Mic stopped: {e.Exception.Message}"));
+        EndSource(AudioTrackType.Mic);
     }
 
     private void OnSystemRecordingStopped(object? sender, StoppedEventArgs e)
     {
-        if (e.Exception != null)
-            Delegate?.OnError(CaptureException.DeviceNotAvailable($"System audio stopped: {e.Exception.Message}"));
+        LeaveMmcss(ref _systemMmcss);
+        if (e.Exception != null && AudioClientErrors.IsDeviceInvalidated(e.Exception)
+            && sender is IWaveIn stale && ReferenceEquals(stale, _systemCapture) && _config is { } config)
+        {
+            _ = SwitchSystemDeviceAsync(stale, config);
+            return;
+        }
+        if (e.Exception == null) return;
+        Delegate?.OnError(CaptureException.DeviceNotAvailable($"System audio stopped: {e.Exception.Message}"));
+        EndSource(AudioTrackType.System);
+    }
+
+    /// <summary>
+    /// Follows the default playback device after the endpoint loopback was capturing
+    /// from is invalidated — the user switched outputs, or unplugged a headset. The
+    /// replacement may run at another rate or channel count; it gets its own
+    /// normalizer, so the mix never sees the difference. Nothing arrives meanwhile,
+    /// and the mix fills the gap with silence just as it does whenever playback stops.
+    /// </summary>
+    private async Task SwitchSystemDeviceAsync(IWaveIn stale, CaptureConfiguration config)
+    {
+        stale.DataAvailable -= OnSystemDataAvailable;
+        stale.RecordingStopped -= OnSystemRecordingStopped;
+        stale.Dispose();
+
+        lock (_lock)
+        {
+            if (_state.Kind is not (CaptureStateKind.Capturing or CaptureStateKind.Paused))
+                return;
+        }
+
+        try
+        {
+            _pendingSystemMmcssTask = _systemFactory == null ? config.SystemMmcssTask : null;
+            await StartSourceAsync(
+                () => CreateSystemSource(config),
+                OnSystemDataAvailable, OnSystemRecordingStopped, config).ConfigureAwait(false);
+
+            // A stop that began meanwhile has already stopped and released the
+            // sources it saw, so the replacement is this call's to release.
+            IWaveIn? orphan = null;
+            lock (_lock)
+            {
+                if (_callbacksClosed)
+                {
+                    orphan = _systemCapture;
+                    _systemCapture = null;
+                }
+                else
+                {
+                    _systemDeviceSwitches++;
+                }
+            }
+            if (orphan != null)
+            {
+                orphan.DataAvailable -= OnSystemDataAvailable;
+                orphan.RecordingStopped -= OnSystemRecordingStopped;
+                orphan.StopRecording();
+                orphan.Dispose();
+            }
+        }
+        catch (Exception ex)
+        {
+            Delegate?.OnError(CaptureException.DeviceNotAvailable(
+                $"System audio stopped: no playback device to follow ({ex.Message})"));
+            EndSource(AudioTrackType.System);
+        }
+    }
+
+    /// <summary>
+    /// Deals with the files a failed run leaves: keeps them and writes a
+    /// <see cref="PartialRecording"/> marker beside the WAV, since nothing says the
+    /// header was patched or the last chunk whole; with
+    /// <see cref="CaptureConfiguration.DeletePartialOnFailure"/>, deletes them instead.
+    /// A resumed file holds earlier runs and is never deleted. Best effort: the run's
+    /// own failure is what the caller hears about.
+    /// </summary>
+    private void SettlePartialRecording(
+        CaptureConfiguration config, string filePath, CaptureException failure, long writtenBytes, TimeSpan duration)
+    {
+        if (filePath.Length == 0) return; // in memory or a sink: no file to keep or drop
+
+        try
+        {
+            if (config.DeletePartialOnFailure && config.ResumeFilePath == null)
+            {
+                DeleteIfExists(filePath);
+                DeleteIfExists(_micPcmPath);
+                DeleteIfExists(_systemPcmPath);
+                return;
+            }
+            var marker = new PartialRecording(
+                filePath, failure.ErrorKind, failure.Message, writtenBytes, duration.TotalSeconds, DateTime.UtcNow);
+            File.WriteAllText(PartialRecording.MarkerPath(filePath), JsonSerializer.Serialize(marker));
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            Delegate?.OnError(CaptureException.StorageError($"Settling the partial recording failed: {ex.Message}"));
+        }
+    }
+
+    /// <summary>
+    /// Lets go of the writer without finishing the recording normally. Our own is
+    /// disposed, which closes the file as far as it still can; a caller's sink is
+    /// closed best-effort and otherwise left to them.
+    /// </summary>
+    private void DiscardWriter()
+    {
+        if (_config?.OutputSink == null)
+        {
+            (_wavWriter as IDisposable)?.Dispose();
+            return;
+        }
+        try { _wavWriter?.Close(); }
+        catch (Exception) { /* already failing, or being torn down */ }
     }
 
     private void DisposeCapture()
@@ -793,16 +2870,93 @@ public sealed class WasapiCaptureSession : ICaptureSession
             _micCapture.Dispose();
             _micCapture = null;
         }
-        if (_systemCapture != null)
+        // Taken under the lock: a device switch may be installing a replacement.
+        IWaveIn? system;
+        lock (_lock)
         {
-            _systemCapture.DataAvailable -= OnSystemDataAvailable;
-            _systemCapture.RecordingStopped -= OnSystemRecordingStopped;
-            _systemCapture.Dispose();
+            system = _systemCapture;
             _systemCapture = null;
         }
+        if (system != null)
+        {
+            system.DataAvailable -= OnSystemDataAvailable;
+            system.RecordingStopped -= OnSystemRecordingStopped;
+            system.Dispose();
+        }
         _micDevice = null;
     }
 
+    /// <summary>
+    /// Checks a source's format on its first buffer. A device reporting no rate or
+    /// no channels can't be converted or mixed — left alone it surfaces as a divide
+    /// by zero or empty output somewhere downstream — so the source is reported as
+    /// <see cref="CaptureErrorKind.ConfigurationFailed"/>, by name, and stopped.
+    /// The recording carries on with whatever else is capturing.
+    /// </summary>
+    private bool CheckSourceFormat(ref bool? valid, IWaveIn source, string name)
+    {
+        if (valid is { } known) return known;
+
+        var format = source.WaveFormat;
+        valid = IsUsableFormat(format);
+        if (valid == true) return true;
+
+        Delegate?.OnError(CaptureException.ConfigurationFailed(
+            $"{name} source reported an unusable format ({format.SampleRate} Hz, {format.Channels} channels)"));
+        try { source.StopRecording(); }
+        catch (Exception) { /* stopping from its own callback is best effort */ }
+        return false;
+    }
+
+    private static bool IsUsableFormat(WaveFormat format) => format.SampleRate > 0 && format.Channels >= 1;
+
+    /// <summary>Decodes a loopback packet, typically 32-bit float already, to float samples.</summary>
+    private static float[] ReadLoopbackSamples(WaveInEventArgs e, WaveFormat format)
+    {
+        if (format.Encoding == WaveFormatEncoding.IeeeFloat && format.BitsPerSample == 32)
+        {
+            var samples = new float[e.BytesRecorded / 4];
+            Buffer.BlockCopy(e.Buffer, 0, samples, 0, e.BytesRecorded);
+            return samples;
+        }
+        return ConvertToFloat(e.Buffer, e.BytesRecorded);
+    }
+
+    /// <summary>
+    /// Zeroes the head of <paramref name="samples"/> while the source is still within
+    /// its <see cref="CaptureConfiguration.PrimeDuration"/>, counting down
+    /// <paramref name="framesLeft"/>.
+    /// </summary>
+    private static void MutePrimeFrames(Span<float> samples, int channels, ref int framesLeft)
+    {
+        if (framesLeft <= 0) return;
+        var frames = Math.Min(framesLeft, samples.Length / channels);
+        samples[..(frames * channels)].Clear();
+        framesLeft -= frames;
+    }
+
+    /// <summary>
+    /// Whether a loopback packet carries sound. WASAPI's SILENT flag reaches us as a
+    /// zero-filled packet — NAudio clears the buffer rather than copying — so an
+    /// all-zero packet is exactly that flag.
+    /// </summary>
+    private static bool IsAudible(float[] samples) => samples.AsSpan().ContainsAnyExcept(0f);
+
+    /// <summary>
+    /// Whether any of <paramref name="mixed"/> survives as more than zero in the file:
+    /// at least half a step in a 16-bit one, anything nonzero in a float one.
+    /// </summary>
+    private static bool HasSignal(ReadOnlySpan<float> mixed, bool floatOutput)
+    {
+        var threshold = floatOutput ? float.Epsilon : 0.5f / short.MaxValue;
+        foreach (var sample in mixed)
+        {
+            if (Math.Abs(sample) >= threshold)
+                return true;
+        }
+        return false;
+    }
+
     private static float[] ConvertToFloat(byte[] buffer, int bytesRecorded)
     {
         var sampleCount = bytesRecorded / 2;
@@ -0,0 +1,22 @@
+namespace AudioCapture.Interfaces;
+
+/// <summary>
+/// Converts one stream of audio between sample rates. Implement this to plug in
+/// your own DSP; the library's default is <see cref="Processing.WdlAudioResampler"/>.
+/// </summary>
+/// <remarks>
+/// An instance serves a single stream and may carry filter state from one call to
+/// the next, so it is never shared between sources or called concurrently.
+/// </remarks>
+public interface IAudioResampler
+{
+    /// <summary>
+    /// Resamples the next chunk of the stream. Output length need only track
+    /// <c>input.Length * outputRate / inputRate</c> on average, not per call.
+    /// </summary>
+    /// <param name="input">Interleaved float samples.</param>
+    /// <param name="channels">Channels interleaved in <paramref name="input"/>; the output keeps the same layout.</param>
+    /// <param name="inputRate">Sample rate of <paramref name="input"/>.</param>
+    /// <param name="outputRate">Sample rate to return.</param>
+    float[] Process(float[] input, int channels, int inputRate, int outputRate);
+}
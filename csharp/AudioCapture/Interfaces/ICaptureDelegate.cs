@@ -11,4 +11,12 @@ public interface ICaptureDelegate
     void OnLevelsUpdated(AudioLevels levels);
     void OnError(CaptureException error);
     void OnCaptureFinished(RecordingResult result);
+
+    /// <summary>
+    /// The mic the configuration asked for — <paramref name="requested"/>, its ID or
+    /// name — couldn't be opened, and <paramref name="actual"/>, the default mic, is
+    /// recording in its place. Only with <see cref="CaptureConfiguration.FallBackToDefaultMic"/>.
+    /// Does nothing by default.
+    /// </summary>
+    void OnDeviceFallback(string? requested, AudioSource actual) { }
 }
@@ -10,6 +10,15 @@ public interface ICaptureEncryptor
     /// </summary>
     byte[] Encrypt(byte[] data);
 
+    /// <summary>
+    /// As <see cref="Encrypt(byte[])"/>, also authenticating
+    /// <paramref name="associatedData"/>, which is not stored: decryption must supply
+    /// the same bytes. Needed for <see cref="Models.CaptureConfiguration.SequenceBoundChunks"/>;
+    /// not supported unless implemented.
+    /// </summary>
+    byte[] Encrypt(byte[] data, byte[] associatedData) =>
+        throw new NotSupportedException($"{GetType().Name} cannot authenticate associated data");
+
     /// <summary>
     /// Metadata about the encryption key (key ID, creation date, etc.).
     /// </summary>
@@ -19,4 +28,10 @@ public interface ICaptureEncryptor
     /// Algorithm name (e.g. "AES-256-GCM").
     /// </summary>
     string Algorithm { get; }
+
+    /// <summary>
+    /// Bytes <see cref="Encrypt"/> adds to each plaintext. Only used to estimate file
+    /// sizes; the default is AES-GCM's 12-byte nonce plus 16-byte tag.
+    /// </summary>
+    int Overhead => 28;
 }
@@ -0,0 +1,40 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Interfaces;
+
+/// <summary>
+/// Where a recording goes. <see cref="Storage.EncryptedWavWriter"/> is the default,
+/// writing a WAV file; implement this to send the audio elsewhere as it is
+/// captured — an HTTP upload, say — with no file on disk.
+/// </summary>
+/// <remarks>
+/// The session opens the sink at start, writes once per mix cycle from a single
+/// thread, and closes it at stop. Writes carry the mixed audio as little-endian PCM
+/// in the configured format, with no header; with an
+/// <see cref="CaptureConfiguration.Encryptor"/> set, each write is instead one sealed
+/// frame, <c>[4-byte LE length][nonce + ciphertext + tag]</c>, exactly as in the
+/// data chunk of an <c>.enc.wav</c>. A sink that throws ends the recording Failed.
+/// </remarks>
+public interface IOutputSink
+{
+    /// <summary>Starts a recording in <paramref name="config"/>'s format.</summary>
+    void Open(CaptureConfiguration config);
+
+    /// <summary>Delivers the next bytes of the recording.</summary>
+    void Write(ReadOnlySpan<byte> data);
+
+    /// <summary>
+    /// Makes everything written so far durable without ending the recording; called
+    /// at pause. Does nothing by default.
+    /// </summary>
+    void Flush() { }
+
+    /// <summary>
+    /// Ends the recording and returns a checksum of what was delivered, reported as
+    /// <see cref="RecordingResult.Checksum"/>; empty when the sink keeps none.
+    /// </summary>
+    string Close();
+
+    /// <summary>The format of what was delivered, reported as <see cref="RecordingResult.Spec"/>; null by default.</summary>
+    WavSpec? Spec => null;
+}
@@ -0,0 +1,26 @@
+using AudioCapture.Models;
+using NAudio.Wave;
+
+namespace AudioCapture.Interfaces;
+
+/// <summary>
+/// A source that can stop delivering audio without ending its recording, for
+/// <see cref="CaptureConfiguration.PauseDevices"/>. Sources that don't implement it
+/// keep running while paused, their buffers dropped by the session as usual.
+/// </summary>
+/// <remarks>
+/// The session calls <see cref="Pause"/> and <see cref="Resume"/> only between
+/// <see cref="IWaveIn.StartRecording"/> and <see cref="IWaveIn.StopRecording"/>, and
+/// may stop a paused source without resuming it. A source may raise
+/// <see cref="IWaveIn.RecordingStopped"/> without an exception as it pauses; one with
+/// an exception ends the source as it would while capturing. Stopping a paused
+/// source raises it too, unless the source already did as it paused.
+/// </remarks>
+public interface IPausableWaveIn : IWaveIn
+{
+    /// <summary>Stops delivering buffers, keeping the stream open to resume.</summary>
+    void Pause();
+
+    /// <summary>Delivers buffers again after <see cref="Pause"/>.</summary>
+    void Resume();
+}
@@ -0,0 +1,11 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// A stretch of the recording's timeline, in seconds from its start, during which
+/// a source carried sound.
+/// </summary>
+public sealed record ActivityInterval(
+    [property: JsonPropertyName("start_secs")] double StartSecs,
+    [property: JsonPropertyName("end_secs")] double EndSecs);
@@ -0,0 +1,29 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Parameters for the mic's automatic gain control. See
+/// <see cref="Processing.AutomaticGainControl"/>.
+/// </summary>
+public sealed record AgcConfiguration
+{
+    /// <summary>Long-term RMS level the gain steers toward. 0.1 is about -20 dBFS.</summary>
+    [JsonPropertyName("target_rms")]
+    public float TargetRms { get; init; } = 0.1f;
+
+    /// <summary>How quickly gain comes down when the speaker gets louder.</summary>
+    [JsonPropertyName("attack")]
+    public TimeSpan Attack { get; init; } = TimeSpan.FromMilliseconds(50);
+
+    /// <summary>
+    /// How quickly gain comes back up when the speaker gets quieter. Kept much slower
+    /// than <see cref="Attack"/> so pauses between sentences don't pump the noise floor.
+    /// </summary>
+    [JsonPropertyName("release")]
+    public TimeSpan Release { get; init; } = TimeSpan.FromSeconds(2);
+
+    /// <summary>Ceiling on the applied gain, so silence isn't amplified into audible hiss.</summary>
+    [JsonPropertyName("max_gain")]
+    public float MaxGain { get; init; } = 8f;
+}
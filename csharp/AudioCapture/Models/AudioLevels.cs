@@ -10,4 +10,30 @@ public sealed record AudioLevels(
     float PeakSystemLevel)
 {
     public static readonly AudioLevels Zero = new(0f, 0f, 0f, 0f);
+
+    /// <summary>
+    /// Running 4x-oversampled true peak of the mic, which can exceed 1.0 where a
+    /// sample peak reads full scale. 0 unless
+    /// <see cref="CaptureConfiguration.TruePeakMetering"/> is on.
+    /// </summary>
+    public float TruePeakMicLevel { get; init; }
+
+    /// <summary>Running true peak of system audio; see <see cref="TruePeakMicLevel"/>.</summary>
+    public float TruePeakSystemLevel { get; init; }
+
+    /// <summary>
+    /// <see cref="MicLevel"/> with meter ballistics applied — steady enough to drive
+    /// a VU-style display. 0 unless <see cref="CaptureConfiguration.LevelBallistics"/> is set.
+    /// </summary>
+    public float SmoothedMicLevel { get; init; }
+
+    /// <summary>Smoothed system level; see <see cref="SmoothedMicLevel"/>.</summary>
+    public float SmoothedSystemLevel { get; init; }
+
+    /// <summary>
+    /// Left/right correlation of the last mixed chunk, in [-1, 1]: +1 is mono, near
+    /// 0 a wide or separated image, negative an out-of-phase pair that cancels when
+    /// summed. 0 for silence and for recordings that aren't stereo.
+    /// </summary>
+    public float StereoCorrelation { get; init; }
 }
@@ -0,0 +1,32 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// <see cref="AudioLevels"/> stamped with when they were read, ready to serialize
+/// and send to a frontend — which can then line meters up on the recording's
+/// timeline instead of drawing each reading as it happens to arrive.
+/// </summary>
+/// <param name="MicLevel">See <see cref="AudioLevels.MicLevel"/>.</param>
+/// <param name="SystemLevel">See <see cref="AudioLevels.SystemLevel"/>.</param>
+/// <param name="PeakMicLevel">See <see cref="AudioLevels.PeakMicLevel"/>.</param>
+/// <param name="PeakSystemLevel">See <see cref="AudioLevels.PeakSystemLevel"/>.</param>
+/// <param name="ElapsedSecs">Recorded time when the levels were read, pauses excluded.</param>
+/// <param name="CapturedAt">Wall-clock time of the reading, UTC.</param>
+public sealed record AudioLevelsSnapshot(
+    [property: JsonPropertyName("mic_level")] float MicLevel,
+    [property: JsonPropertyName("system_level")] float SystemLevel,
+    [property: JsonPropertyName("peak_mic_level")] float PeakMicLevel,
+    [property: JsonPropertyName("peak_system_level")] float PeakSystemLevel,
+    [property: JsonPropertyName("elapsed_secs")] double ElapsedSecs,
+    [property: JsonPropertyName("captured_at")] DateTimeOffset CapturedAt)
+{
+    /// <summary>Stamps <paramref name="levels"/> with a point on the recording's timeline.</summary>
+    public static AudioLevelsSnapshot From(AudioLevels levels, TimeSpan elapsed, DateTimeOffset capturedAt)
+    {
+        ArgumentNullException.ThrowIfNull(levels);
+        return new AudioLevelsSnapshot(
+            levels.MicLevel, levels.SystemLevel, levels.PeakMicLevel, levels.PeakSystemLevel,
+            elapsed.TotalSeconds, capturedAt);
+    }
+}
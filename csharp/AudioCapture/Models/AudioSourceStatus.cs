@@ -0,0 +1,11 @@
+namespace AudioCapture.Models;
+
+/// <summary>
+/// The availability of one kind of capture source. <see cref="Source"/> is the
+/// device that would be used, or null when <see cref="Status"/> is
+/// <see cref="SourceStatus.NoDevice"/>.
+/// </summary>
+public sealed record AudioSourceStatus(
+    AudioTrackType SourceType,
+    AudioSource? Source,
+    SourceStatus Status);
@@ -0,0 +1,23 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Contents of the Broadcast Wave (EBU Tech 3285) <c>bext</c> chunk. The origination
+/// date, time and time reference aren't set here: they're stamped from the moment
+/// the recording opens. Text fields are ASCII and cut to their fixed widths.
+/// </summary>
+public sealed record BwfConfiguration
+{
+    /// <summary>Free-text description of the recording, up to 256 characters.</summary>
+    [JsonPropertyName("description")]
+    public string Description { get; init; } = "";
+
+    /// <summary>Name of the originating organisation or application, up to 32 characters.</summary>
+    [JsonPropertyName("originator")]
+    public string Originator { get; init; } = "AudioCaptureKit";
+
+    /// <summary>Originator's own reference for the recording, up to 32 characters.</summary>
+    [JsonPropertyName("originator_reference")]
+    public string OriginatorReference { get; init; } = "";
+}
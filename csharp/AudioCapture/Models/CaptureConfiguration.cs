@@ -1,21 +1,626 @@
+using System.Text.Json;
+using System.Text.Json.Serialization;
 using AudioCapture.Interfaces;
 
 namespace AudioCapture.Models;
 
 /// <summary>
 /// Configuration for an audio capture session. Mirrors Swift CaptureConfiguration.
+/// Saves to and loads from JSON with <see cref="ToJson"/> and <see cref="FromJson"/>,
+/// for keeping recording presets.
 /// </summary>
 public sealed record CaptureConfiguration
 {
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        WriteIndented = true,
+        Converters = { new JsonStringEnumConverter(JsonNamingPolicy.SnakeCaseLower) },
+    };
+
+    /// <summary>
+    /// How often the session's mix loop drains the sources into the file — and so,
+    /// without <see cref="EncryptionChunkSize"/>, how often a frame is sealed.
+    /// </summary>
+    internal static readonly TimeSpan MixInterval = TimeSpan.FromMilliseconds(100);
+
+    /// <summary>
+    /// Rates every endpoint handles, natively or through the audio engine. Others are
+    /// rejected unless <see cref="AllowNonStandardSampleRate"/> is set.
+    /// </summary>
+    public static IReadOnlyList<int> StandardSampleRates { get; } = [8000, 16000, 22050, 24000, 32000, 44100, 48000, 88200, 96000, 192000];
+
+    /// <summary>The <see cref="StandardSampleRates"/> entry closest to <paramref name="sampleRate"/>; the higher of two equally close.</summary>
+    public static int NearestStandardSampleRate(double sampleRate) =>
+        StandardSampleRates.OrderBy(rate => Math.Abs(rate - sampleRate)).ThenByDescending(rate => rate).First();
+
+    /// <summary>
+    /// Must be one of <see cref="StandardSampleRates"/>, unless
+    /// <see cref="AllowNonStandardSampleRate"/> is set.
+    /// </summary>
+    [JsonPropertyName("sample_rate")]
     public double SampleRate { get; init; } = 48000;
+    [JsonPropertyName("bit_depth")]
     public int BitDepth { get; init; } = 16;
+    [JsonPropertyName("channels")]
     public int Channels { get; init; } = 2;
+
+    /// <summary>
+    /// Rate of the delivered recording, when it should differ from
+    /// <see cref="SampleRate"/> — a 16 kHz file for a speech API, say, while capture
+    /// and mixing run at full rate. The mix is resampled once, just before it is
+    /// written, and the WAV header declares this rate. Raw PCM sidecars stay at
+    /// <see cref="SampleRate"/>. Null, the default, delivers at the capture rate.
+    /// </summary>
+    [JsonPropertyName("delivery_sample_rate")]
+    public int? DeliverySampleRate { get; init; }
+
+    /// <summary>
+    /// Advanced: accept a <see cref="SampleRate"/> or <see cref="DeliverySampleRate"/>
+    /// outside <see cref="StandardSampleRates"/> — anything from 8000 to 384000 Hz,
+    /// fractions included. Few devices run at such a rate, so capture mostly goes
+    /// through a resampler, and a device that can't be opened at it fails only once
+    /// WASAPI is asked. Off by default, which catches typos like 4800 at
+    /// <see cref="Capture.WasapiCaptureSession.Configure"/>.
+    /// </summary>
+    [JsonPropertyName("allow_non_standard_sample_rate")]
+    public bool AllowNonStandardSampleRate { get; init; }
+
+    /// <summary>The rate the recording is written at: <see cref="DeliverySampleRate"/>, else <see cref="SampleRate"/>.</summary>
+    internal double OutputSampleRate => DeliverySampleRate ?? SampleRate;
+
+    /// <summary>Seals the audio as it is written. Never saved by <see cref="ToJson"/>; supply it to <see cref="FromJson"/>.</summary>
+    [JsonIgnore]
     public ICaptureEncryptor? Encryptor { get; init; }
+
+    /// <summary>
+    /// Makes the resampler for each source whose rate differs from
+    /// <see cref="SampleRate"/> — a loopback endpoint, or a mic converted here rather
+    /// than by the audio engine. Called once per source, since a resampler carries
+    /// state. Null uses <see cref="Processing.WdlAudioResampler"/>. Not saved by
+    /// <see cref="ToJson"/>.
+    /// </summary>
+    [JsonIgnore]
+    public Func<IAudioResampler>? ResamplerFactory { get; init; }
+
+    /// <summary>
+    /// Low-pass the mic after it is upsampled here, just under its own Nyquist, to
+    /// suppress the images a cheap <see cref="ResamplerFactory"/> leaves above it —
+    /// a 16 kHz hands-free mic in a 48 kHz mix, say. See
+    /// <see cref="Processing.AntiImagingResampler"/>. The default sinc resampler
+    /// already rejects them, and a mic the audio engine converts never reaches it.
+    /// Off by default.
+    /// </summary>
+    [JsonPropertyName("mic_anti_imaging")]
+    public bool MicAntiImaging { get; init; }
+
+    /// <summary>As <see cref="MicAntiImaging"/>, for a loopback endpoint below the capture rate.</summary>
+    [JsonPropertyName("system_anti_imaging")]
+    public bool SystemAntiImaging { get; init; }
+    [JsonPropertyName("output_directory")]
     public string OutputDirectory { get; init; } = "";
+    [JsonPropertyName("max_duration")]
     public TimeSpan? MaxDuration { get; init; }
+
+    /// <summary>
+    /// Largest the recording may grow, in bytes, header and encryption framing
+    /// included — to stay under an upload limit, say. The write that would pass it is
+    /// cut to what fits, and the session stops with
+    /// <see cref="StopReason.MaxSize"/>. Unlike rotation, nothing carries on
+    /// into another file. A resumed file counts from its existing size. Null, the
+    /// default, sets no cap.
+    /// </summary>
+    [JsonPropertyName("max_file_bytes")]
+    public long? MaxFileBytes { get; init; }
+
+    /// <summary>
+    /// Extend a new recording file to its estimated final size when it is opened —
+    /// the size at <see cref="MaxDuration"/>, within <see cref="MaxFileBytes"/>, one of
+    /// which must be set — and cut it back to exactly the header and data at close.
+    /// Worth it only for long recordings on spinning disks, where a file grown a
+    /// buffer at a time ends up fragmented and seeks mid-recording; on an SSD it only
+    /// costs the allocation. Until close the file ends in zeros, so a copy taken at a
+    /// pause, or a file left by a crash, carries them past the data. A resumed file
+    /// isn't preallocated, but resuming with this and its caps unchanged is how the
+    /// crashed file's zero tail is recognised and cut. Ignored for an <see cref="OutputSink"/> and
+    /// <see cref="CaptureToMemory"/>. Off by default.
+    /// </summary>
+    [JsonPropertyName("preallocate_file")]
+    public bool PreallocateFile { get; init; }
+
+    /// <summary>
+    /// How long an enabled source may go without delivering a single buffer before
+    /// the session reports <see cref="CaptureErrorKind.Timeout"/> — a hung driver
+    /// otherwise leaves it Capturing forever, writing nothing. Null (the default)
+    /// disables the watchdog. A stall is noticed within one to two timeouts.
+    /// </summary>
+    [JsonPropertyName("stall_timeout")]
+    public TimeSpan? StallTimeout { get; init; }
+
+    /// <summary>
+    /// When the <see cref="StallTimeout"/> watchdog fires, also stop and finalize the
+    /// recording, so an unattended deployment ends up with a file it can act on.
+    /// </summary>
+    [JsonPropertyName("stop_on_stall")]
+    public bool StopOnStall { get; init; }
+
+    /// <summary>
+    /// Most audio, in bytes, the session may hold in memory: what waits for a mix
+    /// cycle while storage lags, plus the recording itself with
+    /// <see cref="CaptureToMemory"/>. Buffers never drop audio to stay small, so
+    /// past this the session stops and ends Failed with
+    /// <see cref="CaptureErrorKind.StorageError"/> instead of running the process out
+    /// of memory. Null (the default) sets no cap.
+    /// </summary>
+    [JsonPropertyName("memory_budget_bytes")]
+    public long? MemoryBudgetBytes { get; init; }
+
+    /// <summary>
+    /// Recordings with less audio than this are flagged
+    /// <see cref="RecordingResult.IsTooShort"/>. One with no audio at all always is.
+    /// </summary>
+    [JsonPropertyName("minimum_recording_duration")]
+    public TimeSpan MinimumRecordingDuration { get; init; } = TimeSpan.Zero;
+
+    /// <summary>
+    /// Delete the WAV and its sidecars when the recording is too short, instead of
+    /// leaving a header-only file for downstream tools to trip over.
+    /// </summary>
+    [JsonPropertyName("delete_too_short_recordings")]
+    public bool DeleteTooShortRecordings { get; init; }
+
+    /// <summary>
+    /// Also report a recording that came out entirely silent through
+    /// <see cref="ICaptureDelegate.OnError"/> at stop, not just as
+    /// <see cref="RecordingResult.IsSilent"/>.
+    /// </summary>
+    [JsonPropertyName("report_silent_recordings")]
+    public bool ReportSilentRecordings { get; init; }
+
+    /// <summary>
+    /// Leave long silences out of the file — for meeting notes and monitoring, where
+    /// hours of nothing cost space and transcription. Each one left out is listed in
+    /// <see cref="RecordingMetadata.SkippedGaps"/>. Null, the default, writes everything.
+    /// </summary>
+    [JsonPropertyName("skip_silence")]
+    public SilenceSkipConfiguration? SkipSilence { get; init; }
+
+    /// <summary>
+    /// When a recording ends Failed, delete the partial WAV and its sidecars instead
+    /// of leaving them with a <see cref="PartialRecording"/> marker beside the WAV.
+    /// Off by default: what a failed run captured is often all there is of it. The
+    /// stop still throws the failure either way. A resumed file is never deleted,
+    /// since it holds earlier runs.
+    /// </summary>
+    [JsonPropertyName("delete_partial_on_failure")]
+    public bool DeletePartialOnFailure { get; init; }
+
+    /// <summary>
+    /// What happens when a capture callback throws, a delegate's included: the
+    /// exception is caught and reported once per source through <c>OnError</c>
+    /// either way, rather than unwinding the capture thread. Stops that source by
+    /// default.
+    /// </summary>
+    [JsonPropertyName("callback_fault_policy")]
+    public CallbackFaultPolicy CallbackFaultPolicy { get; init; } = CallbackFaultPolicy.StopSource;
+
+    /// <summary>
+    /// Keep a timestamped log of the session's state transitions — when it was
+    /// configured, started, paused, resumed and stopped — in
+    /// <see cref="RecordingMetadata.StateLog"/>, as an audit trail for support.
+    /// Off by default.
+    /// </summary>
+    [JsonPropertyName("record_state_log")]
+    public bool RecordStateLog { get; init; }
+
+    [JsonPropertyName("mic_device_id")]
     public string? MicDeviceId { get; init; }
+
+    /// <summary>
+    /// Selects the mic by friendly name, or part of one, instead of by
+    /// <see cref="MicDeviceId"/> — which can change across reboots while the name
+    /// stays put. Resolved at <c>Configure</c>, which fails when nothing matches or
+    /// the match is ambiguous; see <see cref="Capture.DeviceEnumerator.SelectByName"/>.
+    /// </summary>
+    [JsonPropertyName("mic_device_name")]
+    public string? MicDeviceName { get; init; }
+
+    /// <summary>
+    /// Which default endpoints to capture when no mic is chosen and for loopback:
+    /// those for <see cref="Models.DeviceRole.Communications"/> are what calling apps
+    /// use, and may not be the multimedia defaults. Multimedia by default.
+    /// </summary>
+    [JsonPropertyName("device_role")]
+    public DeviceRole DeviceRole { get; init; } = DeviceRole.Multimedia;
+
+    /// <summary>
+    /// When the mic chosen by <see cref="MicDeviceId"/> or <see cref="MicDeviceName"/>
+    /// is missing or inactive at configure time, or has gone by the start, record from
+    /// the default mic for <see cref="DeviceRole"/> instead, and say so through
+    /// <see cref="ICaptureDelegate.OnDeviceFallback"/>. Off by default: a missing mic
+    /// fails <c>Configure</c>, or the start.
+    /// </summary>
+    [JsonPropertyName("fall_back_to_default_mic")]
+    public bool FallBackToDefaultMic { get; init; }
+
+    /// <summary>
+    /// Further capture endpoints summed into the mic track alongside
+    /// <see cref="MicDeviceId"/> — e.g. one USB mic per speaker. Each may run at its
+    /// own rate; see <see cref="Capture.CompositeMicWaveIn"/>.
+    /// </summary>
+    [JsonPropertyName("additional_mic_device_ids")]
+    public IReadOnlyList<string> AdditionalMicDeviceIds { get; init; } = [];
+
+    /// <summary>
+    /// How a multichannel mic is folded to mono. Anything but the default Average
+    /// opens the mic at its own mix format and folds it here, since the audio engine
+    /// only knows how to average.
+    /// </summary>
+    [JsonPropertyName("mic_downmix")]
+    public MicDownmix MicDownmix { get; init; } = MicDownmix.Average;
+
+    /// <summary>
+    /// Automatic gain control on the mic, applied before mixing and metering. Null,
+    /// the default, leaves the mic level untouched. Raw PCM sidecars stay unprocessed.
+    /// </summary>
+    [JsonPropertyName("mic_agc")]
+    public AgcConfiguration? MicAgc { get; init; }
+
+    /// <summary>
+    /// Noise gate on the mic, run before <see cref="MicAgc"/> so the AGC never lifts
+    /// a gated pause back up. Null, the default, disables it.
+    /// </summary>
+    [JsonPropertyName("mic_gate")]
+    public GateConfiguration? MicGate { get; init; }
+    [JsonPropertyName("enable_mic_capture")]
     public bool EnableMicCapture { get; init; } = true;
+    [JsonPropertyName("enable_system_capture")]
     public bool EnableSystemCapture { get; init; } = true;
+    [JsonPropertyName("mixing_strategy")]
     public MixingStrategy MixingStrategy { get; init; } = MixingStrategy.Blended;
+
+    /// <summary>
+    /// Where a blended stereo mix puts the mono mic: both channels, the default, or
+    /// hard left or right for splitting the voice out later. System audio stays on
+    /// both channels. Other strategies and channel counts take only Center.
+    /// </summary>
+    [JsonPropertyName("mic_placement")]
+    public MicPlacement MicPlacement { get; init; } = MicPlacement.Center;
+
+    /// <summary>
+    /// Which source paces the mix when both are enabled. The default keeps them
+    /// aligned and hands the clock to the mic while nothing is playing.
+    /// </summary>
+    [JsonPropertyName("timing_source")]
+    public TimingSource TimingSource { get; init; } = TimingSource.Auto;
+
+    /// <summary>
+    /// <see cref="SampleFormat.Int16"/> writes a 16-bit mic straight to a 16-bit mono
+    /// file, skipping the float conversion and mix — for simple voice recordings
+    /// where the float path only costs cycles. Configure rejects it for anything
+    /// that needs the mixer. Float32 is the default.
+    /// </summary>
+    [JsonPropertyName("processing_format")]
+    public SampleFormat ProcessingFormat { get; init; } = SampleFormat.Float32;
+
+    /// <summary>
+    /// Writes the mix as 32-bit IEEE float (WAV format code 3) instead of integer PCM:
+    /// no conversion and no clamp, so overs survive for later gain staging. Requires
+    /// a <see cref="BitDepth"/> of 32. Off by default.
+    /// </summary>
+    [JsonPropertyName("float_output")]
+    public bool FloatOutput { get; init; }
+
+    /// <summary>
+    /// Opening stretch of each source to mute, for devices whose first callbacks
+    /// carry a ramp-up pop or garbage that would spike the meters and the file's
+    /// first samples. Muted, not dropped, so both sources keep their timeline. Raw
+    /// PCM sidecars stay unprocessed. Zero, the default, mutes nothing.
+    /// </summary>
+    [JsonPropertyName("prime_duration")]
+    public TimeSpan PrimeDuration { get; init; }
+
+    /// <summary>
+    /// Also meter 4x-oversampled true peak, reported in
+    /// <see cref="AudioLevels.TruePeakMicLevel"/> and
+    /// <see cref="AudioLevels.TruePeakSystemLevel"/>. Off by default: it costs
+    /// dozens of multiplies per sample on every callback, where the sample peak is
+    /// one comparison.
+    /// </summary>
+    [JsonPropertyName("true_peak_metering")]
+    public bool TruePeakMetering { get; init; }
+
+    /// <summary>
+    /// Attack and release for <see cref="AudioLevels.SmoothedMicLevel"/> and
+    /// <see cref="AudioLevels.SmoothedSystemLevel"/>, so every UI gets the same
+    /// steady meter instead of smoothing the raw per-callback RMS itself. The raw
+    /// levels are reported either way. Null, the default, leaves the smoothed levels at 0.
+    /// </summary>
+    [JsonPropertyName("level_ballistics")]
+    public MeterBallisticsConfiguration? LevelBallistics { get; init; }
+    [JsonPropertyName("export_raw_pcm")]
     public bool ExportRawPcm { get; init; }
+
+    /// <summary>
+    /// Length of the WASAPI buffer requested for both the mic and loopback clients.
+    /// Larger tolerates scheduling hiccups on loaded machines; smaller lowers latency.
+    /// A request below the device's minimum period is raised to that minimum.
+    /// </summary>
+    [JsonPropertyName("device_buffer_duration")]
+    public TimeSpan DeviceBufferDuration { get; init; } = TimeSpan.FromMilliseconds(100);
+
+    /// <summary>
+    /// How many more times to try opening a source whose first open failed because
+    /// the device was busy or invalidated — usually a device switch, or a Bluetooth
+    /// headset still settling after it connects. 0, the default, fails at once.
+    /// </summary>
+    [JsonPropertyName("device_open_retries")]
+    public int DeviceOpenRetries { get; init; }
+
+    /// <summary>Wait before the first retry; it doubles for each one after.</summary>
+    [JsonPropertyName("device_open_backoff")]
+    public TimeSpan DeviceOpenBackoff { get; init; } = TimeSpan.FromMilliseconds(50);
+
+    /// <summary>
+    /// Stream flags for the mic's WASAPI client, replacing the defaults — for virtual
+    /// drivers that misbehave with one of them. Null keeps the defaults, which
+    /// include <see cref="WasapiStreamFlags.AutoConvertPcm"/>; leave that out and the
+    /// device must already deliver the configured rate as 16-bit mono.
+    /// </summary>
+    [JsonPropertyName("mic_stream_flags")]
+    public WasapiStreamFlags? MicStreamFlags { get; init; }
+
+    /// <summary>Stream flags for the loopback client, as <see cref="MicStreamFlags"/>.</summary>
+    [JsonPropertyName("system_stream_flags")]
+    public WasapiStreamFlags? SystemStreamFlags { get; init; }
+
+    /// <summary>
+    /// Advanced: the format to open the mic's WASAPI client in, instead of the
+    /// endpoint's mix format — for drivers whose reported mix format is wrong or
+    /// glitches. The endpoint must accept it in shared mode (see
+    /// <see cref="Capture.DeviceEnumerator.GetSupportedFormats"/>); a refusal fails
+    /// <see cref="Capture.WasapiCaptureSession.Configure"/>. What arrives is
+    /// converted to the configured format here. Must be 16-bit or 32-bit float.
+    /// Ignored for injected sources.
+    /// </summary>
+    [JsonPropertyName("mic_format_override")]
+    public WavSpec? MicFormatOverride { get; init; }
+
+    /// <summary>
+    /// Advanced: as <see cref="MicFormatOverride"/>, for the loopback client. The
+    /// default render endpoint is only known at start, so a refusal fails the start.
+    /// Ignored by <see cref="ExcludeOwnAudio"/>'s process loopback.
+    /// </summary>
+    [JsonPropertyName("system_format_override")]
+    public WavSpec? SystemFormatOverride { get; init; }
+
+    /// <summary>
+    /// Record system audio from every active output device at once — speakers and a
+    /// headset, say, or a display's HDMI audio — rather than just the default one. Each
+    /// output is opened as its own loopback client and resampled to the capture rate
+    /// before they are summed (see <see cref="Capture.CompositeLoopbackWaveIn"/>), so
+    /// CPU cost grows with the number of outputs. An idle output contributes nothing.
+    /// Outputs chained together — a virtual cable or mirroring tool replaying one
+    /// device on another — are captured twice, doubled and possibly echoed; leave this
+    /// off on such setups. Ignored by <see cref="ExcludeOwnAudio"/>'s process loopback,
+    /// which already covers every output, and by <see cref="SystemFormatOverride"/>,
+    /// which applies to one endpoint only. Off by default.
+    /// </summary>
+    [JsonPropertyName("capture_all_outputs")]
+    public bool CaptureAllOutputs { get; init; }
+
+    /// <summary>
+    /// Leave this app's own sounds — UI chimes, playback of an earlier recording —
+    /// out of system audio, using process loopback that excludes its process tree.
+    /// That captures what every other process plays rather than one endpoint's
+    /// output, and ignores <see cref="SystemStreamFlags"/>. Needs Windows 10 build
+    /// 20348 or later; on older versions loopback records everything as usual and
+    /// the start reports as much through <see cref="ICaptureDelegate.OnError"/>.
+    /// </summary>
+    [JsonPropertyName("exclude_own_audio")]
+    public bool ExcludeOwnAudio { get; init; }
+
+    /// <summary>
+    /// MMCSS task the WASAPI mic thread joins on its first buffer ("Pro Audio",
+    /// "Audio", "Capture", ...). Null, the default, leaves the thread at normal
+    /// priority. Registration is best effort: a refusal is counted in
+    /// <see cref="CaptureDiagnostics.MmcssRegistrationFailures"/>, reported through
+    /// <see cref="ICaptureDelegate.OnError"/> with the Windows error, and capture
+    /// carries on. The thread leaves the task when
+    /// its source stops. Ignored for injected sources.
+    /// </summary>
+    [JsonPropertyName("mic_mmcss_task")]
+    public string? MicMmcssTask { get; init; }
+
+    /// <summary>As <see cref="MicMmcssTask"/>, for the loopback thread.</summary>
+    [JsonPropertyName("system_mmcss_task")]
+    public string? SystemMmcssTask { get; init; }
+
+    /// <summary>
+    /// Stop the WASAPI clients while paused, rather than only dropping what they
+    /// deliver. The streams stay initialized, so resuming is just a restart of each
+    /// client, but no capture thread runs and no buffers arrive meanwhile. The mic's
+    /// recording indicator goes out while paused. Applies to sources implementing
+    /// <see cref="Interfaces.IPausableWaveIn"/>; <see cref="CaptureAllOutputs"/> and
+    /// <see cref="ExcludeOwnAudio"/>'s process loopback keep running. Off by default.
+    /// </summary>
+    [JsonPropertyName("pause_devices")]
+    public bool PauseDevices { get; init; }
+
+    /// <summary>
+    /// What <see cref="RecordingResult.Checksum"/> covers. The default hashes the whole
+    /// file by re-reading it at stop; <see cref="Models.ChecksumMode.Streaming"/> skips the re-read.
+    /// </summary>
+    [JsonPropertyName("checksum_mode")]
+    public ChecksumMode ChecksumMode { get; init; } = ChecksumMode.File;
+
+    /// <summary>
+    /// Plaintext bytes per sealed frame when encrypting, e.g. 64 KB. Each frame costs
+    /// 32 bytes of framing — length prefix, nonce and tag — so sealing every 100 ms
+    /// mix cycle on its own bloats the file; batching into larger frames doesn't.
+    /// Rounded down to whole sample frames. The trade: up to one chunk of audio sits
+    /// in memory unsealed, and is lost if the process dies before close. Null, the
+    /// default, seals each mix cycle as its own frame.
+    /// </summary>
+    [JsonPropertyName("encryption_chunk_size")]
+    public int? EncryptionChunkSize { get; init; }
+
+    /// <summary>
+    /// Bytes of the recording gathered in memory before they go to the file, e.g.
+    /// 256 KB, so a short mix cycle doesn't cost a system call per write — or two per
+    /// sealed frame. Frames are buffered whole, length prefix and all, and reach the
+    /// file unchanged. The buffer is written out when full, at a pause and at close;
+    /// with <see cref="LiveHeader"/> after every write too, as patching the header
+    /// flushes it. Up to this much audio is lost if the process dies. File targets
+    /// only: a stream target or <see cref="OutputSink"/> buffers as it chooses. Null,
+    /// the default, keeps the file's 4 KB buffer.
+    /// </summary>
+    [JsonPropertyName("write_combine_bytes")]
+    public int? WriteCombineBytes { get; init; }
+
+    /// <summary>
+    /// <see cref="EncryptionChunkSize"/> rounded down to whole sample frames, so every
+    /// sealed box decodes to complete samples on its own. Null when unset.
+    /// </summary>
+    internal int? SealedChunkBytes
+    {
+        get
+        {
+            if (EncryptionChunkSize is not { } requested) return null;
+            var blockAlign = Channels * BitDepth / 8;
+            return Math.Max(blockAlign, requested / blockAlign * blockAlign);
+        }
+    }
+
+    /// <summary>
+    /// Writes a Broadcast Wave <c>bext</c> chunk between <c>fmt </c> and <c>data</c>,
+    /// stamped with the recording's start, for post-production tools that line
+    /// files up by timecode. Null, the default, writes a plain WAV.
+    /// </summary>
+    [JsonPropertyName("bwf")]
+    public BwfConfiguration? Bwf { get; init; }
+
+    /// <summary>
+    /// The caller's own key/value pairs — a meeting ID, participants, a session
+    /// token — copied into <see cref="RecordingMetadata.Tags"/>, so recordings can be
+    /// matched to the app's records without a store of its own. The session doesn't
+    /// read them. Empty by default.
+    /// </summary>
+    [JsonPropertyName("tags")]
+    public IReadOnlyDictionary<string, string> Tags { get; init; } = new Dictionary<string, string>();
+
+    /// <summary>
+    /// Re-patch the WAV header's RIFF and data sizes after every write rather than
+    /// only at pause and stop, so a player or monitor reading the file mid-recording
+    /// sees a growing, playable file instead of an empty one. Each write then costs
+    /// two extra seeks and a flush. Has no effect with an <see cref="OutputSink"/>,
+    /// which gets no header. Off by default.
+    /// </summary>
+    [JsonPropertyName("live_header")]
+    public bool LiveHeader { get; init; }
+
+    /// <summary>
+    /// An earlier recording to continue instead of starting a new file — after a
+    /// crash, or a stop the user didn't mean. Its format must match this
+    /// configuration, and it is reopened with <see cref="Encryptor"/>, so that must
+    /// be how it was written. Sidecars, when exported, are appended to as well. The
+    /// result's duration counts only the resumed run.
+    /// </summary>
+    [JsonPropertyName("resume_file_path")]
+    public string? ResumeFilePath { get; init; }
+
+    /// <summary>
+    /// Write the recording to memory instead of a file, returned whole in
+    /// <see cref="RecordingResult.AudioData"/> — for short clips such as a voice
+    /// command, where a disk round trip is wasted. The bytes are the same WAV the
+    /// file would hold, sealed when <see cref="Encryptor"/> is set. Cannot be
+    /// combined with <see cref="ResumeFilePath"/> or <see cref="ExportRawPcm"/>.
+    /// </summary>
+    [JsonPropertyName("capture_to_memory")]
+    public bool CaptureToMemory { get; init; }
+
+    /// <summary>
+    /// Bind each sealed frame to its position, by authenticating its 0-based index
+    /// as associated data, and report a <see cref="ChunkManifest"/> in the metadata —
+    /// so frames dropped or reordered within the file fail to decrypt with
+    /// <see cref="Storage.EncryptedWavReader"/>. Only the index is bound: a frame from
+    /// another recording sealed under the same key at the same index still decrypts,
+    /// so use a key per recording, or check the manifest, to catch that. Needs an
+    /// <see cref="Encryptor"/> that supports associated data. Such files can't be read as plain sealed frames, and
+    /// cannot be resumed or sent to an <see cref="OutputSink"/>.
+    /// </summary>
+    [JsonPropertyName("sequence_bound_chunks")]
+    public bool SequenceBoundChunks { get; init; }
+
+    /// <summary>
+    /// Send the recording to this sink instead of a file — to stream it to storage
+    /// as it is captured. <see cref="Encryptor"/>, when set, seals each write before
+    /// the sink sees it. Cannot be combined with <see cref="CaptureToMemory"/>,
+    /// <see cref="ResumeFilePath"/>, <see cref="ExportRawPcm"/> or
+    /// <see cref="EncryptionChunkSize"/>. Not saved by <see cref="ToJson"/>.
+    /// </summary>
+    [JsonIgnore]
+    public IOutputSink? OutputSink { get; init; }
+
+    /// <summary>
+    /// Recording both sides of a call: the mic and loopback, each on the default
+    /// <see cref="Models.DeviceRole.Communications"/> device — the headset a calling
+    /// app talks and listens through, which the multimedia defaults often aren't.
+    /// Everything else is as default; adjust with <c>with</c>.
+    /// </summary>
+    public static CaptureConfiguration ForCall(string outputDirectory) => new()
+    {
+        OutputDirectory = outputDirectory,
+        EnableMicCapture = true,
+        EnableSystemCapture = true,
+        DeviceRole = DeviceRole.Communications,
+    };
+
+    /// <summary>
+    /// Everything in this configuration that is data, as JSON. The
+    /// <see cref="Encryptor"/>, <see cref="ResamplerFactory"/> and
+    /// <see cref="OutputSink"/> are objects rather than settings — a key above all
+    /// doesn't belong in a preset file — so they are left out. Keys and enum values
+    /// are snake_case, as in the recording metadata.
+    /// </summary>
+    public string ToJson() => JsonSerializer.Serialize(this, JsonOptions);
+
+    /// <summary>
+    /// Restores a configuration saved by <see cref="ToJson"/>, with
+    /// <paramref name="encryptor"/> in place of the one that wasn't saved. Settings
+    /// missing from <paramref name="json"/> — saved by an older version — keep their
+    /// defaults.
+    /// </summary>
+    /// <exception cref="CaptureException">ConfigurationFailed when <paramref name="json"/> isn't a saved configuration.</exception>
+    public static CaptureConfiguration FromJson(string json, ICaptureEncryptor? encryptor = null)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+        try
+        {
+            var configuration = JsonSerializer.Deserialize<CaptureConfiguration>(json, JsonOptions)
+                ?? throw CaptureException.ConfigurationFailed("Saved configuration is empty");
+            return configuration with { Encryptor = encryptor };
+        }
+        catch (JsonException ex)
+        {
+            throw CaptureException.ConfigurationFailed($"Saved configuration is unreadable: {ex.Message}");
+        }
+    }
+
+    /// <summary>
+    /// Bytes of file each second of recording adds: the PCM data rate plus, when
+    /// encrypting, each sealed frame's 4-byte length prefix and
+    /// <see cref="ICaptureEncryptor.Overhead"/> — one frame per mix cycle, or per
+    /// <see cref="EncryptionChunkSize"/> when set. The one-off header isn't included;
+    /// <see cref="Capture.WasapiCaptureSession.EstimatedFinalSize"/> adds it.
+    /// </summary>
+    public double EstimatedBytesPerSecond()
+    {
+        var pcm = OutputSampleRate * Channels * BitDepth / 8.0;
+        if (Encryptor == null) return pcm;
+
+        var framesPerSecond = SealedChunkBytes is { } chunkBytes
+            ? pcm / chunkBytes
+            : 1 / MixInterval.TotalSeconds;
+        return pcm + framesPerSecond * (4 + Encryptor.Overhead);
+    }
 }
@@ -32,12 +32,60 @@ public sealed record CaptureDiagnostics
     /// <summary>Mix/write cycles that threw. Non-zero means audio was lost.</summary>
     public long MixErrors { get; init; }
 
+    /// <summary>
+    /// Mixed chunks the live audio sink refused because its consumer fell behind.
+    /// Only the sink loses them — the recording still has every chunk.
+    /// </summary>
+    public long SinkDroppedChunks { get; init; }
+
+    /// <summary>
+    /// Mixed samples beyond full scale — clamped in a 16-bit file, kept as they are in
+    /// a float one. Any at all means the recording clipped; see
+    /// <see cref="RecordingResult.DidClip"/>.
+    /// </summary>
+    public long ClippedSamples { get; init; }
+
+    /// <summary>
+    /// Capture threads that asked to join an MMCSS task and were refused. They ran
+    /// at normal priority instead — fine on an idle machine, glitch-prone on a busy one.
+    /// </summary>
+    public long MmcssRegistrationFailures { get; init; }
+
+    /// <summary>
+    /// Capture callbacks that threw and were contained — see
+    /// <see cref="CaptureConfiguration.CallbackFaultPolicy"/>. Each lost the buffer
+    /// it was handling.
+    /// </summary>
+    public long CallbackFaults { get; init; }
+
     /// <summary>
     /// High-water mark of samples buffered awaiting a mix cycle. Climbing without
     /// bound points at a stalled mix timer.
     /// </summary>
     public int PeakBufferedSamples { get; init; }
 
+    /// <summary>
+    /// High-water mark of audio held in memory, in bytes: samples awaiting a mix
+    /// cycle plus, when recording to memory, the recording itself. Nothing is ever
+    /// dropped to bound it — see <see cref="CaptureConfiguration.MemoryBudgetBytes"/>
+    /// for the cap, and this for sizing one.
+    /// </summary>
+    public long PeakMemoryBytes { get; init; }
+
+    /// <summary>
+    /// Mic frames received minus system frames received. Positive means the mic's
+    /// clock runs fast relative to the loopback device's. Two devices never share a
+    /// clock exactly, so this creeps over a long call; a steady slope is drift, a
+    /// sudden jump is one source pausing.
+    /// </summary>
+    public long SourceDriftFrames { get; init; }
+
+    /// <summary>
+    /// Frames trimmed from the leading source to pull the two back into line once
+    /// drift outgrew the jitter tolerance. See <see cref="TimingSource.Auto"/>.
+    /// </summary>
+    public long DriftCorrectedFrames { get; init; }
+
     /// <summary>
     /// The system-audio endpoint's own mix-format rate, or 0 when system capture
     /// was off. Unlike the mic, this rate is the device's choice, not ours — and a
@@ -49,9 +97,23 @@ public sealed record CaptureDiagnostics
     /// <summary>The system-audio endpoint's own channel count, or 0 when system capture was off.</summary>
     public int SystemSourceChannels { get; init; }
 
+    /// <summary>
+    /// Times loopback followed the default playback device to a new endpoint after
+    /// the old one was invalidated mid-recording. The source rate and channels above
+    /// describe the endpoint in use now, which may differ from the one the recording
+    /// started on.
+    /// </summary>
+    public int SystemDeviceSwitches { get; init; }
+
     /// <summary>
     /// Whether the system-audio endpoint's format had to be reconciled to the
     /// configured shape. False means it already matched (or system capture was off).
     /// </summary>
     public bool SystemNormalized { get; init; }
+
+    /// <summary>
+    /// How the mic reached the configured format: converted by the audio engine, or
+    /// — when the device refused that format — resampled here.
+    /// </summary>
+    public MicConversion MicConversion { get; init; }
 }
@@ -13,6 +13,7 @@ public enum CaptureErrorKind
     StorageError,
     Timeout,
     Unknown,
+    DeviceInUse,
 }
 
 /// <summary>
@@ -34,6 +35,14 @@ public class CaptureException : Exception
     public static CaptureException DeviceNotAvailable(string message = "Audio device not available") =>
         new(CaptureErrorKind.DeviceNotAvailable, message);
 
+    /// <summary>
+    /// The device exists and is permitted, but another application holds it in
+    /// exclusive mode. Worth telling apart: the fix is closing that app, not
+    /// reconfiguring this one.
+    /// </summary>
+    public static CaptureException DeviceInUse(string message = "Audio device is in use by another application") =>
+        new(CaptureErrorKind.DeviceInUse, message);
+
     public static CaptureException ConfigurationFailed(string message) =>
         new(CaptureErrorKind.ConfigurationFailed, message);
 
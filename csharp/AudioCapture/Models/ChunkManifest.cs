@@ -0,0 +1,20 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// What an encrypted recording's data chunk should hold when
+/// <see cref="CaptureConfiguration.SequenceBoundChunks"/> is set: how many sealed
+/// frames, and a hash chained through them in order. Checked by
+/// <see cref="Storage.EncryptedWavReader.VerifyManifest"/> without the key, so an
+/// auditor can tell no frame was cut off, dropped, reordered or swapped for another
+/// since the manifest was recorded.
+/// </summary>
+/// <param name="ChunkCount">Sealed frames written.</param>
+/// <param name="RollingHash">
+/// Hex SHA-256 chain: starting from 32 zero bytes, each frame's hash is
+/// SHA-256(previous ‖ length prefix ‖ sealed box).
+/// </param>
+public sealed record ChunkManifest(
+    [property: JsonPropertyName("chunk_count")] long ChunkCount,
+    [property: JsonPropertyName("rolling_hash")] string RollingHash);
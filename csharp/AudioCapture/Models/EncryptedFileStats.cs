@@ -0,0 +1,13 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// The shape of an encrypted recording's data chunk, as measured by
+/// <see cref="Storage.EncryptedWavReader.VerifyStructure"/> without the key.
+/// </summary>
+/// <param name="ChunkCount">Sealed frames in the data chunk.</param>
+/// <param name="CiphertextBytes">Bytes of sealed boxes, length prefixes excluded.</param>
+public sealed record EncryptedFileStats(
+    [property: JsonPropertyName("chunk_count")] long ChunkCount,
+    [property: JsonPropertyName("ciphertext_bytes")] long CiphertextBytes);
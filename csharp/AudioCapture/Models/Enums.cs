@@ -11,10 +11,27 @@ public enum MixingStrategy
     /// Mic on left, system mono-folded on right: L = mic, R = (sys_L + sys_R) / 2
     Separated,
 
-    /// Reserved for future multi-track output.
+    /// With four channels, a multitrack file: Ch1-2 = mic, Ch3-4 = system stereo,
+    /// nothing summed. With two, the same as Separated.
     Multichannel,
 }
 
+/// <summary>
+/// Where the mono mic sits in a <see cref="MixingStrategy.Blended"/> stereo mix.
+/// System audio stays on both channels either way.
+/// </summary>
+public enum MicPlacement
+{
+    /// Mic in both channels: L = mic + sys_L, R = mic + sys_R
+    Center,
+
+    /// Mic in the left channel only: L = mic + sys_L, R = sys_R
+    Left,
+
+    /// Mic in the right channel only: L = sys_L, R = mic + sys_R
+    Right,
+}
+
 /// <summary>
 /// Describes the channel layout of a recording.
 /// </summary>
@@ -28,6 +45,139 @@ public enum ChannelLayout
 
     /// Single mono channel.
     Mono,
+
+    /// Four channels (FL FR BL BR): system on the front pair, mic on the rears.
+    Quad,
+
+    /// 5.1 (FL FR FC LFE BL BR): system on the front pair, mic on centre.
+    Surround51,
+
+    /// Four discrete tracks, not speaker feeds: Ch1-2 = mic, Ch3-4 = system stereo.
+    Multitrack,
+}
+
+/// <summary>
+/// What the recording's SHA-256 checksum covers, and so how it is computed.
+/// </summary>
+public enum ChecksumMode
+{
+    /// The whole file, header included, hashed by re-reading it after close.
+    /// Matches <c>sha256sum</c> on the file, but re-reads every byte — a
+    /// noticeable stall at the end of a multi-GB recording.
+    File,
+
+    /// The data chunk only, hashed as it is written, so close returns at once.
+    /// The header is left out because close patches its size fields after the
+    /// bytes before them were hashed, and SHA-256 can't go back. It carries no
+    /// audio — format and sizes only — so nothing recorded goes unverified.
+    /// Verify with <c>EncryptedWavWriter.ComputePayloadChecksum</c>.
+    Streaming,
+}
+
+/// <summary>
+/// Which source paces the mix when both are captured — the one whose frames are
+/// written as they arrive, the other zero-filled where it falls short.
+/// </summary>
+public enum TimingSource
+{
+    /// Keep the two frame-locked, waiting for whichever lags; but when loopback has
+    /// delivered nothing for a moment — nothing is playing, so WASAPI sends no
+    /// packets at all — let the mic drive instead of holding it back.
+    Auto,
+
+    /// The mic paces the mix; system audio fills in behind it.
+    Mic,
+
+    /// System audio paces the mix; the mic fills in behind it.
+    System,
+}
+
+/// <summary>
+/// <c>AUDCLNT_STREAMFLAGS_*</c> a WASAPI source may be initialized with. Loopback
+/// and event-callback flags aren't listed: the capture sets those itself.
+/// </summary>
+[Flags]
+public enum WasapiStreamFlags
+{
+    None = 0,
+
+    /// Volume and mute changes made to the stream aren't remembered for next time.
+    NoPersist = 1 << 0,
+
+    /// The client may nudge the sample rate to track a drifting clock.
+    RateAdjust = 1 << 1,
+
+    /// The audio engine converts to the format the client asks for, so a mic can
+    /// be opened at the configured rate and layout rather than its mix format.
+    AutoConvertPcm = 1 << 2,
+
+    /// Use the better default-quality resampler for that conversion.
+    SrcDefaultQuality = 1 << 3,
+}
+
+/// <summary>
+/// The Windows default-device role to resolve. Each role can have its own default
+/// mic and speaker, set in the Sound control panel.
+/// </summary>
+public enum DeviceRole
+{
+    /// Games, system sounds and voice commands.
+    Console,
+
+    /// Music and video playback, and recording.
+    Multimedia,
+
+    /// Voice calls — the devices a VoIP app uses unless told otherwise.
+    Communications,
+}
+
+/// <summary>
+/// How a multichannel mic is folded to the mono the session records.
+/// </summary>
+public enum MicDownmix
+{
+    /// Mean of every channel. A device that carries the voice on only one of two
+    /// channels comes out 6 dB down.
+    Average,
+
+    /// The first channel alone; any others are ignored.
+    First,
+
+    /// Mean of the channels that carry signal, buffer by buffer. A dead channel is
+    /// left out rather than halving the live one; dual-mono and true stereo average
+    /// as usual.
+    Detect,
+}
+
+/// <summary>
+/// Who converted the mic from its device's mix format to the configured one.
+/// </summary>
+public enum MicConversion
+{
+    /// Nothing to report: mic capture was off, or the source was injected.
+    None,
+
+    /// WASAPI delivered the configured format itself (<see cref="WasapiStreamFlags.AutoConvertPcm"/>).
+    AudioEngine,
+
+    /// The mic was opened at its mix format and converted here — because the device
+    /// refused the configured format, or because <see cref="MicDownmix"/> asked to
+    /// fold the channels itself. A different resampler, worth knowing when
+    /// comparing recordings.
+    Resampler,
+}
+
+/// <summary>
+/// The sample format audio is processed in between capture and the file.
+/// </summary>
+public enum SampleFormat
+{
+    /// Decoded to float, metered, processed and mixed. Works for every configuration.
+    Float32,
+
+    /// 16-bit mic samples go to the file untouched — no float round trip, no mixer,
+    /// no clamp. Only for a mic-only, mono, 16-bit recording with no gate or AGC.
+    Int16,
 }
 
 /// <summary>
@@ -39,6 +189,24 @@ public enum AudioTrackType
     System,
 }
 
+/// <summary>
+/// Whether a capture source can be used right now, and if not, why.
+/// </summary>
+public enum SourceStatus
+{
+    /// Present and usable.
+    Available,
+
+    /// No endpoint of this kind is active.
+    NoDevice,
+
+    /// The OS privacy setting blocks this app from the device.
+    PermissionDenied,
+
+    /// Another application holds the device in exclusive mode.
+    InUse,
+}
+
 /// <summary>
 /// Physical transport type of an audio device.
 /// </summary>
@@ -62,3 +230,44 @@ public enum AudioChannel
     Center,
     Stereo,
 }
+
+/// <summary>
+/// What a source does when its capture callback throws — the delegate's
+/// <c>OnLevelsUpdated</c>, most often, which runs on the capture thread.
+/// </summary>
+public enum CallbackFaultPolicy
+{
+    /// Stop the faulting source cleanly. The other source carries on, and the
+    /// session stops as usual.
+    StopSource,
+
+    /// Drop the buffer that was being handled and keep capturing.
+    Continue,
+}
+
+/// <summary>
+/// Why a recording stopped. See <see cref="RecordingResult.StopReason"/>.
+/// </summary>
+public enum StopReason
+{
+    /// The caller stopped it.
+    UserStopped,
+
+    /// <see cref="CaptureConfiguration.MaxDuration"/> ran out.
+    MaxDuration,
+
+    /// The file reached <see cref="CaptureConfiguration.MaxFileBytes"/>.
+    MaxSize,
+
+    /// Reserved for a recording ended by its devices going away. Not reported yet:
+    /// a session keeps recording, and stays Capturing, until stopped.
+    DeviceLost,
+
+    /// A source stalled, with <see cref="CaptureConfiguration.StopOnStall"/> set.
+    Stalled,
+
+    /// Reserved for a recording ended by a failure. Not reported yet: a source
+    /// stopped under <see cref="CallbackFaultPolicy.StopSource"/> leaves the
+    /// recording running until stopped.
+    Error,
+}
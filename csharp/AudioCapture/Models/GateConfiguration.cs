@@ -0,0 +1,35 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Parameters for the mic noise gate. See <see cref="Processing.NoiseGate"/>.
+/// </summary>
+public sealed record GateConfiguration
+{
+    /// <summary>Level, in dBFS, above which the gate opens.</summary>
+    [JsonPropertyName("threshold_dbfs")]
+    public float ThresholdDbfs { get; init; } = -45f;
+
+    /// <summary>Fade-in time when the gate opens. Short, so word onsets aren't clipped.</summary>
+    [JsonPropertyName("attack")]
+    public TimeSpan Attack { get; init; } = TimeSpan.FromMilliseconds(5);
+
+    /// <summary>
+    /// How long the level must stay under the threshold before the gate starts to
+    /// close — bridges the gaps between words.
+    /// </summary>
+    [JsonPropertyName("hold")]
+    public TimeSpan Hold { get; init; } = TimeSpan.FromMilliseconds(200);
+
+    /// <summary>Fade-out time once the hold has run out.</summary>
+    [JsonPropertyName("release")]
+    public TimeSpan Release { get; init; } = TimeSpan.FromMilliseconds(150);
+
+    /// <summary>
+    /// Attenuation, in dB, applied while closed. Not full silence: a dead-silent
+    /// floor sounds like a dropout, and transcription copes better with quiet room tone.
+    /// </summary>
+    [JsonPropertyName("floor_db")]
+    public float FloorDb { get; init; } = -30f;
+}
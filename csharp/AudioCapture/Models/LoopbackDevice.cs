@@ -0,0 +1,8 @@
+namespace AudioCapture.Models;
+
+/// <summary>
+/// A render endpoint as a loopback target. <see cref="IsRendering"/> is whether any
+/// application currently has an active audio session on it — the one a user
+/// expects to record is almost always the one playing something.
+/// </summary>
+public sealed record LoopbackDevice(AudioSource Device, bool IsRendering);
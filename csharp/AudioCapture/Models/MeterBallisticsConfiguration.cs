@@ -0,0 +1,17 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Rise and fall times for smoothed level meters. See <see cref="Processing.MeterBallistics"/>.
+/// </summary>
+public sealed record MeterBallisticsConfiguration
+{
+    /// <summary>Time to cover ~63% of a rise in level. Short, so transients still register.</summary>
+    [JsonPropertyName("attack")]
+    public TimeSpan Attack { get; init; } = TimeSpan.FromMilliseconds(10);
+
+    /// <summary>Time to cover ~63% of a fall in level — the slow decay that stops a meter flickering.</summary>
+    [JsonPropertyName("release")]
+    public TimeSpan Release { get; init; } = TimeSpan.FromMilliseconds(300);
+}
@@ -0,0 +1,27 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Why a recording ended Failed, written as JSON to a <c>.partial</c> marker beside
+/// the file unless <see cref="CaptureConfiguration.DeletePartialOnFailure"/> is set —
+/// so whoever picks the file up later knows not to trust it, and support knows
+/// what went wrong.
+/// </summary>
+/// <param name="FilePath">The partial recording the marker describes.</param>
+/// <param name="ErrorKind">The failure's <see cref="CaptureException.ErrorKind"/>.</param>
+/// <param name="Error">The failure's message.</param>
+/// <param name="BytesWritten">Audio bytes handed to the writer before it failed.</param>
+/// <param name="DurationSecs">How long the run had been recording.</param>
+/// <param name="FailedAt">When the run was finalized, UTC.</param>
+public sealed record PartialRecording(
+    [property: JsonPropertyName("file_path")] string FilePath,
+    [property: JsonPropertyName("error_kind")] CaptureErrorKind ErrorKind,
+    [property: JsonPropertyName("error")] string Error,
+    [property: JsonPropertyName("bytes_written")] long BytesWritten,
+    [property: JsonPropertyName("duration_secs")] double DurationSecs,
+    [property: JsonPropertyName("failed_at")] DateTime FailedAt)
+{
+    /// <summary>The marker's path for the recording at <paramref name="filePath"/>.</summary>
+    public static string MarkerPath(string filePath) => filePath + ".partial";
+}
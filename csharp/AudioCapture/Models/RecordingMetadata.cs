@@ -46,4 +46,55 @@ public sealed record RecordingMetadata(
 
     [JsonPropertyName("channel_layout")]
     public ChannelLayout ChannelLayout { get; init; } = ChannelLayout;
+
+    /// <summary>
+    /// When system audio was playing, as intervals on the recording's timeline — a
+    /// cheap voice-activity hint for the system track. Empty when system capture
+    /// was off or nothing played.
+    /// </summary>
+    [JsonPropertyName("system_activity")]
+    public ActivityInterval[] SystemActivity { get; init; } = [];
+
+    /// <summary>The recording holds only digital silence. See <see cref="RecordingResult.IsSilent"/>.</summary>
+    [JsonPropertyName("is_silent")]
+    public bool IsSilent { get; init; }
+
+    /// <summary>
+    /// The sealed-frame count and hash chain of a sequence-bound encrypted recording;
+    /// null otherwise. See <see cref="Models.ChunkManifest"/>.
+    /// </summary>
+    [JsonPropertyName("chunk_manifest")]
+    public ChunkManifest? ChunkManifest { get; init; }
+
+    /// <summary>
+    /// The session's state transitions from <c>Configure</c> through stopping, with
+    /// timestamps, when <see cref="CaptureConfiguration.RecordStateLog"/> is set; empty
+    /// otherwise. The last 64 at most. The final state is the result itself.
+    /// </summary>
+    [JsonPropertyName("state_log")]
+    public StateTransition[] StateLog { get; init; } = [];
+
+    /// <summary>
+    /// The silences left out of the file when <see cref="CaptureConfiguration.SkipSilence"/>
+    /// is set, in order; empty otherwise. Times are on this run's timeline.
+    /// </summary>
+    [JsonPropertyName("skipped_gaps")]
+    public SkippedGap[] SkippedGaps { get; init; } = [];
+
+    /// <summary>What ended the recording, as a snake_case name. See <see cref="RecordingResult.StopReason"/>.</summary>
+    [JsonPropertyName("stop_reason")]
+    [JsonConverter(typeof(SnakeCaseEnumConverter<StopReason>))]
+    public StopReason StopReason { get; init; }
+
+    /// <summary>The caller's <see cref="CaptureConfiguration.Tags"/>, as configured for the run.</summary>
+    [JsonPropertyName("tags")]
+    public IReadOnlyDictionary<string, string> Tags { get; init; } = new Dictionary<string, string>();
+
+    /// <summary>
+    /// The encryptor's <see cref="Interfaces.ICaptureEncryptor.KeyMetadata"/> — never
+    /// the key, but what finds or rebuilds it, such as a
+    /// <see cref="Storage.PassphraseEncryptor"/>'s salt. Empty when not encrypted.
+    /// </summary>
+    [JsonPropertyName("encryption_key_metadata")]
+    public IReadOnlyDictionary<string, string> EncryptionKeyMetadata { get; init; } = new Dictionary<string, string>();
 }
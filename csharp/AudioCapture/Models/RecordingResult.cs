@@ -8,4 +8,48 @@ public sealed record RecordingResult(
     double DurationSecs,
     RecordingMetadata Metadata,
     string Checksum,
-    string[] RawPcmFilePaths);
+    string[] RawPcmFilePaths)
+{
+    /// <summary>
+    /// The session wrote no audio, or less than
+    /// <see cref="CaptureConfiguration.MinimumRecordingDuration"/>. With
+    /// <see cref="CaptureConfiguration.DeleteTooShortRecordings"/> the files are
+    /// already gone and <see cref="Checksum"/> is empty.
+    /// </summary>
+    public bool IsTooShort { get; init; }
+
+    /// <summary>
+    /// Nothing but digital silence was written — the wrong device, a muted mic, or
+    /// nothing playing for a system-only recording. Worth warning about before the
+    /// file goes anywhere. An empty recording counts as silent too.
+    /// </summary>
+    public bool IsSilent { get; init; }
+
+    /// <summary>
+    /// Some of the mix went past full scale — the recording clipped.
+    /// Worth a warning, or turning the gain down or a limiter on for the next one.
+    /// <see cref="CaptureDiagnostics.ClippedSamples"/> says how much.
+    /// </summary>
+    public bool DidClip { get; init; }
+
+    /// <summary>
+    /// What ended the recording: the caller, or one of the limits the session
+    /// enforces itself — <see cref="CaptureConfiguration.MaxDuration"/>,
+    /// <see cref="CaptureConfiguration.MaxFileBytes"/>, or a stall. One place to
+    /// branch on the outcome; also in <see cref="RecordingMetadata.StopReason"/>.
+    /// </summary>
+    public StopReason StopReason { get; init; }
+
+    /// <summary>
+    /// The complete WAV, header included, when
+    /// <see cref="CaptureConfiguration.CaptureToMemory"/> is set; <see cref="FilePath"/>
+    /// is then empty. Null for file recordings.
+    /// </summary>
+    public byte[]? AudioData { get; init; }
+
+    /// <summary>
+    /// The format the recording's header declares, as written. Read the file with
+    /// this rather than with the configuration it was started from.
+    /// </summary>
+    public WavSpec? Spec { get; init; }
+}
@@ -0,0 +1,29 @@
+namespace AudioCapture.Models;
+
+/// <summary>
+/// What <see cref="Capture.WasapiCaptureSession.SelfTestAsync"/> found: whether a
+/// configuration could record here and now, and if not, which part is in the way.
+/// </summary>
+/// <param name="ConfigurationError">Why the configuration itself is rejected, or null when it is valid.</param>
+/// <param name="OutputError">
+/// Why the output directory can't take the recording, or null when it can — or when
+/// the recording goes to memory or a sink instead.
+/// </param>
+/// <param name="Sources">One result per enabled source, mic first.</param>
+public sealed record SelfTestReport(string? ConfigurationError, string? OutputError, SourceSelfTest[] Sources)
+{
+    /// <summary>
+    /// Everything checked out: a record button can be offered. Loopback only has to
+    /// open, since it delivers nothing while nothing plays.
+    /// </summary>
+    public bool IsReady => ConfigurationError == null && OutputError == null
+        && Sources.All(s => s.Status == SourceStatus.Available
+            && (s.Delivered || s.SourceType == AudioTrackType.System));
+}
+
+/// <summary>How one source fared when opened for a self-test.</summary>
+/// <param name="SourceType">Mic or system audio.</param>
+/// <param name="Status">Whether it opened, and if not, why.</param>
+/// <param name="Delivered">Whether a buffer of audio arrived within the self-test's timeout.</param>
+/// <param name="Error">What opening it reported when it failed, or null.</param>
+public sealed record SourceSelfTest(AudioTrackType SourceType, SourceStatus Status, bool Delivered, string? Error);
@@ -0,0 +1,21 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Parameters for leaving long silences out of the recording. See
+/// <see cref="Processing.SilenceSkipper"/>.
+/// </summary>
+public sealed record SilenceSkipConfiguration
+{
+    /// <summary>Level, in dBFS, of the mix below which it counts as silent.</summary>
+    [JsonPropertyName("threshold_dbfs")]
+    public float ThresholdDbfs { get; init; } = -50f;
+
+    /// <summary>
+    /// How long the mix must stay silent before writing stops. That much of each
+    /// silence is kept, so pauses in conversation survive and only long ones go.
+    /// </summary>
+    [JsonPropertyName("hold")]
+    public TimeSpan Hold { get; init; } = TimeSpan.FromSeconds(2);
+}
@@ -0,0 +1,14 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// A silence left out of the recording by <see cref="CaptureConfiguration.SkipSilence"/>:
+/// where it would have been on the run's timeline, where the file resumes, and how
+/// long it was. Inserting <see cref="DurationSecs"/> of silence at
+/// <see cref="FileSecs"/> for each gap restores the original timeline.
+/// </summary>
+public sealed record SkippedGap(
+    [property: JsonPropertyName("start_secs")] double StartSecs,
+    [property: JsonPropertyName("file_secs")] double FileSecs,
+    [property: JsonPropertyName("duration_secs")] double DurationSecs);
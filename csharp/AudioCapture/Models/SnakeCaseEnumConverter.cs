@@ -0,0 +1,12 @@
+using System.Text.Json;
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// Writes an enum as its snake_case name — <c>"user_stopped"</c> rather than
+/// <c>0</c> — so the JSON reads the same to a backend in any language and survives
+/// members being added.
+/// </summary>
+internal sealed class SnakeCaseEnumConverter<TEnum>() : JsonStringEnumConverter<TEnum>(JsonNamingPolicy.SnakeCaseLower)
+    where TEnum : struct, Enum;
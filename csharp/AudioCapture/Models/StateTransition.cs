@@ -0,0 +1,11 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// One entry of <see cref="RecordingMetadata.StateLog"/>: a state the session
+/// entered, and when.
+/// </summary>
+public sealed record StateTransition(
+    [property: JsonPropertyName("state")] CaptureStateKind State,
+    [property: JsonPropertyName("at")] DateTimeOffset At);
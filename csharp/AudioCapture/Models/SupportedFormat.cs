@@ -0,0 +1,10 @@
+namespace AudioCapture.Models;
+
+/// <summary>
+/// A format an endpoint accepts, and in which share modes, as reported by
+/// <see cref="Capture.DeviceEnumerator.GetSupportedFormats"/>.
+/// </summary>
+/// <param name="Spec">Rate, channels and sample format.</param>
+/// <param name="Shared">Accepted as-is by a shared-mode client.</param>
+/// <param name="Exclusive">Accepted by an exclusive-mode client.</param>
+public sealed record SupportedFormat(WavSpec Spec, bool Shared, bool Exclusive);
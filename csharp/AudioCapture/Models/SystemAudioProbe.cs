@@ -0,0 +1,12 @@
+namespace AudioCapture.Models;
+
+/// <summary>
+/// What a short listen to system audio found. <see cref="IsAudible"/> false with a
+/// device name means loopback works and nothing is playing — not that capture failed.
+/// </summary>
+/// <param name="IsAudible">Whether any non-silent packet arrived during the probe.</param>
+/// <param name="RenderDeviceName">
+/// Friendly name of the default render device loopback records, or null when there
+/// is none (or the source is injected).
+/// </param>
+public sealed record SystemAudioProbe(bool IsAudible, string? RenderDeviceName);
@@ -0,0 +1,14 @@
+using System.Text.Json.Serialization;
+
+namespace AudioCapture.Models;
+
+/// <summary>
+/// The format a recording's header actually declares — read back from what the
+/// writer wrote, not re-derived from the configuration. Interpret the file with
+/// this; the configuration only says what was asked for.
+/// </summary>
+public sealed record WavSpec(
+    [property: JsonPropertyName("sample_rate")] int SampleRate,
+    [property: JsonPropertyName("channels")] int Channels,
+    [property: JsonPropertyName("bit_depth")] int BitDepth,
+    [property: JsonPropertyName("is_float")] bool IsFloat);
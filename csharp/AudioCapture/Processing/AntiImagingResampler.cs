@@ -0,0 +1,63 @@
+using AudioCapture.Interfaces;
+using NAudio.Dsp;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// Wraps another <see cref="IAudioResampler"/> and low-passes what it produces when
+/// upsampling, just under the source's Nyquist frequency — where a cheap resampler,
+/// linear interpolation say, leaves images of the source's spectrum mirrored above
+/// it. A 16 kHz hands-free mic mixed into 48 kHz system audio is the case in mind:
+/// nothing real lives above 8 kHz there, so anything that does is an artifact.
+///
+/// <para>The filter is an 8th-order Butterworth — four biquads per channel — with its
+/// cutoff at 0.45 of the input rate. Downsampling and same-rate calls pass through
+/// the inner resampler untouched.</para>
+///
+/// <para><b>Threading.</b> As for any <see cref="IAudioResampler"/>: one stream, not
+/// thread-safe. Filter state carries across calls and restarts on a format change.</para>
+/// </summary>
+public sealed class AntiImagingResampler : IAudioResampler
+{
+    // Cutoff as a fraction of the input rate: the passband keeps all but the top
+    // tenth below Nyquist, while the first image sits well inside the stopband.
+    private const float CutoffRatio = 0.45f;
+
+    // Per-stage Q of an 8th-order Butterworth low-pass.
+    private static readonly float[] ButterworthQs = [0.5098f, 0.6013f, 0.9000f, 2.5629f];
+
+    private readonly IAudioResampler _inner;
+    private BiQuadFilter[][]? _filters;
+    private (int Channels, int InputRate, int OutputRate) _format;
+
+    /// <param name="inner">The resampler to filter; a <see cref="WdlAudioResampler"/> when null.</param>
+    public AntiImagingResampler(IAudioResampler? inner = null)
+    {
+        _inner = inner ?? new WdlAudioResampler();
+    }
+
+    public float[] Process(float[] input, int channels, int inputRate, int outputRate)
+    {
+        var output = _inner.Process(input, channels, inputRate, outputRate);
+        if (outputRate <= inputRate || output.Length == 0) return output;
+
+        if (_filters == null || _format != (channels, inputRate, outputRate))
+        {
+            _filters = new BiQuadFilter[channels][];
+            for (int ch = 0; ch < channels; ch++)
+                _filters[ch] = [.. ButterworthQs.Select(q => BiQuadFilter.LowPassFilter(outputRate, inputRate * CutoffRatio, q))];
+            _format = (channels, inputRate, outputRate);
+        }
+
+        // The inner resampler may hand back its input array; filter a copy.
+        var filtered = ReferenceEquals(output, input) ? (float[])output.Clone() : output;
+        for (int i = 0; i < filtered.Length; i++)
+        {
+            var sample = filtered[i];
+            foreach (var stage in _filters[i % channels])
+                sample = stage.Transform(sample);
+            filtered[i] = sample;
+        }
+        return filtered;
+    }
+}
@@ -0,0 +1,72 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// A slow-acting gain that steers mono mic audio toward a target RMS level, so a
+/// speaker leaning in or away from the mic lands at a similar level in the file.
+///
+/// <para>The level is tracked with a short mean-square envelope; the gain that
+/// would bring it to target is capped at <see cref="AgcConfiguration.MaxGain"/> and
+/// approached with the attack time when it has to fall and the release time when it
+/// may rise. Output is clamped to full scale.</para>
+///
+/// <para><b>Threading.</b> Not thread-safe: it carries envelope and gain state
+/// across buffers and is driven only from the mic capture callback.</para>
+/// </summary>
+public sealed class AutomaticGainControl
+{
+    // Averaging window for the level estimate — long enough to ride over single
+    // syllables, short enough to notice a speaker moving within a sentence.
+    private const double LevelWindowSeconds = 0.1;
+
+    private readonly float _targetRms;
+    private readonly float _maxGain;
+    private readonly float _levelCoefficient;
+    private readonly float _attackCoefficient;
+    private readonly float _releaseCoefficient;
+
+    private float _meanSquare;
+    private float _gain = 1f;
+
+    public AutomaticGainControl(AgcConfiguration configuration, int sampleRate)
+    {
+        ArgumentNullException.ThrowIfNull(configuration);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sampleRate);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(configuration.TargetRms);
+        if (configuration.MaxGain < 1f)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "MaxGain must be at least 1");
+        if (configuration.Attack <= TimeSpan.Zero || configuration.Release <= TimeSpan.Zero)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "Attack and release must be positive");
+
+        _targetRms = configuration.TargetRms;
+        _maxGain = configuration.MaxGain;
+        _levelCoefficient = Coefficient(LevelWindowSeconds, sampleRate);
+        _attackCoefficient = Coefficient(configuration.Attack.TotalSeconds, sampleRate);
+        _releaseCoefficient = Coefficient(configuration.Release.TotalSeconds, sampleRate);
+    }
+
+    /// <summary>The gain applied to the most recent sample.</summary>
+    public float CurrentGain => _gain;
+
+    /// <summary>Applies the gain to <paramref name="samples"/> in place.</summary>
+    public void Process(Span<float> samples)
+    {
+        for (int i = 0; i < samples.Length; i++)
+        {
+            var x = samples[i];
+            _meanSquare += (1f - _levelCoefficient) * (x * x - _meanSquare);
+
+            var rms = MathF.Sqrt(_meanSquare);
+            var desired = rms > 0f ? Math.Min(_targetRms / rms, _maxGain) : _maxGain;
+            var coefficient = desired < _gain ? _attackCoefficient : _releaseCoefficient;
+            _gain = desired + coefficient * (_gain - desired);
+
+            samples[i] = Math.Clamp(x * _gain, -1f, 1f);
+        }
+    }
+
+    /// <summary>One-pole smoothing coefficient reaching ~63% of a step in <paramref name="seconds"/>.</summary>
+    private static float Coefficient(double seconds, int sampleRate) =>
+        (float)Math.Exp(-1.0 / (seconds * sampleRate));
+}
@@ -0,0 +1,50 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// Smooths a stream of level readings the way a hardware meter needle moves:
+/// quick to rise over the attack time, slow to fall over the release time.
+///
+/// <para>Readings arrive once per capture callback, whose length varies, so each
+/// update takes the time it covers and the one-pole coefficient is worked out for
+/// that span rather than fixed per sample.</para>
+///
+/// <para><b>Threading.</b> Not thread-safe: the smoothed level carries across
+/// updates, and each instance is driven only from its source's capture callback.</para>
+/// </summary>
+public sealed class MeterBallistics
+{
+    private readonly double _attackSeconds;
+    private readonly double _releaseSeconds;
+
+    public MeterBallistics(MeterBallisticsConfiguration configuration)
+    {
+        ArgumentNullException.ThrowIfNull(configuration);
+        if (configuration.Attack <= TimeSpan.Zero || configuration.Release <= TimeSpan.Zero)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "Attack and release must be positive");
+
+        _attackSeconds = configuration.Attack.TotalSeconds;
+        _releaseSeconds = configuration.Release.TotalSeconds;
+    }
+
+    /// <summary>The smoothed level after the most recent update.</summary>
+    public float Current { get; private set; }
+
+    /// <summary>
+    /// Moves the smoothed level toward <paramref name="level"/> by as much as
+    /// <paramref name="elapsed"/> allows, and returns it.
+    /// </summary>
+    public float Update(float level, TimeSpan elapsed)
+    {
+        if (elapsed <= TimeSpan.Zero) return Current;
+
+        var seconds = level > Current ? _attackSeconds : _releaseSeconds;
+        var coefficient = (float)Math.Exp(-elapsed.TotalSeconds / seconds);
+        Current = level + coefficient * (Current - level);
+        return Current;
+    }
+
+    /// <summary>Drops the meter back to zero, as at the start of a recording.</summary>
+    public void Reset() => Current = 0f;
+}
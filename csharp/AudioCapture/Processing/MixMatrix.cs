@@ -0,0 +1,136 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// Gains routing the three capture inputs — mic, system left, system right — onto
+/// an arbitrary number of output channels.
+///
+/// <para>Stereo output keeps going through <see cref="StereoMixer.Mix"/>; this is
+/// what lets the same interleave logic produce mono, quad, or 5.1 without a mixer
+/// per layout. Output channel order follows the WAVE_FORMAT_EXTENSIBLE speaker
+/// order for <see cref="ChannelMask"/>, so the file's header and its samples agree
+/// by construction.</para>
+/// </summary>
+public sealed class MixMatrix
+{
+    /// <summary>Input columns: mic, system left, system right.</summary>
+    public const int InputCount = 3;
+
+    // SPEAKER_* bits from ksmedia.h, in the order WAV interleaves them.
+    private const uint FrontLeft = 0x1;
+    private const uint FrontRight = 0x2;
+    private const uint FrontCenter = 0x4;
+    private const uint LowFrequency = 0x8;
+    private const uint BackLeft = 0x10;
+    private const uint BackRight = 0x20;
+
+    // KSAUDIO_SPEAKER_DIRECTOUT: channels are discrete tracks, not speaker feeds.
+    private const uint DirectOut = 0x0;
+
+    private readonly float[,] _gains;
+
+    /// <param name="gains">[output channel, input] gains; inputs are mic, system L, system R.</param>
+    /// <param name="channelMask">Speaker positions of the output channels, in order.</param>
+    public MixMatrix(float[,] gains, uint channelMask)
+    {
+        ArgumentNullException.ThrowIfNull(gains);
+        if (gains.GetLength(1) != InputCount)
+            throw new ArgumentException($"A mix matrix needs {InputCount} input columns.", nameof(gains));
+        if (gains.GetLength(0) == 0)
+            throw new ArgumentException("A mix matrix needs at least one output channel.", nameof(gains));
+
+        _gains = (float[,])gains.Clone();
+        ChannelMask = channelMask;
+    }
+
+    /// <summary>Number of interleaved channels <see cref="Mix"/> produces.</summary>
+    public int OutputChannels => _gains.GetLength(0);
+
+    /// <summary>The dwChannelMask the extensible WAV header should carry.</summary>
+    public uint ChannelMask { get; }
+
+    /// <summary>Output channel counts <see cref="ForLayout"/> knows how to build.</summary>
+    public static IReadOnlyList<int> SupportedChannelCounts { get; } = [1, 2, 4, 6];
+
+    /// <summary>
+    /// The default routing for an output channel count:
+    /// <list type="bullet">
+    /// <item>1 — mic plus the system mono-fold.</item>
+    /// <item>2 — the stereo <paramref name="strategy"/>, same as <see cref="StereoMixer"/>.</item>
+    /// <item>4 (quad: FL FR BL BR) — system on the front pair, mic on both rears.</item>
+    /// <item>4 with <see cref="MixingStrategy.Multichannel"/> — a multitrack file
+    /// rather than a speaker layout: mic on channels 1–2, system stereo on 3–4,
+    /// nothing summed. The mask is direct-out, so players and editors open the
+    /// channels as discrete tracks instead of routing them to speakers.</item>
+    /// <item>6 (5.1: FL FR FC LFE BL BR) — system on the front pair, mic alone on
+    /// centre, LFE and rears silent. Dialogue on centre is the convention every
+    /// surround decoder already expects.</item>
+    /// </list>
+    /// </summary>
+    public static MixMatrix ForLayout(int channels, MixingStrategy strategy = MixingStrategy.Blended)
+    {
+        return channels switch
+        {
+            1 => new MixMatrix(new float[,] { { 1f, 0.5f, 0.5f } }, FrontCenter),
+            2 when strategy is MixingStrategy.Separated or MixingStrategy.Multichannel => new MixMatrix(
+                new float[,] { { 1f, 0f, 0f }, { 0f, 0.5f, 0.5f } },
+                FrontLeft | FrontRight),
+            2 => new MixMatrix(
+                new float[,] { { 1f, 1f, 0f }, { 1f, 0f, 1f } },
+                FrontLeft | FrontRight),
+            4 when strategy is MixingStrategy.Multichannel => new MixMatrix(
+                new float[,] { { 1f, 0f, 0f }, { 1f, 0f, 0f }, { 0f, 1f, 0f }, { 0f, 0f, 1f } },
+                DirectOut),
+            4 => new MixMatrix(
+                new float[,] { { 0f, 1f, 0f }, { 0f, 0f, 1f }, { 1f, 0f, 0f }, { 1f, 0f, 0f } },
+                FrontLeft | FrontRight | BackLeft | BackRight),
+            6 => new MixMatrix(
+                new float[,]
+                {
+                    { 0f, 1f, 0f }, { 0f, 0f, 1f }, { 1f, 0f, 0f },
+                    { 0f, 0f, 0f }, { 0f, 0f, 0f }, { 0f, 0f, 0f },
+                },
+                FrontLeft | FrontRight | FrontCenter | LowFrequency | BackLeft | BackRight),
+            _ => throw new ArgumentOutOfRangeException(
+                nameof(channels), channels, $"Supported output channel counts: {string.Join(", ", SupportedChannelCounts)}"),
+        };
+    }
+
+    /// <summary>
+    /// Mixes mono mic and interleaved stereo system audio into interleaved
+    /// <see cref="OutputChannels"/>-channel output. The shorter input is
+    /// zero-padded, as in <see cref="StereoMixer"/>.
+    /// </summary>
+    public float[] Mix(float[] mic, float[] system)
+    {
+        var output = Array.Empty<float>();
+        MixInto(mic, system, ref output);
+        return output;
+    }
+
+    /// <summary>
+    /// As <see cref="Mix"/>, but into a reusable buffer, grown only when too small.
+    /// Returns the number of samples written.
+    /// </summary>
+    public int MixInto(ReadOnlySpan<float> mic, ReadOnlySpan<float> system, ref float[] destination)
+    {
+        int micFrames = mic.Length;
+        int systemFrames = system.Length / 2;
+        int frameCount = Math.Max(micFrames, systemFrames);
+        var outputChannels = OutputChannels;
+        StereoMixer.EnsureCapacity(ref destination, frameCount * outputChannels);
+
+        for (int i = 0; i < frameCount; i++)
+        {
+            float micSample = i < micFrames ? mic[i] : 0f;
+            float sysL = (i * 2) < system.Length ? system[i * 2] : 0f;
+            float sysR = (i * 2 + 1) < system.Length ? system[i * 2 + 1] : 0f;
+
+            for (int ch = 0; ch < outputChannels; ch++)
+                destination[i * outputChannels + ch] =
+                    _gains[ch, 0] * micSample + _gains[ch, 1] * sysL + _gains[ch, 2] * sysR;
+        }
+        return frameCount * outputChannels;
+    }
+}
@@ -0,0 +1,82 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// Attenuates mono mic audio between utterances, so keyboard clatter and room
+/// noise in the pauses don't reach transcription.
+///
+/// <para>A peak envelope is compared with the threshold. While it is above, the
+/// gate fades open over the attack time; once it has stayed below for the hold
+/// time, the gate fades down to the floor over the release time. Gain is smoothed
+/// per sample either way, so opening and closing never click.</para>
+///
+/// <para><b>Threading.</b> Not thread-safe: envelope, hold and gain state carry
+/// across buffers, and it is driven only from the mic capture callback.</para>
+/// </summary>
+public sealed class NoiseGate
+{
+    // Envelope release — quick enough to notice a word ending, slow enough not to
+    // chatter on the troughs of a single low-pitched cycle.
+    private const double EnvelopeReleaseSeconds = 0.02;
+
+    private readonly float _threshold;
+    private readonly float _floorGain;
+    private readonly int _holdSamples;
+    private readonly float _envelopeCoefficient;
+    private readonly float _attackCoefficient;
+    private readonly float _releaseCoefficient;
+
+    private float _envelope;
+    private int _samplesBelow;
+    private float _gain = 1f;
+
+    public NoiseGate(GateConfiguration configuration, int sampleRate)
+    {
+        ArgumentNullException.ThrowIfNull(configuration);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sampleRate);
+        if (configuration.FloorDb > 0f)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "FloorDb is an attenuation and must be <= 0");
+        if (configuration.Attack <= TimeSpan.Zero || configuration.Release <= TimeSpan.Zero
+            || configuration.Hold < TimeSpan.Zero)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "Attack and release must be positive, hold non-negative");
+
+        _threshold = DbToLinear(configuration.ThresholdDbfs);
+        _floorGain = DbToLinear(configuration.FloorDb);
+        _holdSamples = (int)(configuration.Hold.TotalSeconds * sampleRate);
+        _envelopeCoefficient = Coefficient(EnvelopeReleaseSeconds, sampleRate);
+        _attackCoefficient = Coefficient(configuration.Attack.TotalSeconds, sampleRate);
+        _releaseCoefficient = Coefficient(configuration.Release.TotalSeconds, sampleRate);
+    }
+
+    /// <summary>The gain applied to the most recent sample: 1 when open, the floor when closed.</summary>
+    public float CurrentGain => _gain;
+
+    /// <summary>Gates <paramref name="samples"/> in place.</summary>
+    public void Process(Span<float> samples)
+    {
+        for (int i = 0; i < samples.Length; i++)
+        {
+            var level = Math.Abs(samples[i]);
+            _envelope = level > _envelope ? level : level + _envelopeCoefficient * (_envelope - level);
+
+            if (_envelope >= _threshold)
+                _samplesBelow = 0;
+            else if (_samplesBelow < _holdSamples)
+                _samplesBelow++;
+
+            var open = _samplesBelow < _holdSamples || _envelope >= _threshold;
+            var target = open ? 1f : _floorGain;
+            var coefficient = open ? _attackCoefficient : _releaseCoefficient;
+            _gain = target + coefficient * (_gain - target);
+
+            samples[i] *= _gain;
+        }
+    }
+
+    private static float DbToLinear(float db) => MathF.Pow(10f, db / 20f);
+
+    /// <summary>One-pole smoothing coefficient reaching ~63% of a step in <paramref name="seconds"/>.</summary>
+    private static float Coefficient(double seconds, int sampleRate) =>
+        (float)Math.Exp(-1.0 / (seconds * sampleRate));
+}
@@ -0,0 +1,33 @@
+using NAudio.Wave;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// An <see cref="ISampleProvider"/> fed by pushed buffers instead of pulling
+/// from a source. Returns short (or empty) reads when drained rather than
+/// padding with silence — padding would splice fake frames into the timeline
+/// every time a chunk boundary fell mid-read.
+/// </summary>
+internal sealed class PushSampleProvider(WaveFormat waveFormat) : ISampleProvider
+{
+    private readonly Queue<float> _queue = new();
+
+    public WaveFormat WaveFormat { get; } = waveFormat;
+
+    public void Push(float[] samples)
+    {
+        foreach (var sample in samples)
+            _queue.Enqueue(sample);
+    }
+
+    /// <summary>Drops everything pushed but not yet read.</summary>
+    public void Clear() => _queue.Clear();
+
+    public int Read(float[] buffer, int offset, int count)
+    {
+        int written = 0;
+        while (written < count && _queue.Count > 0)
+            buffer[offset + written++] = _queue.Dequeue();
+        return written;
+    }
+}
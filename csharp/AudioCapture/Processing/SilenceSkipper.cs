@@ -0,0 +1,112 @@
+using AudioCapture.Models;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// Drops long silences from the mix before it is written, and records each one as
+/// a <see cref="SkippedGap"/> so the original timeline can be rebuilt.
+///
+/// <para>The mix is judged in 10 ms blocks by RMS across all channels. Once blocks
+/// have stayed under the threshold for the hold time, the silent blocks after it
+/// are dropped until one is above again; the gap closes there. A gap still open
+/// at the end is closed by <see cref="Finish"/>.</para>
+///
+/// <para><b>Threading.</b> Not thread-safe: it is driven only from the mix loop.</para>
+/// </summary>
+public sealed class SilenceSkipper
+{
+    private const int BlocksPerSecond = 100;
+
+    private readonly float _threshold;
+    private readonly long _holdFrames;
+    private readonly int _sampleRate;
+    private readonly int _channels;
+    private readonly int _blockFrames;
+    private readonly List<SkippedGap> _gaps = [];
+
+    private long _timelineFrames;
+    private long _writtenFrames;
+    private long _quietFrames;
+    private long? _gapStartFrame;
+
+    public SilenceSkipper(SilenceSkipConfiguration configuration, int sampleRate, int channels)
+    {
+        ArgumentNullException.ThrowIfNull(configuration);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sampleRate);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(channels);
+        if (configuration.Hold < TimeSpan.Zero)
+            throw new ArgumentOutOfRangeException(nameof(configuration), "Hold must be non-negative");
+
+        _threshold = MathF.Pow(10f, configuration.ThresholdDbfs / 20f);
+        _holdFrames = (long)(configuration.Hold.TotalSeconds * sampleRate);
+        _sampleRate = sampleRate;
+        _channels = channels;
+        _blockFrames = Math.Max(sampleRate / BlocksPerSecond, 1);
+    }
+
+    /// <summary>The gaps closed so far, in order.</summary>
+    public IReadOnlyList<SkippedGap> Gaps => _gaps;
+
+    /// <summary>Whether the silence being passed over now is being left out.</summary>
+    public bool IsSkipping => _gapStartFrame != null;
+
+    /// <summary>
+    /// Removes the skipped blocks from interleaved <paramref name="samples"/>, moving
+    /// the kept ones to the front.
+    /// </summary>
+    /// <returns>How many samples were kept.</returns>
+    public int Process(Span<float> samples)
+    {
+        var kept = 0;
+        var blockSamples = _blockFrames * _channels;
+        for (var start = 0; start < samples.Length; start += blockSamples)
+        {
+            var block = samples.Slice(start, Math.Min(blockSamples, samples.Length - start));
+            var frames = block.Length / _channels;
+
+            if (Rms(block) >= _threshold)
+            {
+                CloseGap();
+                _quietFrames = 0;
+            }
+            else if (_gapStartFrame == null && _quietFrames >= _holdFrames)
+            {
+                _gapStartFrame = _timelineFrames;
+            }
+            else
+            {
+                _quietFrames += frames;
+            }
+
+            _timelineFrames += frames;
+            if (_gapStartFrame != null)
+                continue;
+            block.CopyTo(samples[kept..]);
+            kept += block.Length;
+            _writtenFrames += frames;
+        }
+        return kept;
+    }
+
+    /// <summary>Closes a gap still open when the recording ends.</summary>
+    public void Finish() => CloseGap();
+
+    private void CloseGap()
+    {
+        if (_gapStartFrame is not { } gapStart)
+            return;
+        _gaps.Add(new SkippedGap(
+            StartSecs: (double)gapStart / _sampleRate,
+            FileSecs: (double)_writtenFrames / _sampleRate,
+            DurationSecs: (double)(_timelineFrames - gapStart) / _sampleRate));
+        _gapStartFrame = null;
+    }
+
+    private static float Rms(ReadOnlySpan<float> block)
+    {
+        double sumSquares = 0;
+        foreach (var sample in block)
+            sumSquares += sample * sample;
+        return block.IsEmpty ? 0f : (float)Math.Sqrt(sumSquares / block.Length);
+    }
+}
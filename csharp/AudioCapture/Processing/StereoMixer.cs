@@ -1,3 +1,4 @@
+using System.Buffers.Binary;
 using AudioCapture.Models;
 
 namespace AudioCapture.Processing;
@@ -13,35 +14,47 @@ public sealed class StereoMixer
     /// <param name="mic">Mono mic samples (Float32).</param>
     /// <param name="system">Interleaved stereo system samples [L0, R0, L1, R1, ...].</param>
     /// <param name="strategy">Mixing strategy.</param>
+    /// <param name="placement">Where a blended mix puts the mic; ignored by Separated.</param>
     /// <returns>Interleaved stereo Float32 samples.</returns>
-    public float[] Mix(float[] mic, float[] system, MixingStrategy strategy)
+    public float[] Mix(float[] mic, float[] system, MixingStrategy strategy, MicPlacement placement = MicPlacement.Center)
     {
         return strategy switch
         {
             MixingStrategy.Separated => MixSeparated(mic, system),
-            _ => MixBlended(mic, system),
+            _ => MixBlended(mic, system, placement),
         };
     }
 
     /// <summary>
-    /// Blended: L = mic + sys_L, R = mic + sys_R
+    /// As <see cref="Mix"/>, but writes into <paramref name="destination"/>, growing it
+    /// only when it is too small. Returns the number of samples written. Lets a
+    /// long-running session reuse one scratch buffer instead of allocating per cycle.
     /// </summary>
-    public float[] MixBlended(float[] mic, float[] system)
+    public int MixInto(
+        ReadOnlySpan<float> mic, ReadOnlySpan<float> system, MixingStrategy strategy, ref float[] destination,
+        MicPlacement placement = MicPlacement.Center)
     {
-        int micFrames = mic.Length;
-        int systemFrames = system.Length / 2;
-        int frameCount = Math.Max(micFrames, systemFrames);
+        int frameCount = Math.Max(mic.Length, system.Length / 2);
+        EnsureCapacity(ref destination, frameCount * 2);
+        var output = destination.AsSpan(0, frameCount * 2);
+        if (strategy == MixingStrategy.Separated)
+            MixSeparated(mic, system, output);
+        else
+            MixBlended(mic, system, output, placement);
+        return output.Length;
+    }
+
+    /// <summary>
+    /// Blended: L = mic + sys_L, R = mic + sys_R, with the mic on one side only
+    /// when <paramref name="placement"/> says so.
+    /// </summary>
+    public float[] MixBlended(float[] mic, float[] system, MicPlacement placement = MicPlacement.Center)
+    {
+        int frameCount = Math.Max(mic.Length, system.Length / 2);
         if (frameCount == 0) return [];
 
         var stereo = new float[frameCount * 2];
-        for (int i = 0; i < frameCount; i++)
-        {
-            float micSample = i < micFrames ? mic[i] : 0f;
-            float sysL = (i * 2) < system.Length ? system[i * 2] : 0f;
-            float sysR = (i * 2 + 1) < system.Length ? system[i * 2 + 1] : 0f;
-            stereo[i * 2] = micSample + sysL;
-            stereo[i * 2 + 1] = micSample + sysR;
-        }
+        MixBlended(mic, system, stereo, placement);
         return stereo;
     }
 
@@ -50,20 +63,68 @@ public sealed class StereoMixer
     /// </summary>
     public float[] MixSeparated(float[] mic, float[] system)
     {
-        int micFrames = mic.Length;
-        int systemFrames = system.Length / 2;
-        int frameCount = Math.Max(micFrames, systemFrames);
+        int frameCount = Math.Max(mic.Length, system.Length / 2);
         if (frameCount == 0) return [];
 
         var stereo = new float[frameCount * 2];
-        for (int i = 0; i < frameCount; i++)
+        MixSeparated(mic, system, stereo);
+        return stereo;
+    }
+
+    /// <summary>
+    /// Interleaves two mono channels into stereo [L0, R0, L1, R1, ...], padding the
+    /// shorter one with silence.
+    /// </summary>
+    public float[] Interleave(float[] left, float[] right) => InterleaveStereo(left, right);
+
+    /// <summary>
+    /// Splits interleaved samples into one array per channel — the planar layout
+    /// most ML and DSP libraries expect. A trailing partial frame is padded with
+    /// silence, as <see cref="Interleave"/> pads a short channel.
+    /// </summary>
+    public static float[][] Deinterleave(ReadOnlySpan<float> samples, int channels)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(channels);
+        var frames = (samples.Length + channels - 1) / channels;
+        var planes = new float[channels][];
+        for (var c = 0; c < channels; c++)
         {
-            stereo[i * 2] = i < micFrames ? mic[i] : 0f;
-            float sysL = (i * 2) < system.Length ? system[i * 2] : 0f;
-            float sysR = (i * 2 + 1) < system.Length ? system[i * 2 + 1] : 0f;
-            stereo[i * 2 + 1] = (sysL + sysR) / 2f;
+            var plane = new float[frames];
+            for (var i = 0; i < frames; i++)
+            {
+                var index = i * channels + c;
+                plane[i] = index < samples.Length ? samples[index] : 0f;
+            }
+            planes[c] = plane;
         }
-        return stereo;
+        return planes;
+    }
+
+    /// <summary>
+    /// Interleaves one array per channel into a single buffer, the inverse of
+    /// <see cref="Deinterleave"/>. Two channels go through <see cref="Interleave"/>;
+    /// for any count, channels shorter than the longest are padded with silence.
+    /// </summary>
+    public static float[] InterleavePlanar(IReadOnlyList<float[]> planes)
+    {
+        ArgumentNullException.ThrowIfNull(planes);
+        var channels = planes.Count;
+        if (channels == 2)
+            return InterleaveStereo(planes[0], planes[1]);
+
+        var frames = 0;
+        foreach (var plane in planes)
+            frames = Math.Max(frames, plane.Length);
+        if (frames == 0) return [];
+
+        var interleaved = new float[frames * channels];
+        for (var c = 0; c < channels; c++)
+        {
+            var plane = planes[c];
+            for (var i = 0; i < plane.Length; i++)
+                interleaved[i * channels + c] = plane[i];
+        }
+        return interleaved;
     }
 
     /// <summary>
@@ -72,6 +133,189 @@ public sealed class StereoMixer
     public static byte[] ConvertToInt16Pcm(float[] samples)
     {
         var data = new byte[samples.Length * 2];
+        ConvertToInt16Pcm(samples, data);
+        return data;
+    }
+
+    /// <summary>
+    /// As <see cref="ConvertToInt16Pcm(float[])"/>, but into a reusable buffer, grown
+    /// only when too small. Returns the number of bytes written.
+    /// </summary>
+    public static int ConvertToInt16PcmInto(ReadOnlySpan<float> samples, ref byte[] destination)
+    {
+        EnsureCapacity(ref destination, samples.Length * 2);
+        ConvertToInt16Pcm(samples, destination);
+        return samples.Length * 2;
+    }
+
+    /// <summary>
+    /// Copies interleaved Float32 samples as 32-bit little-endian IEEE float PCM into
+    /// a reusable buffer, grown only when too small — unclamped, bit for bit. Returns
+    /// the number of bytes written.
+    /// </summary>
+    public static int ConvertToFloat32PcmInto(ReadOnlySpan<float> samples, ref byte[] destination)
+    {
+        EnsureCapacity(ref destination, samples.Length * 4);
+        for (var i = 0; i < samples.Length; i++)
+            BinaryPrimitives.WriteSingleLittleEndian(destination.AsSpan(i * 4), samples[i]);
+        return samples.Length * 4;
+    }
+
+    /// <summary>
+    /// Samples beyond full scale: the ones <see cref="ConvertToInt16Pcm(float[])"/>
+    /// clamps, and that a float file carries past what any fixed-point copy can hold.
+    /// One comparison per sample, cheap enough for every mix cycle.
+    /// </summary>
+    public static int CountClipped(ReadOnlySpan<float> samples)
+    {
+        var clipped = 0;
+        foreach (var sample in samples)
+        {
+            if (Math.Abs(sample) > 1f) clipped++;
+        }
+        return clipped;
+    }
+
+    /// <summary>
+    /// Correlation between the left and right channels of interleaved stereo, in
+    /// [-1, 1]: +1 when both carry the same signal (mono), around 0 for unrelated
+    /// channels or a wide image, -1 when one is the other inverted — the case that
+    /// cancels when summed to mono. Silence, with no image to judge, reads 0.
+    /// </summary>
+    public static float StereoCorrelation(ReadOnlySpan<float> stereo)
+    {
+        double sumLR = 0, sumLL = 0, sumRR = 0;
+        for (var i = 0; i + 1 < stereo.Length; i += 2)
+        {
+            double left = stereo[i], right = stereo[i + 1];
+            sumLR += left * right;
+            sumLL += left * left;
+            sumRR += right * right;
+        }
+        var energy = Math.Sqrt(sumLL * sumRR);
+        return energy > 0 ? (float)Math.Clamp(sumLR / energy, -1, 1) : 0f;
+    }
+
+    /// <summary>
+    /// Highest absolute level of <paramref name="samples"/> once oversampled 4x — the
+    /// true peak, which catches the inter-sample overs a sample peak misses and a
+    /// DAC's reconstruction filter turns into clipping. Interleaved channels are
+    /// measured separately. Each call stands alone, so within a few frames of either
+    /// end — where the interpolator would run off the buffer — only sample peaks count.
+    /// </summary>
+    public static float TruePeakLevel(ReadOnlySpan<float> samples, int channels = 1)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(channels);
+        var frames = samples.Length / channels;
+        float peak = 0;
+        for (int ch = 0; ch < channels; ch++)
+        {
+            for (int i = 0; i < frames; i++)
+            {
+                // Phase 0 is the sample itself; the others interpolate toward i + 1.
+                peak = Math.Max(peak, Math.Abs(samples[i * channels + ch]));
+                var first = i - (TruePeakTaps / 2 - 1);
+                if (first < 0 || first + TruePeakTaps > frames)
+                    continue;
+
+                for (int phase = 0; phase < TruePeakPhases.Length; phase++)
+                {
+                    var taps = TruePeakPhases[phase];
+                    float sum = 0;
+                    for (int k = 0; k < taps.Length; k++)
+                        sum += samples[(first + k) * channels + ch] * taps[k];
+                    peak = Math.Max(peak, Math.Abs(sum));
+                }
+            }
+        }
+        return peak;
+    }
+
+    // Taps per phase of the true-peak interpolator: 12, as in ITU-R BS.1770's
+    // 48-tap, 4-phase example filter.
+    private const int TruePeakTaps = 12;
+
+    // The three fractional phases (1/4, 2/4, 3/4) of a Hann-windowed sinc, each
+    // normalized to unity gain at DC. TruePeakPhases[p][k] weights frame
+    // i + k - 5 for the point (p + 1) / 4 past frame i.
+    private static readonly float[][] TruePeakPhases = BuildTruePeakPhases();
+
+    private static float[][] BuildTruePeakPhases()
+    {
+        var phases = new float[3][];
+        for (int p = 0; p < phases.Length; p++)
+        {
+            var offset = (p + 1) / 4.0;
+            var taps = new double[TruePeakTaps];
+            for (int k = 0; k < TruePeakTaps; k++)
+            {
+                var t = k - (TruePeakTaps / 2 - 1) - offset;
+                var sinc = Math.Sin(Math.PI * t) / (Math.PI * t);
+                var window = 0.5 * (1 + Math.Cos(Math.PI * t / (TruePeakTaps / 2)));
+                taps[k] = sinc * window;
+            }
+            var sum = taps.Sum();
+            phases[p] = taps.Select(tap => (float)(tap / sum)).ToArray();
+        }
+        return phases;
+    }
+
+    /// <summary>
+    /// Grows <paramref name="buffer"/> to hold at least <paramref name="length"/> items.
+    /// Existing contents are not preserved — callers overwrite what they use.
+    /// </summary>
+    internal static void EnsureCapacity<T>(ref T[] buffer, int length)
+    {
+        if (buffer.Length < length)
+            buffer = new T[length];
+    }
+
+    // Output is exactly max(micFrames, systemFrames) * 2 samples; the shorter input
+    // is zero-padded.
+    private static void MixBlended(
+        ReadOnlySpan<float> mic, ReadOnlySpan<float> system, Span<float> stereo, MicPlacement placement)
+    {
+        float micLeft = placement == MicPlacement.Right ? 0f : 1f;
+        float micRight = placement == MicPlacement.Left ? 0f : 1f;
+        int frameCount = stereo.Length / 2;
+        for (int i = 0; i < frameCount; i++)
+        {
+            float micSample = i < mic.Length ? mic[i] : 0f;
+            float sysL = (i * 2) < system.Length ? system[i * 2] : 0f;
+            float sysR = (i * 2 + 1) < system.Length ? system[i * 2 + 1] : 0f;
+            stereo[i * 2] = micSample * micLeft + sysL;
+            stereo[i * 2 + 1] = micSample * micRight + sysR;
+        }
+    }
+
+    private static void MixSeparated(ReadOnlySpan<float> mic, ReadOnlySpan<float> system, Span<float> stereo)
+    {
+        int frameCount = stereo.Length / 2;
+        for (int i = 0; i < frameCount; i++)
+        {
+            stereo[i * 2] = i < mic.Length ? mic[i] : 0f;
+            float sysL = (i * 2) < system.Length ? system[i * 2] : 0f;
+            float sysR = (i * 2 + 1) < system.Length ? system[i * 2 + 1] : 0f;
+            stereo[i * 2 + 1] = (sysL + sysR) / 2f;
+        }
+    }
+
+    private static float[] InterleaveStereo(float[] left, float[] right)
+    {
+        int frameCount = Math.Max(left.Length, right.Length);
+        if (frameCount == 0) return [];
+
+        var stereo = new float[frameCount * 2];
+        for (int i = 0; i < frameCount; i++)
+        {
+            stereo[i * 2] = i < left.Length ? left[i] : 0f;
+            stereo[i * 2 + 1] = i < right.Length ? right[i] : 0f;
+        }
+        return stereo;
+    }
+
+    private static void ConvertToInt16Pcm(ReadOnlySpan<float> samples, Span<byte> data)
+    {
         for (int i = 0; i < samples.Length; i++)
         {
             float clamped = Math.Clamp(samples[i], -1f, 1f);
@@ -79,6 +323,5 @@ public sealed class StereoMixer
             data[i * 2] = (byte)(int16 & 0xFF);
             data[i * 2 + 1] = (byte)((int16 >> 8) & 0xFF);
         }
-        return data;
     }
 }
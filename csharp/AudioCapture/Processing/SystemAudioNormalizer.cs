@@ -1,5 +1,4 @@
-using NAudio.Wave;
-using NAudio.Wave.SampleProviders;
+using AudioCapture.Interfaces;
 
 namespace AudioCapture.Processing;
 
@@ -34,8 +33,7 @@ public sealed class SystemAudioNormalizer
     /// <summary>The channel count every consumer downstream expects.</summary>
     public const int TargetChannels = 2;
 
-    private readonly PushSampleProvider? _pushed;
-    private readonly ISampleProvider? _resampler;
+    private readonly IAudioResampler? _resampler;
 
     public int SourceSampleRate { get; }
     public int SourceChannels { get; }
@@ -47,7 +45,9 @@ public sealed class SystemAudioNormalizer
     /// <param name="sourceSampleRate">The endpoint's mix-format rate.</param>
     /// <param name="sourceChannels">The endpoint's mix-format channel count.</param>
     /// <param name="targetSampleRate">The configured capture rate the sidecar will be stamped with.</param>
-    public SystemAudioNormalizer(int sourceSampleRate, int sourceChannels, int targetSampleRate)
+    /// <param name="resampler">Converts the rate when it differs; a <see cref="WdlAudioResampler"/> when null.</param>
+    public SystemAudioNormalizer(
+        int sourceSampleRate, int sourceChannels, int targetSampleRate, IAudioResampler? resampler = null)
     {
         ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sourceSampleRate);
         ArgumentOutOfRangeException.ThrowIfNegativeOrZero(sourceChannels);
@@ -61,9 +61,7 @@ public sealed class SystemAudioNormalizer
         {
             // Channels are folded before the resampler runs, so it only ever sees
             // stereo — fewer samples to filter, and one less shape to reason about.
-            _pushed = new PushSampleProvider(
-                WaveFormat.CreateIeeeFloatWaveFormat(sourceSampleRate, TargetChannels));
-            _resampler = new WdlResamplingSampleProvider(_pushed, targetSampleRate);
+            _resampler = resampler ?? new WdlAudioResampler();
         }
     }
 
@@ -84,9 +82,7 @@ public sealed class SystemAudioNormalizer
 
         var stereo = FoldToStereo(interleaved);
         if (_resampler is null) return stereo;
-
-        _pushed!.Push(stereo);
-        return Drain(stereo.Length);
+        return _resampler.Process(stereo, TargetChannels, SourceSampleRate, TargetSampleRate);
     }
 
     // --- Private helpers ---
@@ -123,55 +119,4 @@ public sealed class SystemAudioNormalizer
         }
         return stereo;
     }
-
-    /// <summary>
-    /// Pulls everything the resampler can currently produce.
-    /// </summary>
-    private float[] Drain(int pushedSamples)
-    {
-        // Enough for the rate-converted chunk plus the resampler's carried frames,
-        // so the common case drains in a single read.
-        var estimate = (int)(pushedSamples * ((double)TargetSampleRate / SourceSampleRate));
-        var buffer = new float[Math.Max(estimate + (TargetChannels * 64), 1024)];
-
-        var read = _resampler!.Read(buffer, 0, buffer.Length);
-        if (read < buffer.Length)
-            return buffer.AsSpan(0, read).ToArray();
-
-        // Filled the buffer — there may be more waiting.
-        var output = new List<float>(buffer);
-        while ((read = _resampler.Read(buffer, 0, buffer.Length)) > 0)
-        {
-            output.AddRange(buffer.AsSpan(0, read));
-            if (read < buffer.Length) break;
-        }
-        return [.. output];
-    }
-
-    /// <summary>
-    /// An <see cref="ISampleProvider"/> fed by pushed buffers instead of pulling
-    /// from a source. Returns short (or empty) reads when drained rather than
-    /// padding with silence — padding would splice fake frames into the timeline
-    /// every time a chunk boundary fell mid-read.
-    /// </summary>
-    private sealed class PushSampleProvider(WaveFormat waveFormat) : ISampleProvider
-    {
-        private readonly Queue<float> _queue = new();
-
-        public WaveFormat WaveFormat { get; } = waveFormat;
-
-        public void Push(float[] samples)
-        {
-            foreach (var sample in samples)
-                _queue.Enqueue(sample);
-        }
-
-        public int Read(float[] buffer, int offset, int count)
-        {
-            int written = 0;
-            while (written < count && _queue.Count > 0)
-                buffer[offset + written++] = _queue.Dequeue();
-            return written;
-        }
-    }
 }
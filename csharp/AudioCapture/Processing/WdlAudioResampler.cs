@@ -0,0 +1,149 @@
+using AudioCapture.Interfaces;
+using NAudio.Dsp;
+using NAudio.Wave;
+
+namespace AudioCapture.Processing;
+
+/// <summary>
+/// The default <see cref="IAudioResampler"/>: NAudio's WDL resampler in windowed-sinc
+/// mode, fed chunk by chunk through a <see cref="PushSampleProvider"/>.
+/// </summary>
+/// <remarks>
+/// NAudio's own <c>WdlResamplingSampleProvider</c> runs WDL with linear
+/// interpolation behind a pair of IIR low-pass stages. That is fine between 44.1
+/// and 48 kHz, but a steep downsample — 48 kHz to 8 kHz for telephony — lets
+/// content above the new Nyquist fold back into the band as audible aliases. The
+/// sinc filter's cutoff follows the output rate and rejects it.
+///
+/// <para>The filter bank — 256 taps, oversampled for fractional positions — is
+/// built on the first chunk at a given pair of rates, and per-chunk work after that
+/// is only the convolution. A source that changes rate, a Bluetooth headset
+/// dropping to its 16 kHz hands-free profile and back, would otherwise rebuild it at
+/// every switch, so the banks of the last few formats are kept and reused with
+/// their filter state cleared.</para>
+/// </remarks>
+public sealed class WdlAudioResampler : IAudioResampler
+{
+    // Formats whose filter banks are kept for a switch back, most recent first.
+    private const int CachedFormats = 4;
+
+    private readonly List<SincResamplingProvider> _cache = [];
+    private SincResamplingProvider? _resampler;
+
+    public float[] Process(float[] input, int channels, int inputRate, int outputRate)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(channels);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(inputRate);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(outputRate);
+        if (input.Length == 0) return [];
+        if (inputRate == outputRate) return input;
+
+        // The filter state belongs to one format; a new one starts afresh.
+        if (_resampler == null || _resampler.Format != (channels, inputRate, outputRate))
+            _resampler = SwitchTo(channels, inputRate, outputRate);
+
+        _resampler.Push(input);
+        return Drain(input.Length, channels, inputRate, outputRate);
+    }
+
+    /// <summary>
+    /// The resampler for a new format, reset if its filter bank is cached, built
+    /// otherwise — evicting the least recently used bank once
+    /// <see cref="CachedFormats"/> are held.
+    /// </summary>
+    private SincResamplingProvider SwitchTo(int channels, int inputRate, int outputRate)
+    {
+        var index = _cache.FindIndex(cached => cached.Format == (channels, inputRate, outputRate));
+        SincResamplingProvider resampler;
+        if (index >= 0)
+        {
+            resampler = _cache[index];
+            _cache.RemoveAt(index);
+            resampler.Restart();
+        }
+        else
+        {
+            resampler = new SincResamplingProvider(channels, inputRate, outputRate);
+            if (_cache.Count == CachedFormats)
+                _cache.RemoveAt(_cache.Count - 1);
+        }
+        _cache.Insert(0, resampler);
+        return resampler;
+    }
+
+    /// <summary>
+    /// Pulls everything the resampler can currently produce.
+    /// </summary>
+    private float[] Drain(int pushedSamples, int channels, int inputRate, int outputRate)
+    {
+        // Enough for the rate-converted chunk plus the resampler's carried frames,
+        // so the common case drains in a single read.
+        var estimate = (int)(pushedSamples * ((double)outputRate / inputRate));
+        var buffer = new float[Math.Max(estimate + (channels * 64), 1024)];
+
+        var read = _resampler!.Read(buffer, 0, buffer.Length);
+        if (read < buffer.Length)
+            return buffer.AsSpan(0, read).ToArray();
+
+        // Filled the buffer — there may be more waiting.
+        var output = new List<float>(buffer);
+        while ((read = _resampler.Read(buffer, 0, buffer.Length)) > 0)
+        {
+            output.AddRange(buffer.AsSpan(0, read));
+            if (read < buffer.Length) break;
+        }
+        return [.. output];
+    }
+
+    /// <summary>
+    /// Pushed chunks resampled to <paramref name="outputRate"/> by WDL's windowed
+    /// sinc — NAudio's <c>WdlResamplingSampleProvider</c> in every other respect,
+    /// output-driven and reading only what has been pushed.
+    /// </summary>
+    private sealed class SincResamplingProvider : ISampleProvider
+    {
+        private readonly PushSampleProvider _source;
+        private const int SincTaps = 256;
+
+        private readonly WdlResampler _resampler = new();
+
+        public SincResamplingProvider(int channels, int inputRate, int outputRate)
+        {
+            _source = new PushSampleProvider(WaveFormat.CreateIeeeFloatWaveFormat(inputRate, channels));
+            Format = (channels, inputRate, outputRate);
+            WaveFormat = WaveFormat.CreateIeeeFloatWaveFormat(outputRate, channels);
+            // Interpolated sinc, no IIR stages. 256 taps rather than WDL's default 64
+            // keep the transition band narrow enough that a 6x downsample still
+            // rejects everything a little above the new Nyquist.
+            _resampler.SetMode(true, 0, true, SincTaps);
+            _resampler.SetFeedMode(false);
+            _resampler.SetRates(inputRate, outputRate);
+        }
+
+        public (int Channels, int InputRate, int OutputRate) Format { get; }
+
+        public WaveFormat WaveFormat { get; }
+
+        public void Push(float[] samples) => _source.Push(samples);
+
+        /// <summary>
+        /// Starts the stream over: drops pushed input and the carried filter history,
+        /// but keeps the filter bank, which depends on the rates alone.
+        /// </summary>
+        public void Restart()
+        {
+            _source.Clear();
+            _resampler.Reset();
+        }
+
+        public int Read(float[] buffer, int offset, int count)
+        {
+            var channels = WaveFormat.Channels;
+            var framesRequested = count / channels;
+            var inNeeded = _resampler.ResamplePrepare(framesRequested, channels, out var inBuffer, out var inBufferOffset);
+            var inAvailable = _source.Read(inBuffer, inBufferOffset, inNeeded * channels) / channels;
+            return _resampler.ResampleOut(buffer, offset, inAvailable, framesRequested, channels) * channels;
+        }
+    }
+}
@@ -33,9 +33,13 @@ public sealed class AesGcmEncryptor : ICaptureEncryptor, IDisposable
 
     public string Algorithm => "AES-256-GCM";
 
+    public int Overhead => NonceSize + TagSize;
+
     public Dictionary<string, string> KeyMetadata => new(_keyMetadata);
 
-    public byte[] Encrypt(byte[] data)
+    public byte[] Encrypt(byte[] data) => Encrypt(data, []);
+
+    public byte[] Encrypt(byte[] data, byte[] associatedData)
     {
         var nonce = new byte[NonceSize];
         RandomNumberGenerator.Fill(nonce);
@@ -44,7 +48,7 @@ public sealed class AesGcmEncryptor : ICaptureEncryptor, IDisposable
         var tag = new byte[TagSize];
 
         using var aes = new AesGcm(_key, TagSize);
-        aes.Encrypt(nonce, data, ciphertext, tag);
+        aes.Encrypt(nonce, data, ciphertext, tag, associatedData);
 
         // Combined format: nonce || ciphertext || tag (matches Swift AES.GCM.SealedBox.combined)
         var combined = new byte[NonceSize + ciphertext.Length + TagSize];
@@ -58,7 +62,13 @@ public sealed class AesGcmEncryptor : ICaptureEncryptor, IDisposable
     /// <summary>
     /// Decrypts data in the combined format: [12-byte nonce] [ciphertext] [16-byte tag].
     /// </summary>
-    public byte[] Decrypt(byte[] combined)
+    public byte[] Decrypt(byte[] combined) => Decrypt(combined, []);
+
+    /// <summary>
+    /// Decrypts data sealed with <paramref name="associatedData"/>; throws
+    /// <see cref="AuthenticationTagMismatchException"/> unless it is the same.
+    /// </summary>
+    public byte[] Decrypt(byte[] combined, byte[] associatedData)
     {
         if (combined.Length < NonceSize + TagSize)
             throw new ArgumentException("Data too short to contain nonce and tag.");
@@ -74,7 +84,7 @@ public sealed class AesGcmEncryptor : ICaptureEncryptor, IDisposable
 
         var plaintext = new byte[ciphertextLength];
         using var aes = new AesGcm(_key, TagSize);
-        aes.Decrypt(nonce, ciphertext, tag, plaintext);
+        aes.Decrypt(nonce, ciphertext, tag, plaintext, associatedData);
 
         return plaintext;
     }
@@ -0,0 +1,185 @@
+using System.Buffers.Binary;
+using System.Security.Cryptography;
+using AudioCapture.Models;
+
+namespace AudioCapture.Storage;
+
+/// <summary>
+/// Reads back what <see cref="EncryptedWavWriter"/> sealed: walks the data chunk's
+/// <c>[4-byte LE length][sealed box]</c> frames and decrypts them in order.
+/// </summary>
+public static class EncryptedWavReader
+{
+    /// <summary>
+    /// Decrypts an <c>.enc.wav</c>'s data chunk and returns the PCM it holds.
+    /// </summary>
+    /// <param name="sequenceBound">
+    /// The file was written with <see cref="CaptureConfiguration.SequenceBoundChunks"/>:
+    /// each frame must then be the one its position says, which catches frames
+    /// dropped, duplicated or reordered anywhere but at the very end. A frame taken
+    /// from another recording under the same key at the same index still passes; pair
+    /// with <see cref="VerifyManifest"/> to catch that and a cut-off end.
+    /// </param>
+    /// <exception cref="CaptureException">
+    /// EncryptionFailed if a frame fails to authenticate or the last one is torn;
+    /// StorageError if the file can't be read.
+    /// </exception>
+    public static byte[] ReadPcm(string filePath, AesGcmEncryptor decryptor, bool sequenceBound = false)
+    {
+        ArgumentNullException.ThrowIfNull(decryptor);
+
+        using var pcm = new MemoryStream();
+        long index = 0;
+        foreach (var frame in ReadFrames(filePath))
+        {
+            try
+            {
+                var box = frame[4..];
+                pcm.Write(sequenceBound ? decryptor.Decrypt(box, SequenceAad(index)) : decryptor.Decrypt(box));
+            }
+            catch (CryptographicException)
+            {
+                throw CaptureException.EncryptionFailed(sequenceBound
+                    ? $"Chunk {index} failed to authenticate: it was altered, or is out of sequence"
+                    : $"Chunk {index} failed to authenticate: wrong key, or it was altered");
+            }
+            index++;
+        }
+        return pcm.ToArray();
+    }
+
+    /// <summary>
+    /// Whether the file's sealed frames are exactly those <paramref name="manifest"/>
+    /// describes — the same count, chained to the same hash. Needs no key.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError if the file can't be read.</exception>
+    public static bool VerifyManifest(string filePath, ChunkManifest manifest)
+    {
+        ArgumentNullException.ThrowIfNull(manifest);
+
+        var chain = new byte[32];
+        long count = 0;
+        try
+        {
+            foreach (var frame in ReadFrames(filePath))
+            {
+                chain = ChainHash(chain, frame);
+                count++;
+            }
+        }
+        catch (CaptureException ex) when (ex.ErrorKind == CaptureErrorKind.EncryptionFailed)
+        {
+            return false; // a torn frame
+        }
+        return count == manifest.ChunkCount
+            && string.Equals(Convert.ToHexStringLower(chain), manifest.RollingHash, StringComparison.OrdinalIgnoreCase);
+    }
+
+    /// <summary>
+    /// Checks an <c>.enc.wav</c> is structurally whole without the key — before an
+    /// upload, say: every frame's length prefix is followed by that many bytes, none
+    /// is empty, and the header's data size covers exactly the frames present. Says
+    /// nothing about whether the frames authenticate; that takes
+    /// <see cref="ReadPcm"/> with the key, or <see cref="VerifyManifest"/>.
+    /// </summary>
+    /// <exception cref="CaptureException">
+    /// EncryptionFailed if a frame is torn or empty, or the header disagrees with the
+    /// data — as it does for a recording never closed; StorageError if the file can't
+    /// be read.
+    /// </exception>
+    public static EncryptedFileStats VerifyStructure(string filePath)
+    {
+        long count = 0;
+        long ciphertext = 0;
+        foreach (var frame in ReadFrames(filePath))
+        {
+            if (frame.Length == 4)
+                throw CaptureException.EncryptionFailed(
+                    $"Chunk {count} of {Path.GetFileName(filePath)} is empty; no sealed box is");
+            ciphertext += frame.Length - 4;
+            count++;
+        }
+
+        var (declared, present) = MeasureDataChunk(filePath);
+        if (declared != present)
+            throw CaptureException.EncryptionFailed(declared == 0
+                ? $"{Path.GetFileName(filePath)} was never closed: its header declares no data"
+                : $"{Path.GetFileName(filePath)} declares {declared} bytes of data but holds {present}");
+        return new EncryptedFileStats(count, ciphertext);
+    }
+
+    /// <summary>
+    /// The associated data binding a frame to <paramref name="index"/>: the index as 8 LE
+    /// bytes. Nothing ties it to the recording, so the same index under the same key matches.
+    /// </summary>
+    internal static byte[] SequenceAad(long index)
+    {
+        var aad = new byte[8];
+        BinaryPrimitives.WriteInt64LittleEndian(aad, index);
+        return aad;
+    }
+
+    /// <summary>The next link of a <see cref="ChunkManifest.RollingHash"/>.</summary>
+    internal static byte[] ChainHash(byte[] previous, ReadOnlySpan<byte> frame)
+    {
+        using var hash = IncrementalHash.CreateHash(HashAlgorithmName.SHA256);
+        hash.AppendData(previous);
+        hash.AppendData(frame);
+        return hash.GetHashAndReset();
+    }
+
+    /// <summary>Each frame of the data chunk, length prefix included.</summary>
+    private static IEnumerable<byte[]> ReadFrames(string filePath)
+    {
+        FileStream stream;
+        try
+        {
+            stream = File.OpenRead(filePath);
+            EncryptedWavWriter.SeekToData(stream);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            throw CaptureException.StorageError($"Cannot read {Path.GetFileName(filePath)}: {ex.Message}");
+        }
+
+        using (stream)
+        {
+            var prefix = new byte[4];
+            while (stream.Position < stream.Length)
+            {
+                if (stream.Length - stream.Position < 4)
+                    throw Torn(filePath);
+                stream.ReadExactly(prefix);
+                var length = BinaryPrimitives.ReadUInt32LittleEndian(prefix);
+                if (length > stream.Length - stream.Position)
+                    throw Torn(filePath);
+
+                var frame = new byte[4 + length];
+                prefix.CopyTo(frame, 0);
+                stream.ReadExactly(frame, 4, (int)length);
+                yield return frame;
+            }
+        }
+    }
+
+    /// <summary>The data size the header declares, and the bytes actually after it.</summary>
+    private static (long Declared, long Present) MeasureDataChunk(string filePath)
+    {
+        try
+        {
+            using var stream = File.OpenRead(filePath);
+            EncryptedWavWriter.SeekToData(stream);
+            var sizeField = new byte[4];
+            stream.Seek(-4, SeekOrigin.Current);
+            stream.ReadExactly(sizeField);
+            return (BinaryPrimitives.ReadUInt32LittleEndian(sizeField), stream.Length - stream.Position);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            throw CaptureException.StorageError($"Cannot read {Path.GetFileName(filePath)}: {ex.Message}");
+        }
+    }
+
+    private static CaptureException Torn(string filePath) =>
+        CaptureException.EncryptionFailed($"The last chunk of {Path.GetFileName(filePath)} is torn");
+}
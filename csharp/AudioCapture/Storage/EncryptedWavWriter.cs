@@ -1,6 +1,9 @@
+using System.Globalization;
 using System.Security.Cryptography;
+using System.Text;
 using AudioCapture.Interfaces;
 using AudioCapture.Models;
+using AudioCapture.Processing;
 
 namespace AudioCapture.Storage;
 
@@ -10,35 +13,128 @@ namespace AudioCapture.Storage;
 ///   [44-byte WAV header (unencrypted)]
 ///   [Chunk: 4-byte LE length | nonce | ciphertext | tag] ...
 ///
+/// Output wider than stereo gets a 68-byte WAVE_FORMAT_EXTENSIBLE header instead,
+/// since a plain PCM header has no way to say which speaker each channel feeds.
+/// With <see cref="CaptureConfiguration.Bwf"/> set, a fixed 610-byte <c>bext</c>
+/// chunk sits between <c>fmt </c> and <c>data</c>; either way the header stays
+/// unencrypted and the data chunk comes last.
+///
+/// The target is a file path or any writable, seekable <see cref="Stream"/> — a
+/// <see cref="MemoryStream"/> for a short clip that never needs to touch disk.
+///
 /// Thread-safe. A chunk's length prefix and its payload must reach the file as one
 /// unit: a reader that loses frame alignment cannot recover, so a single interleaved
 /// write costs every byte after it, not just the torn chunk.
 /// </summary>
-public sealed class EncryptedWavWriter : IDisposable
+public sealed class EncryptedWavWriter : IOutputSink, IDisposable
 {
-    private readonly string _filePath;
+    // FileStream's own default, kept when WriteCombineBytes is unset.
+    private const int DefaultFileBufferBytes = 4096;
+
+    /// <summary>
+    /// Largest a WAV can be, header included: the RIFF size is 32 bits and counts all
+    /// but the first 8 bytes. A write that would pass it is refused rather than let
+    /// the sizes wrap; there is no RF64 fallback.
+    /// </summary>
+    public const long MaxWavBytes = uint.MaxValue + 8L;
+
+    private readonly string? _filePath;
+    private readonly Stream? _target;
+    private readonly bool _leaveOpen;
     private readonly ICaptureEncryptor? _encryptor;
 
     /// <summary>Serializes access to <see cref="_stream"/> and its framing state.</summary>
     private readonly object _lock = new();
 
-    private FileStream? _stream;
+    private Stream? _stream;
     private long _totalBytesWritten;
+    private int _headerLength;
+    private WavSpec? _spec;
+
+    // Plaintext awaiting a full encryption chunk, when sealing in fixed-size chunks
+    // rather than one frame per Write. _sealChunkBytes is 0 in per-write mode.
+    private int _sealChunkBytes;
+    private byte[] _pending = [];
+    private int _pendingLength;
     private bool _isOpen;
 
+    // With LiveHeader, the header sizes are patched after every write.
+    private bool _liveHeader;
+
+    // With PreallocateFile, the file was extended past the data at open and the
+    // zero tail is cut off at close. Writes go to the data's end, not the file's.
+    private bool _preallocated;
+
+    // Streaming checksum over the data chunk, fed as it's written; null in File mode.
+    private IncrementalHash? _payloadHash;
+
+    // With SequenceBoundChunks: frames sealed so far, each bound to its index, and
+    // the manifest's hash chain through them.
+    private bool _sequenceBound;
+    private long _chunkCount;
+    private byte[] _rollingHash = [];
+    private ChunkManifest? _manifest;
+
+    /// <summary>
+    /// Writes to a file, created at <see cref="Open"/> or reopened at
+    /// <see cref="OpenAppend"/>. Otherwise identical to the stream form — the path
+    /// only says where the stream comes from.
+    /// </summary>
     public EncryptedWavWriter(string filePath, ICaptureEncryptor? encryptor = null)
     {
         _filePath = filePath;
         _encryptor = encryptor;
     }
 
+    /// <summary>
+    /// Writes into <paramref name="target"/> instead of a file. It must be writable
+    /// and seekable, since the header sizes are patched at close; it must also be
+    /// readable for the default <see cref="ChecksumMode.File"/> checksum.
+    /// </summary>
+    /// <param name="leaveOpen">Keep <paramref name="target"/> open after close, so the caller can read it back.</param>
+    public EncryptedWavWriter(Stream target, ICaptureEncryptor? encryptor = null, bool leaveOpen = false)
+    {
+        ArgumentNullException.ThrowIfNull(target);
+        if (!target.CanWrite || !target.CanSeek)
+            throw new ArgumentException("The target stream must be writable and seekable.", nameof(target));
+
+        _target = target;
+        _leaveOpen = leaveOpen;
+        _encryptor = encryptor;
+    }
+
     public long BytesWritten
     {
         get { lock (_lock) return _totalBytesWritten; }
     }
 
     /// <summary>
-    /// Opens the file and writes a 44-byte WAV header with dataSize=0 (patched on close).
+    /// The format the header on the target declares, read from the header bytes
+    /// themselves once opened. Null before <see cref="Open"/> or
+    /// <see cref="OpenAppend"/>; kept after <see cref="Close"/>.
+    /// </summary>
+    public WavSpec? Spec
+    {
+        get { lock (_lock) return _spec; }
+    }
+
+    /// <summary>
+    /// Frame count and hash chain of a <see cref="CaptureConfiguration.SequenceBoundChunks"/>
+    /// recording, set by <see cref="Close"/>; null otherwise.
+    /// </summary>
+    public ChunkManifest? Manifest
+    {
+        get { lock (_lock) return _manifest; }
+    }
+
+    /// <summary>A file's stream is ours to close; a caller's only when not left open.</summary>
+    private bool OwnsStream => _target == null || !_leaveOpen;
+
+    /// <summary>What error messages call the target.</summary>
+    private string TargetName => _filePath != null ? Path.GetFileName(_filePath) : "the target stream";
+
+    /// <summary>
+    /// Opens the file and writes the WAV header with dataSize=0 (patched on close).
     /// </summary>
     public void Open(CaptureConfiguration config)
     {
@@ -46,20 +142,135 @@ public sealed class EncryptedWavWriter : IDisposable
         {
             if (_isOpen) return;
 
-            var dir = Path.GetDirectoryName(_filePath);
-            if (!string.IsNullOrEmpty(dir))
-                Directory.CreateDirectory(dir);
+            if (_target != null)
+            {
+                if (!_target.CanRead && config.ChecksumMode == ChecksumMode.File)
+                    throw CaptureException.StorageError(
+                        "A write-only target needs ChecksumMode.Streaming; there is nothing to re-read");
+                _target.SetLength(0);
+                _stream = _target;
+            }
+            else
+            {
+                var dir = Path.GetDirectoryName(_filePath);
+                if (!string.IsNullOrEmpty(dir))
+                    Directory.CreateDirectory(dir);
 
-            _stream = new FileStream(_filePath, FileMode.Create, FileAccess.Write, FileShare.None);
+                // Read access too, so the File-mode checksum re-reads through this
+                // same handle rather than reopening by path. Shared for reading, so
+                // an uploader can pick up what Flush left at a pause.
+                _stream = new FileStream(
+                    _filePath!, FileMode.Create, FileAccess.ReadWrite, FileShare.Read,
+                    config.WriteCombineBytes ?? DefaultFileBufferBytes);
+            }
 
-            var header = GenerateWavHeader(
-                sampleRate: (uint)config.SampleRate,
-                bitDepth: (ushort)config.BitDepth,
-                channels: (ushort)config.Channels,
-                dataSize: 0);
+            var header = GenerateFormatHeader(config);
+            if (config.Bwf != null)
+                header = InsertBeforeData(header, GenerateBextChunk(config.Bwf, DateTime.Now, (int)config.OutputSampleRate));
 
             _stream.Write(header);
             _totalBytesWritten = header.Length;
+            _headerLength = header.Length;
+            _preallocated = false;
+            if (_target == null && PreallocationLength(config) is { } length && length > header.Length)
+            {
+                // Extending leaves the position where it is, just past the header.
+                _stream.SetLength(length);
+                _preallocated = true;
+            }
+            _spec = ReadSpec(header);
+            ConfigureSealing(config);
+            _liveHeader = config.LiveHeader;
+            _payloadHash = config.ChecksumMode == ChecksumMode.Streaming
+                ? IncrementalHash.CreateHash(HashAlgorithmName.SHA256)
+                : null;
+            _isOpen = true;
+        }
+    }
+
+    /// <summary>
+    /// Reopens a recording left by an earlier <see cref="Close"/> (or a crash) and
+    /// continues its data chunk, so a stopped session can carry on in the same file.
+    /// A stream target must be readable, since the existing data is measured.
+    /// </summary>
+    /// <remarks>
+    /// The existing header must describe <paramref name="config"/>'s format, and the
+    /// file must be reopened with the encryption setting it was written with. The
+    /// header can't tell the two apart, so that one is on the caller — mixing them
+    /// would leave a file no reader can walk. The header's own sizes
+    /// aren't trusted, since a crash leaves them at 0; the data is measured instead
+    /// and any torn tail — a partial frame, or a length prefix with no payload — is
+    /// cut off so new frames start on a boundary. Plaintext zeros past what the header
+    /// last declared are cut too, but only from a file exactly the length
+    /// <paramref name="config"/> would have preallocated: the unwritten tail of a
+    /// preallocated file left by a crash, or at worst digital silence just before one.
+    /// Resume such a file with the configuration it was recorded with. Any other file
+    /// keeps its zeros, as the silence they are.
+    /// </remarks>
+    /// <exception cref="CaptureException">StorageError if the file is missing or the stream unreadable, not a
+    /// WAV this writer produced, in a different format, or sequence-bound.</exception>
+    public void OpenAppend(CaptureConfiguration config)
+    {
+        lock (_lock)
+        {
+            if (_isOpen) return;
+            if (_encryptor != null && config.SequenceBoundChunks)
+                throw CaptureException.StorageError($"Cannot resume {TargetName}: its chunk sequence can't be continued");
+
+            Stream stream;
+            if (_target != null)
+            {
+                if (!_target.CanRead)
+                    throw CaptureException.StorageError("Cannot resume a write-only target: its data can't be measured");
+                stream = _target;
+            }
+            else
+            {
+                if (!File.Exists(_filePath))
+                    throw CaptureException.StorageError($"Cannot resume {TargetName}: file does not exist");
+                stream = new FileStream(
+                    _filePath!, FileMode.Open, FileAccess.ReadWrite, FileShare.Read,
+                    config.WriteCombineBytes ?? DefaultFileBufferBytes);
+            }
+
+            try
+            {
+                var expected = GenerateFormatHeader(config);
+                // The bext chunk records when the first run started; keep it as it is.
+                if (config.Bwf != null)
+                    expected = InsertBeforeData(expected, ReadExistingBext(stream, expected.Length - 8));
+
+                var dataEnd = MeasureResumableData(stream, expected, PreallocationLength(config));
+                stream.SetLength(dataEnd);
+
+                _payloadHash = null;
+                if (config.ChecksumMode == ChecksumMode.Streaming)
+                {
+                    // The digest has to cover the earlier run too, so it starts from a
+                    // read of what's already there — once, at resume, not at close.
+                    _payloadHash = IncrementalHash.CreateHash(HashAlgorithmName.SHA256);
+                    stream.Seek(expected.Length, SeekOrigin.Begin);
+                    var buffer = new byte[256 * 1024];
+                    int read;
+                    while ((read = stream.Read(buffer, 0, buffer.Length)) > 0)
+                        _payloadHash.AppendData(buffer, 0, read);
+                }
+
+                stream.Seek(0, SeekOrigin.End);
+                _stream = stream;
+                _totalBytesWritten = dataEnd;
+                _headerLength = expected.Length;
+                _preallocated = false;
+                // The file's header matched expected in everything but its sizes.
+                _spec = ReadSpec(expected);
+                ConfigureSealing(config);
+                _liveHeader = config.LiveHeader;
+            }
+            catch
+            {
+                if (_target == null) stream.Dispose();
+                throw;
+            }
             _isOpen = true;
         }
     }
@@ -67,35 +278,63 @@ public sealed class EncryptedWavWriter : IDisposable
     /// <summary>
     /// Writes audio data. If encrypted, writes as [4-byte length][sealed box].
     /// </summary>
-    public void Write(byte[] data)
+    public void Write(byte[] data) => Write(data.AsSpan());
+
+    /// <summary>
+    /// As <see cref="Write(byte[])"/>, for a slice of a reused buffer. Plaintext goes
+    /// straight to the stream; the encryptor still takes its own copy.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError when the file would pass <see cref="MaxWavBytes"/>.</exception>
+    public void Write(ReadOnlySpan<byte> data)
     {
         lock (_lock)
         {
             if (!_isOpen || _stream == null)
                 throw new CaptureException(CaptureErrorKind.StorageError, "File is not open for writing");
 
-            if (_encryptor != null)
+            if (_encryptor != null && _sealChunkBytes > 0)
+            {
+                // Fill the pending chunk and seal each one as it completes, however
+                // the caller happens to slice its writes.
+                while (!data.IsEmpty)
+                {
+                    var take = Math.Min(_sealChunkBytes - _pendingLength, data.Length);
+                    data[..take].CopyTo(_pending.AsSpan(_pendingLength));
+                    _pendingLength += take;
+                    data = data[take..];
+
+                    if (_pendingLength == _sealChunkBytes && !SealPending())
+                        throw SizeLimitError(_sealChunkBytes);
+                }
+            }
+            else if (_encryptor != null)
             {
-                var encrypted = _encryptor.Encrypt(data);
-                var lengthBytes = BitConverter.GetBytes((uint)encrypted.Length);
-                if (!BitConverter.IsLittleEndian)
-                    Array.Reverse(lengthBytes);
-
-                _stream.Write(lengthBytes);
-                _stream.Write(encrypted);
-                _totalBytesWritten += 4 + encrypted.Length;
+                SealFrame(data);
             }
             else
             {
+                EnsureFits(data.Length);
                 _stream.Write(data);
+                _payloadHash?.AppendData(data);
                 _totalBytesWritten += data.Length;
             }
+
+            if (_liveHeader)
+            {
+                // Sealed frames only: plaintext still pending isn't in the file yet.
+                PatchSizes();
+                _stream.Seek(_totalBytesWritten, SeekOrigin.Begin);
+            }
         }
     }
 
     /// <summary>
-    /// Patches the WAV header with correct sizes and computes SHA-256 checksum.
+    /// Patches the WAV header with correct sizes and returns the SHA-256 checksum —
+    /// of the whole file, or of the data chunk alone in
+    /// <see cref="ChecksumMode.Streaming"/> (see there for why).
     /// </summary>
+    /// <exception cref="CaptureException">StorageError when the last, short chunk didn't
+    /// fit under <see cref="MaxWavBytes"/>; the file is still finished and closed.</exception>
     public string Close()
     {
         lock (_lock)
@@ -103,22 +342,63 @@ public sealed class EncryptedWavWriter : IDisposable
             if (!_isOpen || _stream == null)
                 throw new CaptureException(CaptureErrorKind.StorageError, "File is not open");
 
-            var dataSize = _totalBytesWritten - 44;
-
-            // Patch RIFF chunk size at offset 4
-            _stream.Seek(4, SeekOrigin.Begin);
-            WriteUInt32LE(_stream, (uint)(_totalBytesWritten - 8));
+            var pending = _pendingLength;
+            var whole = SealPendingAndPatchSizes();
+            // Before the checksum re-reads the file, so the zeros past the data are in neither.
+            if (_preallocated)
+                _stream.SetLength(_totalBytesWritten);
+            _manifest = _sequenceBound
+                ? new ChunkManifest(_chunkCount, Convert.ToHexStringLower(_rollingHash))
+                : null;
 
-            // Patch data sub-chunk size at offset 40
-            _stream.Seek(40, SeekOrigin.Begin);
-            WriteUInt32LE(_stream, (uint)dataSize);
+            string checksum;
+            if (_payloadHash != null)
+            {
+                checksum = Convert.ToHexStringLower(_payloadHash.GetHashAndReset());
+                _payloadHash.Dispose();
+                _payloadHash = null;
+            }
+            else
+            {
+                // Re-read what was written through the same stream — Open made sure
+                // it's readable — so no path is needed to find it again.
+                _stream.Seek(0, SeekOrigin.Begin);
+                checksum = Convert.ToHexStringLower(SHA256.HashData(_stream));
+            }
 
-            _stream.Flush();
-            _stream.Dispose();
+            if (OwnsStream)
+                _stream.Dispose();
             _stream = null;
             _isOpen = false;
 
-            return ComputeChecksum(_filePath);
+            if (!whole)
+                throw SizeLimitError(pending);
+            return checksum;
+        }
+    }
+
+    /// <summary>
+    /// Makes the target a complete WAV of everything written so far without closing
+    /// it: seals any partial encryption chunk, patches the header sizes and flushes
+    /// to disk. Writing can carry on afterwards — the next Write appends after it.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError when the short chunk didn't fit
+    /// under <see cref="MaxWavBytes"/>; what's written is still made whole.</exception>
+    public void Flush()
+    {
+        lock (_lock)
+        {
+            if (!_isOpen || _stream == null)
+                throw new CaptureException(CaptureErrorKind.StorageError, "File is not open");
+
+            var pending = _pendingLength;
+            var whole = SealPendingAndPatchSizes();
+            _stream.Seek(_totalBytesWritten, SeekOrigin.Begin);
+            if (_stream is FileStream file)
+                file.Flush(flushToDisk: true);
+
+            if (!whole)
+                throw SizeLimitError(pending);
         }
     }
 
@@ -130,16 +410,96 @@ public sealed class EncryptedWavWriter : IDisposable
             {
                 try { Close(); } catch { /* best effort */ }
             }
-            _stream?.Dispose();
+            if (OwnsStream)
+                _stream?.Dispose();
             _stream = null;
+            _payloadHash?.Dispose();
+            _payloadHash = null;
             _isOpen = false;
         }
     }
 
     /// <summary>
-    /// Generates a standard 44-byte WAV header (RIFF/WAVE PCM format).
+    /// Hashes a whole recording file, header included — the digest
+    /// <see cref="ChecksumMode.File"/> produces at close — to re-check a stored or
+    /// transferred recording against its metadata.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError if the file can't be read.</exception>
+    public static string ComputeChecksum(string filePath)
+    {
+        try
+        {
+            using var stream = File.OpenRead(filePath);
+            return Convert.ToHexStringLower(SHA256.HashData(stream));
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            throw CaptureException.StorageError($"Cannot checksum {Path.GetFileName(filePath)}: {ex.Message}");
+        }
+    }
+
+    /// <summary>
+    /// Whether <paramref name="filePath"/> still hashes to <paramref name="expected"/>,
+    /// a checksum from <see cref="Close"/> or the recording's metadata. Pass the
+    /// <see cref="ChecksumMode"/> the recording was made with: the two digests cover
+    /// different bytes. Hex case is ignored.
     /// </summary>
-    public static byte[] GenerateWavHeader(uint sampleRate, ushort bitDepth, ushort channels, uint dataSize)
+    /// <exception cref="CaptureException">StorageError if the file can't be read.</exception>
+    public static bool VerifyChecksum(string filePath, string expected, ChecksumMode mode = ChecksumMode.File)
+    {
+        var actual = mode == ChecksumMode.Streaming
+            ? ComputePayloadChecksum(filePath)
+            : ComputeChecksum(filePath);
+        return string.Equals(actual, expected, StringComparison.OrdinalIgnoreCase);
+    }
+
+    /// <summary>
+    /// Re-reads a finished recording and hashes its data chunk — everything after
+    /// the header, whichever header size it has. This is the digest
+    /// <see cref="ChecksumMode.Streaming"/> produces at close, so it is how such a
+    /// checksum is verified later.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError if the file can't be read.</exception>
+    public static string ComputePayloadChecksum(string filePath)
+    {
+        try
+        {
+            return HashDataChunk(filePath);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            throw CaptureException.StorageError($"Cannot checksum {Path.GetFileName(filePath)}: {ex.Message}");
+        }
+    }
+
+    private static string HashDataChunk(string filePath)
+    {
+        using var stream = File.OpenRead(filePath);
+        SeekToData(stream);
+        return Convert.ToHexStringLower(SHA256.HashData(stream));
+    }
+
+    /// <summary>Positions <paramref name="stream"/> at the first byte of the data chunk's payload.</summary>
+    internal static void SeekToData(Stream stream)
+    {
+        // Walk the chunks after "RIFF....WAVE" to "data": fmt, and bext when present.
+        Span<byte> chunk = stackalloc byte[8];
+        stream.Seek(12, SeekOrigin.Begin);
+        while (true)
+        {
+            stream.ReadExactly(chunk);
+            if (chunk[..4].SequenceEqual("data"u8)) break;
+            var size = BitConverter.ToUInt32(chunk[4..]);
+            stream.Seek(size + (size & 1), SeekOrigin.Current);
+        }
+    }
+
+    /// <summary>
+    /// Generates a standard 44-byte WAV header (RIFF/WAVE PCM format), or IEEE float
+    /// (format code 3) when <paramref name="isFloat"/> is set.
+    /// </summary>
+    public static byte[] GenerateWavHeader(
+        uint sampleRate, ushort bitDepth, ushort channels, uint dataSize, bool isFloat = false)
     {
         var header = new byte[44];
         var byteRate = sampleRate * channels * bitDepth / 8u;
@@ -157,7 +517,7 @@ public sealed class EncryptedWavWriter : IDisposable
         // fmt sub-chunk
         w.Write("fmt "u8);
         w.Write(16u);                // sub-chunk size (PCM)
-        w.Write((ushort)1);          // audio format (PCM)
+        w.Write((ushort)(isFloat ? 3 : 1)); // audio format (PCM or IEEE float)
         w.Write(channels);           // offset 22
         w.Write(sampleRate);         // offset 24
         w.Write(byteRate);           // offset 28
@@ -171,6 +531,377 @@ public sealed class EncryptedWavWriter : IDisposable
         return header;
     }
 
+    /// <summary>
+    /// Generates a 68-byte WAVE_FORMAT_EXTENSIBLE header (integer PCM subformat, or
+    /// IEEE float when <paramref name="isFloat"/> is set), needed for more than two
+    /// channels so players map each one to the right speaker via <paramref name="channelMask"/>.
+    /// </summary>
+    public static byte[] GenerateExtensibleWavHeader(
+        uint sampleRate, ushort bitDepth, ushort channels, uint channelMask, uint dataSize, bool isFloat = false)
+    {
+        var header = new byte[68];
+        var byteRate = sampleRate * channels * bitDepth / 8u;
+        var blockAlign = (ushort)(channels * bitDepth / 8);
+        var chunkSize = 60 + dataSize;
+
+        using var ms = new MemoryStream(header);
+        using var w = new BinaryWriter(ms);
+
+        // RIFF chunk descriptor
+        w.Write("RIFF"u8);
+        w.Write(chunkSize);          // offset 4
+        w.Write("WAVE"u8);
+
+        // fmt sub-chunk
+        w.Write("fmt "u8);
+        w.Write(40u);                // sub-chunk size (extensible)
+        w.Write((ushort)0xFFFE);     // audio format (WAVE_FORMAT_EXTENSIBLE)
+        w.Write(channels);           // offset 22
+        w.Write(sampleRate);         // offset 24
+        w.Write(byteRate);           // offset 28
+        w.Write(blockAlign);         // offset 32
+        w.Write(bitDepth);           // offset 34
+        w.Write((ushort)22);         // cbSize
+        w.Write(bitDepth);           // wValidBitsPerSample
+        w.Write(channelMask);        // dwChannelMask, offset 40
+        w.Write(PcmSubFormat);       // SubFormat GUID, offset 44
+        if (isFloat) header[44] = 3; // KSDATAFORMAT_SUBTYPE_IEEE_FLOAT differs only in the first byte
+
+        // data sub-chunk
+        w.Write("data"u8);
+        w.Write(dataSize);           // offset 64
+
+        return header;
+    }
+
+    /// <summary>
+    /// Generates a version 1 Broadcast Wave <c>bext</c> chunk: the 8-byte chunk header
+    /// and a 602-byte body with no coding history. The time reference counts samples
+    /// at <paramref name="sampleRate"/> since the midnight before <paramref name="origination"/>.
+    /// </summary>
+    public static byte[] GenerateBextChunk(BwfConfiguration bwf, DateTime origination, int sampleRate)
+    {
+        var chunk = new byte[8 + BextBodySize];
+
+        using var ms = new MemoryStream(chunk);
+        using var w = new BinaryWriter(ms);
+
+        w.Write("bext"u8);
+        w.Write((uint)BextBodySize);
+        WriteAscii(w, bwf.Description, 256);
+        WriteAscii(w, bwf.Originator, 32);
+        WriteAscii(w, bwf.OriginatorReference, 32);
+        WriteAscii(w, origination.ToString("yyyy-MM-dd", CultureInfo.InvariantCulture), 10);   // offset 328
+        WriteAscii(w, origination.ToString("HH:mm:ss", CultureInfo.InvariantCulture), 8);      // offset 338
+        w.Write((ulong)(origination.TimeOfDay.Ticks * sampleRate / TimeSpan.TicksPerSecond)); // offset 346
+        w.Write((ushort)1);          // version, offset 354
+        // UMID (64), loudness fields and reserved (190) stay zero.
+
+        return chunk;
+    }
+
+    /// <summary>Body size of a version 1 bext chunk without coding history.</summary>
+    private const int BextBodySize = 602;
+
+    /// <summary>KSDATAFORMAT_SUBTYPE_PCM, 00000001-0000-0010-8000-00aa00389b71, in file byte order.</summary>
+    private static ReadOnlySpan<byte> PcmSubFormat =>
+    [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+        0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+    ];
+
+    /// <summary>
+    /// Checks <paramref name="stream"/> against <paramref name="expectedHeader"/> and returns
+    /// the offset just past its last whole unit of audio — a whole frame when
+    /// encrypted, a whole sample block otherwise. Trailing zeros are taken for
+    /// preallocation only when the file is exactly <paramref name="preallocatedLength"/>
+    /// long. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private long MeasureResumableData(Stream stream, byte[] expectedHeader, long? preallocatedLength)
+    {
+        stream.Seek(0, SeekOrigin.Begin);
+        var header = new byte[expectedHeader.Length];
+        if (stream.Read(header, 0, header.Length) != header.Length)
+            throw CaptureException.StorageError($"Cannot resume {TargetName}: header is truncated");
+
+        // Everything but the two size fields, which are only right after a clean close.
+        var matches = header.AsSpan(8, header.Length - 12).SequenceEqual(expectedHeader.AsSpan(8, header.Length - 12))
+            && header.AsSpan(0, 4).SequenceEqual("RIFF"u8);
+        if (!matches)
+            throw CaptureException.StorageError(
+                $"Cannot resume {TargetName}: its format differs from the configured one");
+
+        var length = stream.Length;
+        if (_encryptor == null)
+        {
+            var blockAlign = BitConverter.ToUInt16(expectedHeader, 32);
+            var end = header.Length + (length - header.Length) / blockAlign * blockAlign;
+            // A preallocated file left by a crash runs on in zeros to its estimated
+            // size. Cut them off, but never below what the header last declared. Any
+            // other length was written, zeros and all: digital silence is audio too.
+            if (length != preallocatedLength)
+                return end;
+            var declared = Math.Min(BitConverter.ToUInt32(header, header.Length - 4), end - header.Length);
+            return TrimZeroTail(stream, header.Length, header.Length + declared / blockAlign * blockAlign, end, blockAlign);
+        }
+
+        // Walk the frame chain; the last frame that fits whole marks the end.
+        Span<byte> prefix = stackalloc byte[4];
+        long offset = header.Length;
+        while (offset + 4 <= length)
+        {
+            stream.Seek(offset, SeekOrigin.Begin);
+            stream.ReadExactly(prefix);
+            var frame = BitConverter.ToUInt32(prefix);
+            if (frame == 0 || offset + 4 + frame > length) break;
+            offset += 4 + frame;
+        }
+        return offset;
+    }
+
+    /// <summary>
+    /// Moves <paramref name="end"/> back over trailing zero bytes, no further than
+    /// <paramref name="floor"/>, keeping whole <paramref name="blockAlign"/> blocks
+    /// counted from <paramref name="dataStart"/>. Reads only the tail it trims, so a
+    /// file that ends in audio costs one read.
+    /// </summary>
+    private static long TrimZeroTail(Stream stream, long dataStart, long floor, long end, int blockAlign)
+    {
+        var buffer = new byte[256 * 1024];
+        while (end > floor)
+        {
+            var count = (int)Math.Min(buffer.Length, end - floor);
+            stream.Seek(end - count, SeekOrigin.Begin);
+            stream.ReadExactly(buffer, 0, count);
+            var last = buffer.AsSpan(0, count).LastIndexOfAnyExcept((byte)0);
+            if (last >= 0)
+            {
+                var audioEnd = end - count + last + 1;
+                return dataStart + (audioEnd - dataStart + blockAlign - 1) / blockAlign * blockAlign;
+            }
+            end -= count;
+        }
+        return floor;
+    }
+
+    /// <summary>
+    /// Seals the short chunk still pending, if any, and patches the RIFF and data
+    /// sizes to cover what's written — see <see cref="PatchSizes"/>. Returns false
+    /// when some of that chunk had to be dropped at <see cref="MaxWavBytes"/>, as
+    /// <see cref="SealPending"/> does; the sizes are patched either way.
+    /// </summary>
+    private bool SealPendingAndPatchSizes()
+    {
+        var whole = _pendingLength == 0 || SealPending();
+        PatchSizes();
+        return whole;
+    }
+
+    /// <summary>
+    /// Patches the RIFF and data sizes to cover what's written and pushes them out
+    /// to the target. Leaves the position in the header. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private void PatchSizes()
+    {
+        var dataSize = _totalBytesWritten - _headerLength;
+
+        // Patch RIFF chunk size at offset 4
+        _stream!.Seek(4, SeekOrigin.Begin);
+        WriteUInt32LE(_stream, (uint)(_totalBytesWritten - 8));
+
+        // Patch data sub-chunk size, the header's last field (offset 40, or 64
+        // for the extensible header, plus 610 after a bext chunk)
+        _stream.Seek(_headerLength - 4, SeekOrigin.Begin);
+        WriteUInt32LE(_stream, (uint)dataSize);
+
+        _stream.Flush();
+    }
+
+    /// <summary>
+    /// Seals <paramref name="plaintext"/> as one frame: [4-byte LE length][sealed box].
+    /// Must hold <see cref="_lock"/>.
+    /// </summary>
+    /// <exception cref="CaptureException">EncryptionFailed when the encryptor throws; nothing is written.</exception>
+    private void SealFrame(ReadOnlySpan<byte> plaintext)
+    {
+        byte[] encrypted;
+        try
+        {
+            encrypted = _sequenceBound
+                ? _encryptor!.Encrypt(plaintext.ToArray(), EncryptedWavReader.SequenceAad(_chunkCount))
+                : _encryptor!.Encrypt(plaintext.ToArray());
+        }
+        catch (Exception ex) when (ex is not CaptureException)
+        {
+            throw CaptureException.EncryptionFailed($"Encrypting a chunk failed: {ex.Message}");
+        }
+        EnsureFits(4L + encrypted.Length);
+        var lengthBytes = BitConverter.GetBytes((uint)encrypted.Length);
+        if (!BitConverter.IsLittleEndian)
+            Array.Reverse(lengthBytes);
+
+        _stream!.Write(lengthBytes);
+        _stream.Write(encrypted);
+        _payloadHash?.AppendData(lengthBytes);
+        _payloadHash?.AppendData(encrypted);
+        _totalBytesWritten += 4 + encrypted.Length;
+        if (_sequenceBound)
+        {
+            _rollingHash = EncryptedWavReader.ChainHash(_rollingHash, [.. lengthBytes, .. encrypted]);
+            _chunkCount++;
+        }
+    }
+
+    /// <summary>
+    /// Seals the pending plaintext as one frame and empties it. Near
+    /// <see cref="MaxWavBytes"/>, seals only the whole sample frames that still fit and
+    /// drops the rest, so the file can still be finished; returns false then. Must
+    /// hold <see cref="_lock"/>.
+    /// </summary>
+    private bool SealPending()
+    {
+        var blockAlign = Math.Max(1, _spec!.Channels * _spec.BitDepth / 8);
+        var room = MaxWavBytes - _totalBytesWritten - 4 - _encryptor!.Overhead;
+        var length = (int)Math.Clamp(room, 0, _pendingLength) / blockAlign * blockAlign;
+        try
+        {
+            if (length > 0)
+                SealFrame(_pending.AsSpan(0, length));
+        }
+        catch (CaptureException ex) when (ex.ErrorKind == CaptureErrorKind.StorageError)
+        {
+            // The encryptor added more than its Overhead said; the frame didn't fit.
+            length = 0;
+        }
+        var whole = length == _pendingLength;
+        _pendingLength = 0;
+        return whole;
+    }
+
+    /// <summary>
+    /// Refuses a write of <paramref name="length"/> bytes that would take the file past
+    /// <see cref="MaxWavBytes"/>, before any of it is written, so what's there stays a
+    /// valid WAV that <see cref="Close"/> can still finish. Must hold <see cref="_lock"/>.
+    /// </summary>
+    /// <exception cref="CaptureException">StorageError when the write doesn't fit.</exception>
+    private void EnsureFits(long length)
+    {
+        if (_totalBytesWritten + length > MaxWavBytes)
+            throw SizeLimitError(length);
+    }
+
+    private CaptureException SizeLimitError(long length) => CaptureException.StorageError(
+        $"Writing {length} more bytes would take {TargetName} past the 4 GB WAV size limit");
+
+    /// <summary>
+    /// Sets up fixed-size sealing from <see cref="CaptureConfiguration.SealedChunkBytes"/>,
+    /// and sequence binding from <see cref="CaptureConfiguration.SequenceBoundChunks"/>.
+    /// Must hold <see cref="_lock"/>.
+    /// </summary>
+    private void ConfigureSealing(CaptureConfiguration config)
+    {
+        _pendingLength = 0;
+        _sealChunkBytes = 0;
+        _sequenceBound = _encryptor != null && config.SequenceBoundChunks;
+        _chunkCount = 0;
+        _rollingHash = new byte[32];
+        _manifest = null;
+        if (_encryptor == null || config.SealedChunkBytes is not { } chunkBytes)
+            return;
+
+        _sealChunkBytes = chunkBytes;
+        _pending = new byte[_sealChunkBytes];
+    }
+
+    /// <summary>
+    /// What <see cref="CaptureConfiguration.PreallocateFile"/> extends a new file to:
+    /// the estimated size at <see cref="CaptureConfiguration.MaxDuration"/>, no more
+    /// than <see cref="CaptureConfiguration.MaxFileBytes"/>. Null when not preallocating.
+    /// </summary>
+    internal static long? PreallocationLength(CaptureConfiguration config)
+    {
+        if (!config.PreallocateFile) return null;
+        long? estimate = config.MaxDuration is { } duration
+            ? HeaderLength(config) + (long)Math.Ceiling(config.EstimatedBytesPerSecond() * duration.TotalSeconds)
+            : null;
+        return (estimate, config.MaxFileBytes) switch
+        {
+            ({ } e, { } cap) => Math.Min(e, cap),
+            (var e, var cap) => e ?? cap,
+        };
+    }
+
+    /// <summary>Bytes ahead of the audio in a file written for <paramref name="config"/>.</summary>
+    internal static int HeaderLength(CaptureConfiguration config) =>
+        GenerateFormatHeader(config).Length + (config.Bwf != null ? 8 + BextBodySize : 0);
+
+    /// <summary>The RIFF, fmt and data chunk headers for <paramref name="config"/>'s format.</summary>
+    private static byte[] GenerateFormatHeader(CaptureConfiguration config) =>
+        config.Channels > 2
+            ? GenerateExtensibleWavHeader(
+                sampleRate: (uint)config.OutputSampleRate,
+                bitDepth: (ushort)config.BitDepth,
+                channels: (ushort)config.Channels,
+                channelMask: MixMatrix.ForLayout(config.Channels, config.MixingStrategy).ChannelMask,
+                dataSize: 0,
+                isFloat: config.FloatOutput)
+            : GenerateWavHeader(
+                sampleRate: (uint)config.OutputSampleRate,
+                bitDepth: (ushort)config.BitDepth,
+                channels: (ushort)config.Channels,
+                dataSize: 0,
+                isFloat: config.FloatOutput);
+
+    /// <summary>
+    /// Splices <paramref name="chunk"/> in ahead of the header's closing data chunk
+    /// header and grows the RIFF size to match.
+    /// </summary>
+    private static byte[] InsertBeforeData(byte[] header, byte[] chunk)
+    {
+        byte[] combined = [.. header.AsSpan(0, header.Length - 8), .. chunk, .. header.AsSpan(header.Length - 8)];
+        BitConverter.TryWriteBytes(combined.AsSpan(4), (uint)(combined.Length - 8));
+        return combined;
+    }
+
+    /// <summary>
+    /// Reads the bext chunk a resumed file carries at <paramref name="offset"/>, header
+    /// included. Must hold <see cref="_lock"/>.
+    /// </summary>
+    private byte[] ReadExistingBext(Stream stream, long offset)
+    {
+        var chunk = new byte[8 + BextBodySize];
+        stream.Seek(offset, SeekOrigin.Begin);
+        if (stream.Read(chunk, 0, chunk.Length) != chunk.Length
+            || !chunk.AsSpan(0, 4).SequenceEqual("bext"u8)
+            || BitConverter.ToUInt32(chunk, 4) != BextBodySize)
+            throw CaptureException.StorageError($"Cannot resume {TargetName}: it has no bext chunk to continue");
+        return chunk;
+    }
+
+    /// <summary>Writes <paramref name="text"/> as ASCII, cut or NUL-padded to <paramref name="width"/> bytes.</summary>
+    private static void WriteAscii(BinaryWriter w, string text, int width)
+    {
+        var field = new byte[width];
+        Encoding.ASCII.GetBytes(text.AsSpan(0, Math.Min(text.Length, width)), field);
+        w.Write(field);
+    }
+
+    /// <summary>Decodes the fmt fields of a header this writer generated.</summary>
+    private static WavSpec ReadSpec(byte[] header)
+    {
+        // WAVE_FORMAT_EXTENSIBLE carries the real format in its SubFormat GUID, whose
+        // first two bytes are the plain format tag.
+        var formatTag = BitConverter.ToUInt16(header, 20);
+        if (formatTag == 0xFFFE)
+            formatTag = BitConverter.ToUInt16(header, 44);
+
+        return new WavSpec(
+            SampleRate: (int)BitConverter.ToUInt32(header, 24),
+            Channels: BitConverter.ToUInt16(header, 22),
+            BitDepth: BitConverter.ToUInt16(header, 34),
+            IsFloat: formatTag == 3);
+    }
+
     private static void WriteUInt32LE(Stream stream, uint value)
     {
         Span<byte> buf = stackalloc byte[4];
@@ -179,11 +910,4 @@ public sealed class EncryptedWavWriter : IDisposable
             buf.Reverse();
         stream.Write(buf);
     }
-
-    private static string ComputeChecksum(string filePath)
-    {
-        using var stream = File.OpenRead(filePath);
-        var hash = SHA256.HashData(stream);
-        return Convert.ToHexStringLower(hash);
-    }
 }
@@ -0,0 +1,102 @@
+using System.Globalization;
+using System.Security.Cryptography;
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+
+namespace AudioCapture.Storage;
+
+/// <summary>
+/// AES-256-GCM, as <see cref="AesGcmEncryptor"/>, under a key derived from a
+/// passphrase — for apps with no key-management backend to hold keys for them.
+/// </summary>
+/// <remarks>
+/// The key is PBKDF2-HMAC-SHA256 over the passphrase and a random 16-byte salt
+/// drawn per instance, so make one per recording. The salt and iteration count
+/// aren't secret and travel in <see cref="KeyMetadata"/>, which the session writes
+/// to <see cref="RecordingMetadata.EncryptionKeyMetadata"/>; with the passphrase,
+/// <see cref="FromKeyMetadata"/> rebuilds the same key to read the file back. The
+/// recording is only as strong as the passphrase: PBKDF2 slows guessing, it can't
+/// make a short one safe. (Argon2 would resist GPUs better, but isn't in the BCL.)
+/// </remarks>
+public sealed class PassphraseEncryptor : ICaptureEncryptor, IDisposable
+{
+    /// <summary>OWASP's current recommendation for PBKDF2-HMAC-SHA256.</summary>
+    public const int DefaultIterations = 600_000;
+
+    private const string Kdf = "PBKDF2-HMAC-SHA256";
+    private const int SaltSize = 16;
+
+    private readonly AesGcmEncryptor _cipher;
+    private readonly Dictionary<string, string> _keyMetadata;
+
+    /// <summary>Derives a key from <paramref name="passphrase"/> under a fresh random salt.</summary>
+    /// <param name="iterations">PBKDF2 rounds; more slows guessing and every open alike.</param>
+    /// <param name="keyId">Identifier for key tracking, as for <see cref="AesGcmEncryptor"/>.</param>
+    public PassphraseEncryptor(string passphrase, int iterations = DefaultIterations, string keyId = "passphrase")
+        : this(passphrase, RandomNumberGenerator.GetBytes(SaltSize), iterations, keyId)
+    {
+    }
+
+    private PassphraseEncryptor(string passphrase, byte[] salt, int iterations, string keyId)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(passphrase);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(iterations);
+
+        var key = Rfc2898DeriveBytes.Pbkdf2(passphrase, salt, iterations, HashAlgorithmName.SHA256, 32);
+        try
+        {
+            _cipher = new AesGcmEncryptor(key, keyId);
+        }
+        finally
+        {
+            CryptographicOperations.ZeroMemory(key);
+        }
+        _keyMetadata = new Dictionary<string, string>(_cipher.KeyMetadata)
+        {
+            ["kdf"] = Kdf,
+            ["kdfSalt"] = Convert.ToBase64String(salt),
+            ["kdfIterations"] = iterations.ToString(CultureInfo.InvariantCulture),
+        };
+    }
+
+    /// <summary>
+    /// Rebuilds the encryptor a recording was sealed with from its
+    /// <paramref name="keyMetadata"/> and the passphrase. A wrong passphrase isn't
+    /// detectable here; it shows as every frame failing to authenticate on read.
+    /// </summary>
+    /// <exception cref="CaptureException">EncryptionFailed when the metadata isn't from a passphrase key.</exception>
+    public static PassphraseEncryptor FromKeyMetadata(string passphrase, IReadOnlyDictionary<string, string> keyMetadata)
+    {
+        ArgumentNullException.ThrowIfNull(keyMetadata);
+        if (keyMetadata.GetValueOrDefault("kdf") != Kdf
+            || !keyMetadata.TryGetValue("kdfSalt", out var saltText)
+            || !keyMetadata.TryGetValue("kdfIterations", out var iterationsText)
+            || !int.TryParse(iterationsText, NumberStyles.None, CultureInfo.InvariantCulture, out var iterations)
+            || iterations <= 0)
+            throw CaptureException.EncryptionFailed($"Key metadata doesn't describe a {Kdf} passphrase key");
+
+        byte[] salt;
+        try { salt = Convert.FromBase64String(saltText); }
+        catch (FormatException)
+        {
+            throw CaptureException.EncryptionFailed("Key metadata holds an unreadable salt");
+        }
+        return new PassphraseEncryptor(passphrase, salt, iterations, keyMetadata.GetValueOrDefault("keyId") ?? "passphrase");
+    }
+
+    public string Algorithm => _cipher.Algorithm;
+
+    public int Overhead => _cipher.Overhead;
+
+    /// <summary>The key ID and algorithm, plus <c>kdf</c>, <c>kdfSalt</c> (base64) and <c>kdfIterations</c>.</summary>
+    public Dictionary<string, string> KeyMetadata => new(_keyMetadata);
+
+    public byte[] Encrypt(byte[] data) => _cipher.Encrypt(data);
+
+    public byte[] Encrypt(byte[] data, byte[] associatedData) => _cipher.Encrypt(data, associatedData);
+
+    /// <summary>The derived AES-GCM cipher, for <see cref="EncryptedWavReader.ReadPcm"/> and anything else that decrypts.</summary>
+    public AesGcmEncryptor Cipher => _cipher;
+
+    public void Dispose() => _cipher.Dispose();
+}
@@ -0,0 +1,40 @@
+using System.Buffers.Binary;
+using AudioCapture.Interfaces;
+using AudioCapture.Models;
+
+namespace AudioCapture.Storage;
+
+/// <summary>
+/// Seals each write as one length-prefixed frame before passing it to a caller's
+/// <see cref="IOutputSink"/>, so what leaves the process is ciphertext in the same
+/// framing an <c>.enc.wav</c> data chunk uses.
+/// </summary>
+internal sealed class SealingOutputSink(IOutputSink inner, ICaptureEncryptor encryptor) : IOutputSink
+{
+    public WavSpec? Spec => inner.Spec;
+
+    public void Open(CaptureConfiguration config) => inner.Open(config);
+
+    /// <exception cref="CaptureException">EncryptionFailed when the encryptor throws; nothing is written.</exception>
+    public void Write(ReadOnlySpan<byte> data)
+    {
+        byte[] encrypted;
+        try
+        {
+            encrypted = encryptor.Encrypt(data.ToArray());
+        }
+        catch (Exception ex) when (ex is not CaptureException)
+        {
+            throw CaptureException.EncryptionFailed($"Encrypting a chunk failed: {ex.Message}");
+        }
+
+        var frame = new byte[4 + encrypted.Length];
+        BinaryPrimitives.WriteUInt32LittleEndian(frame, (uint)encrypted.Length);
+        encrypted.CopyTo(frame, 4);
+        inner.Write(frame);
+    }
+
+    public void Flush() => inner.Flush();
+
+    public string Close() => inner.Close();
+}